@@ -0,0 +1,173 @@
+use core::time::Duration;
+
+use lib_efi::{
+    efi::{Event, Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use crate::{
+    protocols::console::graphic_output::{GraphicOutput, Point},
+    services::event::{is_signaled, wait_for_event, TickCounter, Timer},
+};
+
+use super::{canvas::BltBuffer, painter::blend};
+
+///Timer periods are counted in units of 100 ns.
+const TICKS_PER_SECOND: u64 = 10_000_000;
+
+///Why an animation stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    ///The frame callback returned false.
+    Finished,
+    ///The stop event was signaled, usually by a key press. The key is left to be read.
+    Event,
+}
+
+///Calls a frame callback at a target frame rate, paced by a periodic timer event.
+///
+///Frames are not queued: when a frame takes longer than the period, the next one starts at once.
+///The delta time passed to frames is measured by counting timer ticks, so it is a whole number of
+///periods which adds up to the real elapsed time.
+pub struct Animation {
+    st: *mut SystemTable,
+    timer: Timer,
+    ticks: TickCounter,
+    period: u64,
+    stop: Option<Event>,
+}
+impl Animation {
+    ///Fails with INVALID_PARAMETER if frames_per_second is 0.
+    pub fn new(st: *mut SystemTable, frames_per_second: u32) -> Result<Animation, Status> {
+        if frames_per_second == 0 {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        Ok(Animation {
+            st,
+            timer: Timer::new(st)?,
+            ticks: TickCounter::new(st)?,
+            period: (TICKS_PER_SECOND / frames_per_second as u64).max(1),
+            stop: None,
+        })
+    }
+
+    ///Stops animations when event is signaled, usually TextInput::wait_for_key().
+    pub fn stop_on(&mut self, event: Option<Event>) {
+        self.stop = event;
+    }
+
+    ///Time between two frames.
+    pub fn frame_time(&self) -> Duration {
+        Duration::from_nanos(self.period * 100)
+    }
+
+    ///Calls frame with the time elapsed since the previous frame, zero for the first one, until it
+    ///returns false or the stop event is signaled.
+    pub fn run(&mut self, mut frame: impl FnMut(Duration) -> Result<bool, Status>) -> Result<Stop, Status> {
+        // Counting starts first, so a tick of the timer is never seen before the counted one.
+        let status = self.ticks.set_periodic(self.period);
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        let status = self.timer.set_periodic(self.period);
+        if status != Status::SUCCESS {
+            self.ticks.cancel();
+            return Err(status);
+        }
+        // Ticks left from a previous run.
+        self.ticks.take();
+        let result = self.frames(&mut frame);
+        self.timer.cancel();
+        self.ticks.cancel();
+        result
+    }
+
+    fn frames(&mut self, frame: &mut impl FnMut(Duration) -> Result<bool, Status>) -> Result<Stop, Status> {
+        let mut delta = Duration::ZERO;
+        loop {
+            if !frame(delta)? {
+                return Ok(Stop::Finished);
+            }
+            // A tick during the frame means it overran: go on at once.
+            if !self.timer.check()? {
+                let signaled = match self.stop {
+                    Some(stop) => wait_for_event(self.st, &[self.timer.event(), stop])?,
+                    None => wait_for_event(self.st, &[self.timer.event()])?,
+                };
+                if signaled == 1 {
+                    return Ok(Stop::Event);
+                }
+            }
+            delta = Duration::from_nanos(self.ticks.take() * self.period * 100);
+            if let Some(stop) = self.stop {
+                if is_signaled(self.st, stop)? {
+                    return Ok(Stop::Event);
+                }
+            }
+        }
+    }
+
+    ///Runs draw on buffer each frame and copies buffer to the screen at destination after it.
+    pub fn present<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(
+        &mut self,
+        gop: &GraphicOutput,
+        buffer: &mut BltBuffer<S>,
+        destination: Point,
+        mut draw: impl FnMut(&mut BltBuffer<S>, Duration) -> Result<bool, Status>,
+    ) -> Result<Stop, Status> {
+        self.run(|delta| {
+            let running = draw(buffer, delta)?;
+            match buffer.draw(gop, destination) {
+                Status::SUCCESS => Ok(running),
+                status => Err(status),
+            }
+        })
+    }
+
+    ///Fades the screen at destination from one picture to another over duration. The pictures
+    ///and target must have the same size.
+    pub fn crossfade<A, B, S>(
+        &mut self,
+        gop: &GraphicOutput,
+        from: &BltBuffer<A>,
+        to: &BltBuffer<B>,
+        target: &mut BltBuffer<S>,
+        destination: Point,
+        duration: Duration,
+    ) -> Result<Stop, Status>
+    where
+        A: AsRef<[BltPixel]> + AsMut<[BltPixel]>,
+        B: AsRef<[BltPixel]> + AsMut<[BltPixel]>,
+        S: AsRef<[BltPixel]> + AsMut<[BltPixel]>,
+    {
+        let sizes = [(from.width(), from.height()), (to.width(), to.height())];
+        if sizes.iter().any(|&size| size != (target.width(), target.height())) {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        let total = duration.as_micros().max(1);
+        let mut elapsed = 0;
+        self.present(gop, target, destination, |target, delta| {
+            elapsed = (elapsed + delta.as_micros()).min(total);
+            mix(target, from, to, elapsed as usize, total as usize);
+            Ok(elapsed < total)
+        })
+    }
+}
+
+///Sets each pixel of target step count of the way from the pixel of from to the one of to.
+pub fn mix<A, B, S>(target: &mut BltBuffer<S>, from: &BltBuffer<A>, to: &BltBuffer<B>, step: usize, count: usize)
+where
+    A: AsRef<[BltPixel]> + AsMut<[BltPixel]>,
+    B: AsRef<[BltPixel]> + AsMut<[BltPixel]>,
+    S: AsRef<[BltPixel]> + AsMut<[BltPixel]>,
+{
+    let count = count.max(1);
+    let step = step.min(count);
+    let pixels = target
+        .pixels_mut()
+        .iter_mut()
+        .zip(from.pixels().iter().zip(to.pixels()));
+    for (pixel, (&start, &end)) in pixels {
+        *pixel = blend(start, end, step, count);
+    }
+}
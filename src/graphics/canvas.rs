@@ -0,0 +1,127 @@
+use lib_efi::{efi::Status, protocols::graphics_output::BltPixel};
+
+use crate::protocols::console::graphic_output::{GraphicOutput, Point, Rect};
+
+///A surface made of BltPixel: the screen or an off-screen buffer.
+pub trait Canvas {
+    fn bounds(&self) -> Rect;
+
+    ///Fills a rectangle, which must lie inside bounds().
+    fn fill_rect(&mut self, rect: Rect, color: BltPixel) -> Status;
+
+    ///Copies the source rectangle of a buffer of stride pixels per line to destination.
+    fn copy_from(&mut self, buffer: &[BltPixel], source: Rect, destination: Point, stride: usize) -> Status;
+
+    fn set_pixel(&mut self, point: Point, color: BltPixel) -> Status {
+        self.fill_rect(Rect::new(point.x, point.y, 1, 1), color)
+    }
+}
+
+impl Canvas for GraphicOutput {
+    fn bounds(&self) -> Rect {
+        self.screen()
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: BltPixel) -> Status {
+        self.fill(rect, color)
+    }
+
+    fn copy_from(&mut self, buffer: &[BltPixel], source: Rect, destination: Point, stride: usize) -> Status {
+        self.copy_to_screen(buffer, source, destination, stride)
+    }
+}
+
+///Off-screen pixels, width * height of them line by line, in any storage: an array, a slice or
+///a PoolBuffer.
+pub struct BltBuffer<S> {
+    pixels: S,
+    width: usize,
+    height: usize,
+}
+impl<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>> BltBuffer<S> {
+    ///Fails if pixels holds fewer than width * height pixels.
+    pub fn new(pixels: S, width: usize, height: usize) -> Result<BltBuffer<S>, Status> {
+        match width.checked_mul(height) {
+            Some(len) if len <= pixels.as_ref().len() => Ok(BltBuffer { pixels, width, height }),
+            _ => Err(Status::BAD_BUFFER_SIZE),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[BltPixel] {
+        &self.pixels.as_ref()[..self.width * self.height]
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [BltPixel] {
+        &mut self.pixels.as_mut()[..self.width * self.height]
+    }
+
+    pub fn into_inner(self) -> S {
+        self.pixels
+    }
+
+    pub fn get_pixel(&self, point: Point) -> Option<BltPixel> {
+        if !self.bounds().contains(point) {
+            return None;
+        }
+        Some(self.pixels()[point.y * self.width + point.x])
+    }
+
+    ///Copies the whole buffer to the screen at destination.
+    pub fn draw(&self, gop: &GraphicOutput, destination: Point) -> Status {
+        gop.copy_to_screen(self.pixels(), self.bounds(), destination, self.width)
+    }
+
+    ///Copies the source rectangle of the buffer to the screen at destination.
+    pub fn draw_rect(&self, gop: &GraphicOutput, source: Rect, destination: Point) -> Status {
+        gop.copy_to_screen(self.pixels(), source, destination, self.width)
+    }
+
+    ///Fills the buffer with the screen content at origin.
+    pub fn capture(&mut self, gop: &GraphicOutput, origin: Point) -> Status {
+        let rect = Rect::new(origin.x, origin.y, self.width, self.height);
+        gop.copy_from_screen(self.pixels_mut(), rect)
+    }
+}
+
+impl<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>> Canvas for BltBuffer<S> {
+    fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: BltPixel) -> Status {
+        if !self.bounds().contains_rect(&rect) {
+            return Status::INVALID_PARAMETER;
+        }
+        let width = self.width;
+        let pixels = self.pixels_mut();
+        for y in rect.y..rect.bottom() {
+            pixels[y * width + rect.x..y * width + rect.right()].fill(color);
+        }
+        Status::SUCCESS
+    }
+
+    fn copy_from(&mut self, buffer: &[BltPixel], source: Rect, destination: Point, stride: usize) -> Status {
+        let target = Rect::new(destination.x, destination.y, source.width, source.height);
+        let fits = source.is_empty()
+            || (source.right() <= stride && (source.bottom() - 1) * stride + source.right() <= buffer.len());
+        if !fits || !self.bounds().contains_rect(&target) {
+            return Status::INVALID_PARAMETER;
+        }
+        let width = self.width;
+        let pixels = self.pixels_mut();
+        for row in 0..source.height {
+            let from = (source.y + row) * stride + source.x;
+            let to = (destination.y + row) * width + destination.x;
+            pixels[to..to + source.width].copy_from_slice(&buffer[from..from + source.width]);
+        }
+        Status::SUCCESS
+    }
+}
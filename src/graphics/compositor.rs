@@ -0,0 +1,533 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use crate::protocols::console::graphic_output::{GraphicOutput, Point, Rect};
+
+use super::{
+    canvas::{BltBuffer, Canvas},
+    image::{self, Image},
+};
+
+pub const MAX_LAYERS: usize = 16;
+///Dirty rectangles tracked separately. More are merged together.
+const MAX_DIRTY: usize = 32;
+
+///How the pixels of a layer are combined with what lies below.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blend {
+    ///Pixels replace what is below (weighted by the layer opacity).
+    Opaque,
+    ///Pixels are weighted by their alpha channel, BltPixel::reserved, as decoded images have it.
+    Alpha,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayerId(usize);
+
+///Part of the screen covered by a w * h area at (x, y), which may be partly off screen.
+fn on_screen(screen: Rect, x: isize, y: isize, width: usize, height: usize) -> Option<Rect> {
+    let left = x.max(0);
+    let top = y.max(0);
+    let right = (x + width as isize).min(screen.right() as isize);
+    let bottom = (y + height as isize).min(screen.bottom() as isize);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some(Rect::new(
+        left as usize,
+        top as usize,
+        (right - left) as usize,
+        (bottom - top) as usize,
+    ))
+}
+
+fn mix(over: u8, under: u8, alpha: u32) -> u8 {
+    ((over as u32 * alpha + under as u32 * (255 - alpha) + 127) / 255) as u8
+}
+
+///Combines a line of source pixels with the back buffer.
+fn blend_row(destination: &mut [BltPixel], source: &[BltPixel], blend: Blend, opacity: u8) {
+    if blend == Blend::Opaque && opacity == 255 {
+        destination.copy_from_slice(source);
+        return;
+    }
+    for (under, over) in destination.iter_mut().zip(source) {
+        let alpha = match blend {
+            Blend::Opaque => opacity as u32,
+            Blend::Alpha => over.reserved as u32 * opacity as u32 / 255,
+        };
+        match alpha {
+            0 => {}
+            255 => *under = *over,
+            _ => {
+                *under = BltPixel {
+                    blue: mix(over.blue, under.blue, alpha),
+                    green: mix(over.green, under.green, alpha),
+                    red: mix(over.red, under.red, alpha),
+                    reserved: 0,
+                }
+            }
+        }
+    }
+}
+
+///An off-screen surface composited on the screen. Drawing on it marks the area drawn as dirty.
+pub struct Layer {
+    buffer: Image,
+    x: isize,
+    y: isize,
+    z: i32,
+    blend: Blend,
+    opacity: u8,
+    visible: bool,
+    ///Area changed since the last flush, in layer coordinates.
+    dirty: Option<Rect>,
+}
+impl Layer {
+    pub fn width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    pub fn position(&self) -> (isize, isize) {
+        (self.x, self.y)
+    }
+
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn buffer(&self) -> &Image {
+        &self.buffer
+    }
+
+    ///Gives direct access to the pixels: call invalidate() for the area changed.
+    pub fn buffer_mut(&mut self) -> &mut Image {
+        &mut self.buffer
+    }
+
+    ///Marks an area of the layer as changed.
+    pub fn invalidate(&mut self, rect: Rect) {
+        if let Some(rect) = self.bounds().intersection(&rect) {
+            self.dirty = Some(self.dirty.map_or(rect, |dirty| dirty.union(&rect)));
+        }
+    }
+
+    pub fn invalidate_all(&mut self) {
+        self.invalidate(self.bounds());
+    }
+
+    fn screen_rect(&self, screen: Rect) -> Option<Rect> {
+        on_screen(screen, self.x, self.y, self.width(), self.height())
+    }
+}
+
+impl Canvas for Layer {
+    fn bounds(&self) -> Rect {
+        self.buffer.bounds()
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: BltPixel) -> Status {
+        let status = self.buffer.fill_rect(rect, color);
+        if status == Status::SUCCESS {
+            self.invalidate(rect);
+        }
+        status
+    }
+
+    fn copy_from(&mut self, buffer: &[BltPixel], source: Rect, destination: Point, stride: usize) -> Status {
+        let status = self.buffer.copy_from(buffer, source, destination, stride);
+        if status == Status::SUCCESS {
+            self.invalidate(Rect::from_points(destination, source.size()));
+        }
+        status
+    }
+}
+
+struct Cursor {
+    image: Image,
+    hotspot: Point,
+    x: isize,
+    y: isize,
+    visible: bool,
+}
+impl Cursor {
+    fn screen_rect(&self, screen: Rect) -> Option<Rect> {
+        if !self.visible {
+            return None;
+        }
+        let (x, y) = (self.x - self.hotspot.x as isize, self.y - self.hotspot.y as isize);
+        on_screen(screen, x, y, self.image.width(), self.image.height())
+    }
+}
+
+///Screen areas to redraw on the next flush.
+struct DirtyRects {
+    rects: [Rect; MAX_DIRTY],
+    len: usize,
+}
+impl DirtyRects {
+    fn area(rect: &Rect) -> usize {
+        rect.width * rect.height
+    }
+
+    fn add(&mut self, rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        // Merge with a rectangle when that costs no more than drawing both.
+        for i in 0..self.len {
+            let union = self.rects[i].union(&rect);
+            if Self::area(&union) <= Self::area(&self.rects[i]) + Self::area(&rect) {
+                self.remove(i);
+                return self.add(union);
+            }
+        }
+        if self.len == MAX_DIRTY {
+            // Full: merge with the rectangle which grows the least.
+            let growth = |other: &Rect| Self::area(&other.union(&rect)) - Self::area(other);
+            let (best, _) = self.rects[..self.len]
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, other)| growth(other))
+                .unwrap_or((0, &rect));
+            let union = self.rects[best].union(&rect);
+            self.remove(best);
+            return self.add(union);
+        }
+        self.rects[self.len] = rect;
+        self.len += 1;
+    }
+
+    fn remove(&mut self, index: usize) {
+        self.rects.copy_within(index + 1..self.len, index);
+        self.len -= 1;
+    }
+}
+
+///Composes layers and a cursor sprite in a back buffer, and copies only the areas which changed
+///to the screen, so the screen never shows a half drawn frame.
+pub struct Compositor<'a> {
+    gop: &'a GraphicOutput,
+    screen: Rect,
+    back: Image,
+    background: BltPixel,
+    layers: [Option<Layer>; MAX_LAYERS],
+    cursor: Option<Cursor>,
+    dirty: DirtyRects,
+}
+impl<'a> Compositor<'a> {
+    ///Creates a compositor for the current mode; create it again after set_mode(). The first
+    ///flush() draws the whole screen.
+    pub fn new(st: *mut SystemTable, gop: &'a GraphicOutput, background: BltPixel) -> Result<Compositor<'a>, Status> {
+        let screen = gop.screen();
+        let mut compositor = Compositor {
+            gop,
+            screen,
+            back: image::allocate(st, screen.width, screen.height)?,
+            background,
+            layers: core::array::from_fn(|_| None),
+            cursor: None,
+            dirty: DirtyRects {
+                rects: [Rect::default(); MAX_DIRTY],
+                len: 0,
+            },
+        };
+        compositor.invalidate(screen);
+        Ok(compositor)
+    }
+
+    pub fn screen(&self) -> Rect {
+        self.screen
+    }
+
+    pub fn set_background(&mut self, background: BltPixel) {
+        self.background = background;
+        self.invalidate(self.screen);
+    }
+
+    ///Marks an area of the screen to be redrawn.
+    pub fn invalidate(&mut self, rect: Rect) {
+        if let Some(rect) = self.screen.intersection(&rect) {
+            self.dirty.add(rect);
+        }
+    }
+
+    fn invalidate_layer(&mut self, id: LayerId) {
+        let screen = self.screen;
+        let rect = match self.layer(id) {
+            Some(layer) if layer.visible => layer.screen_rect(screen),
+            _ => None,
+        };
+        if let Some(rect) = rect {
+            self.dirty.add(rect);
+        }
+    }
+
+    ///Adds a width * height layer with its top left corner at position, filled with zeros: with
+    ///Blend::Alpha it is transparent until drawn on, with Blend::Opaque it covers what is below
+    ///with black. Layers with a higher z are drawn above.
+    pub fn add_layer(
+        &mut self,
+        st: *mut SystemTable,
+        position: (isize, isize),
+        width: usize,
+        height: usize,
+        z: i32,
+        blend: Blend,
+    ) -> Result<LayerId, Status> {
+        self.add_image_layer(image::allocate(st, width, height)?, position, z, blend)
+    }
+
+    ///Adds a layer showing an image, such as one returned by image::decode().
+    pub fn add_image_layer(
+        &mut self,
+        image: Image,
+        position: (isize, isize),
+        z: i32,
+        blend: Blend,
+    ) -> Result<LayerId, Status> {
+        let (x, y) = position;
+        let index = self
+            .layers
+            .iter()
+            .position(|layer| layer.is_none())
+            .ok_or(Status::OUT_OF_RESOURCES)?;
+        self.layers[index] = Some(Layer {
+            buffer: image,
+            x,
+            y,
+            z,
+            blend,
+            opacity: 255,
+            visible: true,
+            dirty: None,
+        });
+        self.invalidate_layer(LayerId(index));
+        Ok(LayerId(index))
+    }
+
+    ///Removes a layer and returns its content.
+    pub fn remove_layer(&mut self, id: LayerId) -> Option<Image> {
+        self.invalidate_layer(id);
+        self.layers.get_mut(id.0)?.take().map(|layer| layer.buffer)
+    }
+
+    pub fn layer(&self, id: LayerId) -> Option<&Layer> {
+        self.layers.get(id.0)?.as_ref()
+    }
+
+    ///The layer to draw on, for example with a Painter.
+    pub fn layer_mut(&mut self, id: LayerId) -> Option<&mut Layer> {
+        self.layers.get_mut(id.0)?.as_mut()
+    }
+
+    ///Changes a property of a layer, redrawing the area it covers before and after.
+    fn update_layer(&mut self, id: LayerId, update: impl FnOnce(&mut Layer)) -> Status {
+        self.invalidate_layer(id);
+        match self.layer_mut(id) {
+            Some(layer) => update(layer),
+            None => return Status::INVALID_PARAMETER,
+        }
+        self.invalidate_layer(id);
+        Status::SUCCESS
+    }
+
+    pub fn move_layer(&mut self, id: LayerId, x: isize, y: isize) -> Status {
+        self.update_layer(id, |layer| {
+            layer.x = x;
+            layer.y = y;
+        })
+    }
+
+    pub fn set_z(&mut self, id: LayerId, z: i32) -> Status {
+        self.update_layer(id, |layer| layer.z = z)
+    }
+
+    pub fn set_visible(&mut self, id: LayerId, visible: bool) -> Status {
+        self.update_layer(id, |layer| layer.visible = visible)
+    }
+
+    ///Opacity of the whole layer, 255 being opaque.
+    pub fn set_opacity(&mut self, id: LayerId, opacity: u8) -> Status {
+        self.update_layer(id, |layer| layer.opacity = opacity)
+    }
+
+    ///Sets the cursor sprite, drawn above every layer with its alpha channel. hotspot is the pixel
+    ///of the image placed at the pointer position.
+    pub fn set_cursor<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(
+        &mut self,
+        st: *mut SystemTable,
+        sprite: &BltBuffer<S>,
+        hotspot: Point,
+    ) -> Status {
+        let mut image = match image::allocate(st, sprite.width(), sprite.height()) {
+            Ok(image) => image,
+            Err(status) => return status,
+        };
+        image.pixels_mut().copy_from_slice(sprite.pixels());
+        self.invalidate_cursor();
+        let (x, y) = self.cursor.as_ref().map_or((0, 0), |cursor| (cursor.x, cursor.y));
+        self.cursor = Some(Cursor {
+            image,
+            hotspot,
+            x,
+            y,
+            visible: true,
+        });
+        self.invalidate_cursor();
+        Status::SUCCESS
+    }
+
+    fn invalidate_cursor(&mut self) {
+        if let Some(rect) = self.cursor.as_ref().and_then(|cursor| cursor.screen_rect(self.screen)) {
+            self.dirty.add(rect);
+        }
+    }
+
+    ///Moves the cursor: only the areas it leaves and covers are redrawn.
+    pub fn move_cursor(&mut self, x: isize, y: isize) {
+        self.invalidate_cursor();
+        if let Some(cursor) = self.cursor.as_mut() {
+            cursor.x = x;
+            cursor.y = y;
+        }
+        self.invalidate_cursor();
+    }
+
+    pub fn show_cursor(&mut self, visible: bool) {
+        self.invalidate_cursor();
+        if let Some(cursor) = self.cursor.as_mut() {
+            cursor.visible = visible;
+        }
+        self.invalidate_cursor();
+    }
+
+    pub fn cursor_position(&self) -> Option<(isize, isize)> {
+        self.cursor.as_ref().map(|cursor| (cursor.x, cursor.y))
+    }
+
+    ///Draws one source over the back buffer in rect.
+    fn compose_source(
+        back: &mut Image,
+        rect: Rect,
+        pixels: &[BltPixel],
+        origin: (isize, isize),
+        stride: usize,
+        blend: Blend,
+        opacity: u8,
+    ) {
+        let width = back.width();
+        let back = back.pixels_mut();
+        let left = (rect.x as isize - origin.0) as usize;
+        for y in rect.y..rect.bottom() {
+            let top = (y as isize - origin.1) as usize;
+            let source = &pixels[top * stride + left..top * stride + left + rect.width];
+            blend_row(
+                &mut back[y * width + rect.x..y * width + rect.right()],
+                source,
+                blend,
+                opacity,
+            );
+        }
+    }
+
+    ///Redraws rect of the back buffer from the layers and the cursor.
+    fn compose(&mut self, rect: Rect, order: &[usize]) {
+        let _ = self.back.fill_rect(rect, self.background);
+        for &index in order {
+            let layer = match &self.layers[index] {
+                Some(layer) if layer.visible => layer,
+                _ => continue,
+            };
+            if let Some(part) = layer.screen_rect(self.screen).and_then(|area| area.intersection(&rect)) {
+                let stride = layer.width();
+                Self::compose_source(
+                    &mut self.back,
+                    part,
+                    layer.buffer.pixels(),
+                    (layer.x, layer.y),
+                    stride,
+                    layer.blend,
+                    layer.opacity,
+                );
+            }
+        }
+        if let Some(cursor) = &self.cursor {
+            if let Some(part) = cursor
+                .screen_rect(self.screen)
+                .and_then(|area| area.intersection(&rect))
+            {
+                let origin = (
+                    cursor.x - cursor.hotspot.x as isize,
+                    cursor.y - cursor.hotspot.y as isize,
+                );
+                Self::compose_source(
+                    &mut self.back,
+                    part,
+                    cursor.image.pixels(),
+                    origin,
+                    cursor.image.width(),
+                    Blend::Alpha,
+                    255,
+                );
+            }
+        }
+    }
+
+    ///Redraws the areas which changed and copies them to the screen.
+    pub fn flush(&mut self) -> Status {
+        let screen = self.screen;
+        for index in 0..MAX_LAYERS {
+            let rect = match self.layers[index].as_mut() {
+                Some(layer) => match layer.dirty.take() {
+                    Some(dirty) if layer.visible => on_screen(
+                        screen,
+                        layer.x + dirty.x as isize,
+                        layer.y + dirty.y as isize,
+                        dirty.width,
+                        dirty.height,
+                    ),
+                    _ => None,
+                },
+                None => None,
+            };
+            if let Some(rect) = rect {
+                self.dirty.add(rect);
+            }
+        }
+        // Layer indices by increasing z, ties in order of creation.
+        let mut order = [0usize; MAX_LAYERS];
+        let mut count = 0;
+        for (index, layer) in self.layers.iter().enumerate() {
+            if layer.is_some() {
+                order[count] = index;
+                count += 1;
+            }
+        }
+        let layers = &self.layers;
+        order[..count].sort_unstable_by_key(|&index| (layers[index].as_ref().map_or(0, |layer| layer.z), index));
+        let dirty = self.dirty.len;
+        for i in 0..dirty {
+            let rect = self.dirty.rects[i];
+            self.compose(rect, &order[..count]);
+            let status = self
+                .gop
+                .copy_to_screen(self.back.pixels(), rect, rect.origin(), screen.width);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        self.dirty.len = 0;
+        Status::SUCCESS
+    }
+}
@@ -0,0 +1,180 @@
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{Rgb888, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+use lib_efi::{efi::Status, protocols::graphics_output::BltPixel};
+
+use crate::protocols::console::graphic_output::{Color, GraphicOutput, Point, Rect};
+
+use super::canvas::{BltBuffer, Canvas};
+
+///Pixels converted at once by fill_contiguous(), one Blt() call each.
+const CHUNK: usize = 256;
+
+impl From<Rgb888> for Color {
+    fn from(color: Rgb888) -> Color {
+        Color::rgb(color.r(), color.g(), color.b())
+    }
+}
+
+impl From<Color> for Rgb888 {
+    fn from(color: Color) -> Rgb888 {
+        Rgb888::new(color.red, color.green, color.blue)
+    }
+}
+
+fn to_blt(color: Rgb888) -> BltPixel {
+    Color::from(color).to_blt()
+}
+
+fn size<C: Canvas>(canvas: &C) -> Size {
+    let bounds = canvas.bounds();
+    Size::new(bounds.width as u32, bounds.height as u32)
+}
+
+///Part of area inside the canvas, which always starts at (0, 0).
+fn visible<C: Canvas>(canvas: &C, area: &Rectangle) -> Option<Rect> {
+    let area = area.intersection(&Rectangle::new(Default::default(), size(canvas)));
+    if area.size.width == 0 || area.size.height == 0 {
+        return None;
+    }
+    Some(Rect::new(
+        area.top_left.x as usize,
+        area.top_left.y as usize,
+        area.size.width as usize,
+        area.size.height as usize,
+    ))
+}
+
+fn result(status: Status) -> Result<(), Status> {
+    match status {
+        Status::SUCCESS => Ok(()),
+        status => Err(status),
+    }
+}
+
+fn draw_iter<C: Canvas, I: IntoIterator<Item = Pixel<Rgb888>>>(canvas: &mut C, pixels: I) -> Result<(), Status> {
+    let bounds = canvas.bounds();
+    for Pixel(point, color) in pixels {
+        if point.x < 0 || point.y < 0 {
+            continue;
+        }
+        let point = Point {
+            x: point.x as usize,
+            y: point.y as usize,
+        };
+        if bounds.contains(point) {
+            result(canvas.set_pixel(point, to_blt(color)))?;
+        }
+    }
+    Ok(())
+}
+
+///Converts colors CHUNK at a time and copies the visible part of each chunk with one Blt().
+fn fill_contiguous<C: Canvas, I: IntoIterator<Item = Rgb888>>(
+    canvas: &mut C,
+    area: &Rectangle,
+    colors: I,
+) -> Result<(), Status> {
+    let visible = match visible(canvas, area) {
+        Some(visible) => visible,
+        None => return Ok(()),
+    };
+    let mut colors = colors.into_iter();
+    let mut buffer = [Color::BLACK.to_blt(); CHUNK];
+    let (left, top) = (area.top_left.x as isize, area.top_left.y as isize);
+    for row in 0..area.size.height as isize {
+        let y = top + row;
+        let mut column = 0;
+        while column < area.size.width as isize {
+            let count = (area.size.width as isize - column).min(CHUNK as isize);
+            let mut filled = 0;
+            for (pixel, color) in buffer[..count as usize].iter_mut().zip(&mut colors) {
+                *pixel = to_blt(color);
+                filled += 1;
+            }
+            // The chunk covers x in start..end of line y; copy what is visible of it.
+            let start = (left + column).max(visible.x as isize);
+            let end = (left + column + filled).min(visible.right() as isize);
+            if y >= visible.y as isize && y < visible.bottom() as isize && start < end {
+                let source = Rect::new((start - left - column) as usize, 0, (end - start) as usize, 1);
+                let destination = Point {
+                    x: start as usize,
+                    y: y as usize,
+                };
+                result(canvas.copy_from(&buffer, source, destination, CHUNK))?;
+            }
+            if filled < count {
+                return Ok(());
+            }
+            column += count;
+        }
+    }
+    Ok(())
+}
+
+fn fill_solid<C: Canvas>(canvas: &mut C, area: &Rectangle, color: Rgb888) -> Result<(), Status> {
+    match visible(canvas, area) {
+        Some(visible) => result(canvas.fill_rect(visible, to_blt(color))),
+        None => Ok(()),
+    }
+}
+
+impl OriginDimensions for GraphicOutput {
+    fn size(&self) -> Size {
+        size(self)
+    }
+}
+
+impl DrawTarget for GraphicOutput {
+    type Color = Rgb888;
+    type Error = Status;
+
+    fn draw_iter<I: IntoIterator<Item = Pixel<Rgb888>>>(&mut self, pixels: I) -> Result<(), Status> {
+        draw_iter(self, pixels)
+    }
+
+    fn fill_contiguous<I: IntoIterator<Item = Rgb888>>(&mut self, area: &Rectangle, colors: I) -> Result<(), Status> {
+        fill_contiguous(self, area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Rgb888) -> Result<(), Status> {
+        fill_solid(self, area, color)
+    }
+
+    fn clear(&mut self, color: Rgb888) -> Result<(), Status> {
+        let bounds = self.bounding_box();
+        fill_solid(self, &bounds, color)
+    }
+}
+
+impl<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>> OriginDimensions for BltBuffer<S> {
+    fn size(&self) -> Size {
+        size(self)
+    }
+}
+
+impl<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>> DrawTarget for BltBuffer<S> {
+    type Color = Rgb888;
+    type Error = Status;
+
+    fn draw_iter<I: IntoIterator<Item = Pixel<Rgb888>>>(&mut self, pixels: I) -> Result<(), Status> {
+        draw_iter(self, pixels)
+    }
+
+    fn fill_contiguous<I: IntoIterator<Item = Rgb888>>(&mut self, area: &Rectangle, colors: I) -> Result<(), Status> {
+        fill_contiguous(self, area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Rgb888) -> Result<(), Status> {
+        fill_solid(self, area, color)
+    }
+
+    fn clear(&mut self, color: Rgb888) -> Result<(), Status> {
+        let bounds = self.bounding_box();
+        fill_solid(self, &bounds, color)
+    }
+}
@@ -0,0 +1,473 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use crate::{
+    protocols::{
+        console::graphic_output::{Coordinate, Point, Rect},
+        media::file::File,
+    },
+    services::pool::PoolBuffer,
+};
+
+use super::{builtin_font, canvas::Canvas};
+
+///Pixels of a scaled glyph line converted at once.
+const ROW_CHUNK: usize = 256;
+
+///A monospace bitmap font.
+pub trait Font {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    ///Returns the glyph of a character: height() rows of row_bytes() bytes, the leftmost pixel
+    ///in the most significant bit.
+    fn glyph(&self, c: char) -> Option<&[u8]>;
+
+    ///Glyph drawn for characters missing from the font.
+    fn replacement(&self) -> &[u8];
+
+    fn row_bytes(&self) -> usize {
+        self.width().div_ceil(8)
+    }
+}
+
+///The built-in 8x16 font.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BuiltinFont;
+
+impl Font for BuiltinFont {
+    fn width(&self) -> usize {
+        builtin_font::WIDTH
+    }
+
+    fn height(&self) -> usize {
+        builtin_font::HEIGHT
+    }
+
+    fn glyph(&self, c: char) -> Option<&[u8]> {
+        builtin_font::glyph(c).map(|glyph| &glyph[..])
+    }
+
+    fn replacement(&self) -> &[u8] {
+        builtin_font::replacement()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Version {
+    Psf1,
+    Psf2,
+}
+
+///Marks a character missing from the Latin-1 lookup table.
+const MISSING: u16 = u16::MAX;
+
+///A PC Screen Font (version 1 or 2), as used by the Linux console.
+pub struct PsfFont<D: AsRef<[u8]>> {
+    data: D,
+    version: Version,
+    glyphs: usize,
+    glyph_count: usize,
+    glyph_size: usize,
+    width: usize,
+    height: usize,
+    ///Offset of the unicode table, if there is one.
+    unicode: Option<usize>,
+    ///Glyph index of the characters up to U+00FF, looked up once.
+    latin: [u16; 256],
+    replacement: usize,
+}
+impl<D: AsRef<[u8]>> PsfFont<D> {
+    ///Parses a font from its file content.
+    pub fn parse(data: D) -> Result<PsfFont<D>, Status> {
+        let bytes = data.as_ref();
+        let u32_at = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                .ok_or(Status::COMPROMISED_DATA)
+        };
+        let (version, glyphs, glyph_count, glyph_size, width, height, has_table) = match bytes {
+            [0x36, 0x04, mode, size, ..] => {
+                let count = if mode & 0x01 != 0 { 512 } else { 256 };
+                (Version::Psf1, 4, count, *size as usize, 8, *size as usize, mode & 0x06 != 0)
+            }
+            [0x72, 0xB5, 0x4A, 0x86, ..] => {
+                let (header_size, flags, count, size) = (u32_at(8)?, u32_at(12)?, u32_at(16)?, u32_at(20)?);
+                let (height, width) = (u32_at(24)?, u32_at(28)?);
+                if size != width.div_ceil(8) * height {
+                    return Err(Status::COMPROMISED_DATA);
+                }
+                (Version::Psf2, header_size, count, size, width, height, flags & 0x01 != 0)
+            }
+            _ => return Err(Status::UNSUPPORTED),
+        };
+        let end = glyph_count
+            .checked_mul(glyph_size)
+            .and_then(|size| size.checked_add(glyphs))
+            .filter(|&end| end <= bytes.len())
+            .ok_or(Status::COMPROMISED_DATA)?;
+        if width == 0 || height == 0 || glyph_count == 0 {
+            return Err(Status::COMPROMISED_DATA);
+        }
+        let mut font = PsfFont {
+            data,
+            version,
+            glyphs,
+            glyph_count,
+            glyph_size,
+            width,
+            height,
+            unicode: if has_table { Some(end) } else { None },
+            latin: [MISSING; 256],
+            replacement: 0,
+        };
+        match font.unicode {
+            None => {
+                for (code, index) in font.latin.iter_mut().enumerate().take(glyph_count) {
+                    *index = code as u16;
+                }
+            }
+            Some(_) => {
+                let mut latin = [MISSING; 256];
+                font.scan(|index, c| {
+                    if let Some(entry) = latin.get_mut(c as usize) {
+                        if *entry == MISSING {
+                            *entry = index as u16;
+                        }
+                    }
+                    false
+                });
+                font.latin = latin;
+            }
+        }
+        font.replacement = font.index('\u{FFFD}').or_else(|| font.index('?')).unwrap_or(0);
+        Ok(font)
+    }
+
+    pub fn glyph_count(&self) -> usize {
+        self.glyph_count
+    }
+
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    fn glyph_at(&self, index: usize) -> &[u8] {
+        let start = self.glyphs + index * self.glyph_size;
+        &self.data.as_ref()[start..start + self.glyph_size]
+    }
+
+    fn index(&self, c: char) -> Option<usize> {
+        if let Some(&index) = self.latin.get(c as usize) {
+            return if index == MISSING { None } else { Some(index as usize) };
+        }
+        if self.unicode.is_none() {
+            return Some(c as usize).filter(|&index| index < self.glyph_count);
+        }
+        let mut found = None;
+        self.scan(|index, mapped| {
+            if mapped == c {
+                found = Some(index);
+            }
+            found.is_some()
+        });
+        found
+    }
+
+    ///Calls f with each (glyph index, character) pair of the unicode table until it returns true.
+    ///Sequences of combining characters are skipped.
+    fn scan(&self, mut f: impl FnMut(usize, char) -> bool) {
+        let table = match self.unicode {
+            Some(offset) => &self.data.as_ref()[offset..],
+            None => return,
+        };
+        match self.version {
+            Version::Psf1 => {
+                let mut entries = table.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+                for index in 0..self.glyph_count {
+                    let mut in_sequence = false;
+                    for entry in entries.by_ref() {
+                        match entry {
+                            0xFFFF => break,
+                            0xFFFE => in_sequence = true,
+                            _ if in_sequence => {}
+                            _ => {
+                                if let Some(c) = char::from_u32(entry as u32) {
+                                    if f(index, c) {
+                                        return;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Version::Psf2 => {
+                let mut entries = table.split(|&b| b == 0xFF);
+                for index in 0..self.glyph_count {
+                    let entry = match entries.next() {
+                        Some(entry) => entry,
+                        None => return,
+                    };
+                    let singles = entry.split(|&b| b == 0xFE).next().unwrap_or(&[]);
+                    for c in core::str::from_utf8(singles).unwrap_or("").chars() {
+                        if f(index, c) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PsfFont<PoolBuffer<u8>> {
+    ///Reads a font file.
+    pub fn load(st: *mut SystemTable, file: &File) -> Result<PsfFont<PoolBuffer<u8>>, Status> {
+        PsfFont::parse(PoolBuffer::read_file(st, file)?)
+    }
+}
+
+impl<D: AsRef<[u8]>> Font for PsfFont<D> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn glyph(&self, c: char) -> Option<&[u8]> {
+        self.index(c).map(|index| self.glyph_at(index))
+    }
+
+    fn replacement(&self) -> &[u8] {
+        self.glyph_at(self.replacement)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TextStyle {
+    pub foreground: BltPixel,
+    ///None leaves the pixels around the glyphs untouched.
+    pub background: Option<BltPixel>,
+    ///Each font pixel is drawn as scale * scale pixels.
+    pub scale: usize,
+}
+impl TextStyle {
+    pub fn new(foreground: BltPixel, background: Option<BltPixel>) -> TextStyle {
+        TextStyle {
+            foreground,
+            background,
+            scale: 1,
+        }
+    }
+
+    pub fn scaled(self, scale: usize) -> TextStyle {
+        TextStyle {
+            scale: scale.max(1),
+            ..self
+        }
+    }
+}
+
+///Size of a character cell.
+pub fn cell_size<F: Font>(font: &F, scale: usize) -> Coordinate {
+    Coordinate {
+        x: font.width() * scale.max(1),
+        y: font.height() * scale.max(1),
+    }
+}
+
+fn bit(glyph: &[u8], row_bytes: usize, x: usize, y: usize) -> bool {
+    glyph[y * row_bytes + x / 8] & (0x80 >> (x % 8)) != 0
+}
+
+///Draws a character with its top left corner at origin. The parts outside the canvas are skipped.
+pub fn draw_char<C: Canvas, F: Font>(canvas: &mut C, font: &F, c: char, origin: Point, style: &TextStyle) -> Status {
+    let glyph = font.glyph(c).unwrap_or_else(|| font.replacement());
+    let scale = style.scale.max(1);
+    let cell = Rect::from_points(origin, cell_size(font, scale));
+    let visible = match canvas.bounds().intersection(&cell) {
+        Some(visible) => visible,
+        None => return Status::SUCCESS,
+    };
+    let row_bytes = font.row_bytes();
+    for row in 0..font.height() {
+        let line = Rect::new(cell.x, cell.y + row * scale, cell.width, scale);
+        let line = match visible.intersection(&line) {
+            Some(line) => line,
+            None => continue,
+        };
+        let status = match style.background {
+            Some(background) => {
+                draw_opaque_row(canvas, glyph, row_bytes, row, line, cell.x, scale, style.foreground, background)
+            }
+            None => draw_transparent_row(canvas, glyph, row_bytes, row, line, cell.x, scale, style.foreground),
+        };
+        if status != Status::SUCCESS {
+            return status;
+        }
+    }
+    Status::SUCCESS
+}
+
+///Draws the visible part line of glyph row row, copying the pixels ROW_CHUNK at a time.
+#[allow(clippy::too_many_arguments)]
+fn draw_opaque_row<C: Canvas>(
+    canvas: &mut C,
+    glyph: &[u8],
+    row_bytes: usize,
+    row: usize,
+    line: Rect,
+    left: usize,
+    scale: usize,
+    foreground: BltPixel,
+    background: BltPixel,
+) -> Status {
+    let mut pixels = [background; ROW_CHUNK];
+    let mut x = line.x;
+    while x < line.right() {
+        let count = (line.right() - x).min(ROW_CHUNK);
+        for (i, pixel) in pixels[..count].iter_mut().enumerate() {
+            let set = bit(glyph, row_bytes, (x + i - left) / scale, row);
+            *pixel = if set { foreground } else { background };
+        }
+        for y in line.y..line.bottom() {
+            let status = canvas.copy_from(&pixels, Rect::new(0, 0, count, 1), Point { x, y }, ROW_CHUNK);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        x += count;
+    }
+    Status::SUCCESS
+}
+
+///Draws the runs of set pixels of glyph row row within line.
+#[allow(clippy::too_many_arguments)]
+fn draw_transparent_row<C: Canvas>(
+    canvas: &mut C,
+    glyph: &[u8],
+    row_bytes: usize,
+    row: usize,
+    line: Rect,
+    left: usize,
+    scale: usize,
+    foreground: BltPixel,
+) -> Status {
+    let mut x = line.x;
+    while x < line.right() {
+        if !bit(glyph, row_bytes, (x - left) / scale, row) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < line.right() && bit(glyph, row_bytes, (x - left) / scale, row) {
+            x += 1;
+        }
+        let status = canvas.fill_rect(Rect::new(start, line.y, x - start, line.height), foreground);
+        if status != Status::SUCCESS {
+            return status;
+        }
+    }
+    Status::SUCCESS
+}
+
+///Splits text into lines of at most max_chars characters, at '\n' and, when a line is too long,
+///after its last space. Words longer than a line are cut. '\r' is ignored by the drawing functions.
+pub struct Lines<'a> {
+    rest: Option<&'a str>,
+    max_chars: usize,
+}
+
+pub fn lines(text: &str, max_chars: usize) -> Lines<'_> {
+    Lines {
+        rest: Some(text),
+        max_chars: max_chars.max(1),
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let text = self.rest?;
+        let (segment, after) = match text.find('\n') {
+            Some(end) => (&text[..end], Some(&text[end + 1..])),
+            None => (text, None),
+        };
+        let mut last_space = None;
+        for (count, (offset, c)) in segment.char_indices().filter(|&(_, c)| c != '\r').enumerate() {
+            if count == self.max_chars {
+                if c == ' ' {
+                    last_space = Some(offset);
+                }
+                // Too long: break after the last space, or cut the word.
+                let (line, rest) = match last_space {
+                    Some(space) => (&segment[..space], text[space..].trim_start_matches(' ')),
+                    None => (&segment[..offset], &text[offset..]),
+                };
+                self.rest = Some(rest);
+                return Some(line);
+            }
+            if c == ' ' {
+                last_space = Some(offset);
+            }
+        }
+        self.rest = after;
+        Some(segment)
+    }
+}
+
+fn char_count(line: &str) -> usize {
+    line.chars().filter(|&c| c != '\r').count()
+}
+
+///Size of text drawn with draw_text() in an area max_width pixels wide, or unwrapped.
+pub fn measure<F: Font>(font: &F, text: &str, scale: usize, max_width: Option<usize>) -> Coordinate {
+    let cell = cell_size(font, scale);
+    let max_chars = max_width.map_or(usize::MAX, |width| width / cell.x);
+    let (mut width, mut height) = (0, 0);
+    for line in lines(text, max_chars) {
+        width = width.max(char_count(line) * cell.x);
+        height += cell.y;
+    }
+    Coordinate { x: width, y: height }
+}
+
+///Draws text wrapped to the width of area. Lines which do not fit in its height are dropped.
+///Returns the position following the last character drawn.
+pub fn draw_text<C: Canvas, F: Font>(
+    canvas: &mut C,
+    font: &F,
+    text: &str,
+    area: Rect,
+    style: &TextStyle,
+) -> Result<Point, Status> {
+    let cell = cell_size(font, style.scale);
+    let mut end = area.origin();
+    if area.width < cell.x {
+        return Ok(end);
+    }
+    for (index, line) in lines(text, area.width / cell.x).enumerate() {
+        let y = area.y + index * cell.y;
+        if y + cell.y > area.bottom() {
+            break;
+        }
+        let mut x = area.x;
+        for c in line.chars().filter(|&c| c != '\r') {
+            let status = draw_char(canvas, font, c, Point { x, y }, style);
+            if status != Status::SUCCESS {
+                return Err(status);
+            }
+            x += cell.x;
+        }
+        end = Point { x, y };
+    }
+    Ok(end)
+}
@@ -0,0 +1,770 @@
+use lib_efi::{
+    efi::Status,
+    protocols::{graphics_output::BltPixel, simple_text_input::InputKey},
+};
+
+use crate::protocols::console::{
+    graphic_output::{Coordinate, Point, Rect},
+    pointer::{PointerSample, PointerSource},
+    text_input::{ScanCode, TextInput, CHAR_BACKSPACE, CHAR_CARRIAGE_RETURN, CHAR_TAB},
+};
+
+use super::{
+    canvas::Canvas,
+    font::{cell_size, draw_char, Font, TextStyle},
+    painter::{Painter, Vertex},
+};
+
+///Identifies a widget from one frame to the next: widgets are numbered in the order they are drawn.
+pub type WidgetId = usize;
+
+///Padding inside widgets, in pixels.
+const PADDING: usize = 4;
+
+const ARROW: [Vertex; 7] = [(0, 0), (0, 16), (4, 12), (7, 18), (9, 17), (6, 11), (11, 11)];
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub background: BltPixel,
+    ///Background of dialogs.
+    pub panel: BltPixel,
+    pub widget: BltPixel,
+    pub hovered: BltPixel,
+    pub pressed: BltPixel,
+    ///Checked boxes, selected list items and the filled part of sliders.
+    pub accent: BltPixel,
+    pub text: BltPixel,
+    pub border: BltPixel,
+    pub focus: BltPixel,
+    ///Each font pixel is drawn as text_scale * text_scale pixels.
+    pub text_scale: usize,
+    pub radius: usize,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: BltPixel {
+                blue: 0x20,
+                green: 0x20,
+                red: 0x20,
+                reserved: 0,
+            },
+            panel: BltPixel {
+                blue: 0x30,
+                green: 0x30,
+                red: 0x30,
+                reserved: 0,
+            },
+            widget: BltPixel {
+                blue: 0x50,
+                green: 0x50,
+                red: 0x50,
+                reserved: 0,
+            },
+            hovered: BltPixel {
+                blue: 0x68,
+                green: 0x68,
+                red: 0x68,
+                reserved: 0,
+            },
+            pressed: BltPixel {
+                blue: 0xC0,
+                green: 0x80,
+                red: 0x30,
+                reserved: 0,
+            },
+            accent: BltPixel {
+                blue: 0xE0,
+                green: 0x90,
+                red: 0x30,
+                reserved: 0,
+            },
+            text: BltPixel {
+                blue: 0xF0,
+                green: 0xF0,
+                red: 0xF0,
+                reserved: 0,
+            },
+            border: BltPixel {
+                blue: 0x80,
+                green: 0x80,
+                red: 0x80,
+                reserved: 0,
+            },
+            focus: BltPixel {
+                blue: 0x40,
+                green: 0xC0,
+                red: 0xF0,
+                reserved: 0,
+            },
+            text_scale: 1,
+            radius: 3,
+        }
+    }
+}
+
+///Input gathered for one frame.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Input {
+    ///None when the pointer did not change.
+    pub pointer: Option<PointerSample>,
+    pub key: Option<InputKey>,
+}
+impl Input {
+    ///Polls a pointing device, if there is one, and the keyboard once. A Cursor for Pointer and a
+    ///TouchScreen for AbsolutePointer both report positions mapped to the screen.
+    pub fn poll<P: PointerSource>(pointer: Option<&mut P>, keyboard: &TextInput) -> Result<Input, Status> {
+        let pointer = match pointer {
+            Some(pointer) => pointer.poll()?,
+            None => None,
+        };
+        let key = match keyboard.read_key_stroke() {
+            Ok(key) => Some(key),
+            Err(Status::NOT_READY) => None,
+            Err(status) => return Err(status),
+        };
+        Ok(Input { pointer, key })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pointer.is_none() && self.key.is_none()
+    }
+}
+
+///Text edited by Frame::text_field(), stored in N bytes of UTF-8.
+#[derive(Clone, Copy)]
+pub struct TextBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+impl<const N: usize> TextBuffer<N> {
+    pub const fn new() -> TextBuffer<N> {
+        TextBuffer { bytes: [0; N], len: 0 }
+    }
+
+    ///Fails if text does not fit.
+    pub fn with_text(text: &str) -> Result<TextBuffer<N>, Status> {
+        let mut buffer = TextBuffer::new();
+        if text.len() > N {
+            return Err(Status::BUFFER_TOO_SMALL);
+        }
+        buffer.bytes[..text.len()].copy_from_slice(text.as_bytes());
+        buffer.len = text.len();
+        Ok(buffer)
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only whole characters are ever inserted or removed.
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    ///Inserts a character at byte offset at. Returns false if it does not fit.
+    pub fn insert(&mut self, at: usize, c: char) -> bool {
+        let size = c.len_utf8();
+        if self.len + size > N || !self.as_str().is_char_boundary(at) {
+            return false;
+        }
+        self.bytes.copy_within(at..self.len, at + size);
+        c.encode_utf8(&mut self.bytes[at..at + size]);
+        self.len += size;
+        true
+    }
+
+    ///Removes the character starting at byte offset at, returning its size.
+    pub fn remove(&mut self, at: usize) -> usize {
+        let size = match self.as_str().get(at..).and_then(|rest| rest.chars().next()) {
+            Some(c) => c.len_utf8(),
+            None => return 0,
+        };
+        self.bytes.copy_within(at + size..self.len, at);
+        self.len -= size;
+        size
+    }
+
+    ///Byte offset of the character before at.
+    fn previous(&self, at: usize) -> usize {
+        self.as_str()[..at]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    ///Byte offset of the character after at.
+    fn next(&self, at: usize) -> usize {
+        self.as_str()[at..].chars().next().map_or(at, |c| at + c.len_utf8())
+    }
+}
+
+impl<const N: usize> Default for TextBuffer<N> {
+    fn default() -> Self {
+        TextBuffer::new()
+    }
+}
+
+///Hands out the rectangles of widgets stacked top to bottom.
+pub struct Column {
+    area: Rect,
+    y: usize,
+    spacing: usize,
+}
+impl Column {
+    pub fn new(area: Rect, spacing: usize) -> Column {
+        Column {
+            area,
+            y: area.y,
+            spacing,
+        }
+    }
+
+    ///The next rectangle, height pixels high and as wide as the column. It is empty once the
+    ///column is full.
+    pub fn next(&mut self, height: usize) -> Rect {
+        let height = height.min(self.area.bottom().saturating_sub(self.y));
+        let rect = Rect::new(self.area.x, self.y, self.area.width, height);
+        self.y = (self.y + height + self.spacing).min(self.area.bottom());
+        rect
+    }
+
+    ///The next rectangle, split into count columns of equal width.
+    pub fn split(&mut self, height: usize, count: usize, index: usize) -> Rect {
+        let row = Rect::new(self.area.x, self.y, self.area.width, height);
+        if index + 1 >= count {
+            self.next(height);
+        }
+        split(row, count, index, self.spacing)
+    }
+}
+
+///Rectangle of column index when row is split into count columns, spacing pixels apart.
+pub fn split(row: Rect, count: usize, index: usize, spacing: usize) -> Rect {
+    let count = count.max(1);
+    let width = row.width.saturating_sub(spacing * (count - 1)) / count;
+    Rect::new(row.x + index * (width + spacing), row.y, width, row.height)
+}
+
+///State of an immediate mode GUI kept between frames: focus, pressed widget and pointer.
+///
+///Each frame, the application calls frame() with the input received, then draws every widget,
+///acting on what they return. Widgets are drawn over the previous frame: draw into a BltBuffer
+///or a Compositor layer and copy it to the screen to avoid flicker. Tab and the Down arrow move
+///the focus to the next widget, the Up arrow to the previous one, Enter and Space activate it.
+pub struct Gui {
+    theme: Theme,
+    pointer: PointerSample,
+    button_down: bool,
+    focus: Option<WidgetId>,
+    ///The widget the pointer button was pressed on, while it is held.
+    active: Option<WidgetId>,
+    ///Widgets of the dialog drawn in the last frame, which alone receive input.
+    dialog: Option<(WidgetId, WidgetId)>,
+    ///Text field the cursor belongs to, and its byte offset in the text.
+    text_cursor: Option<(WidgetId, usize)>,
+}
+impl Gui {
+    pub fn new(theme: Theme) -> Gui {
+        Gui {
+            theme,
+            pointer: PointerSample::default(),
+            button_down: false,
+            focus: None,
+            active: None,
+            dialog: None,
+            text_cursor: None,
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn focus(&self) -> Option<WidgetId> {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, focus: Option<WidgetId>) {
+        self.focus = focus;
+    }
+
+    ///Height of a widget holding a line of text.
+    pub fn line_height<F: Font>(&self, font: &F) -> usize {
+        cell_size(font, self.theme.text_scale).y + 2 * PADDING
+    }
+
+    ///Starts a frame drawn on canvas with font.
+    pub fn frame<'a, C: Canvas, F: Font>(
+        &'a mut self,
+        canvas: &'a mut C,
+        font: &'a F,
+        input: Input,
+    ) -> Frame<'a, C, F> {
+        if let Some(sample) = input.pointer {
+            self.pointer = sample;
+        }
+        let pressed = self.pointer.primary && !self.button_down;
+        let released = !self.pointer.primary && self.button_down;
+        self.button_down = self.pointer.primary;
+        Frame {
+            gui: self,
+            painter: Painter::new(canvas),
+            font,
+            key: input.key,
+            pressed,
+            released,
+            next: 0,
+            dialog_start: None,
+            dialog: None,
+            status: Status::SUCCESS,
+        }
+    }
+}
+
+///What the pointer and keyboard did to a widget this frame.
+#[derive(Clone, Copy, Debug)]
+struct Interaction {
+    id: WidgetId,
+    hovered: bool,
+    ///The pointer button is held on the widget.
+    held: bool,
+    ///Pressed and released on the widget.
+    clicked: bool,
+    focused: bool,
+}
+
+///A frame being drawn. Call end() to get the first drawing error.
+pub struct Frame<'a, C: Canvas, F: Font> {
+    gui: &'a mut Gui,
+    painter: Painter<'a, C>,
+    font: &'a F,
+    ///The key pressed, until a widget uses it.
+    key: Option<InputKey>,
+    pressed: bool,
+    released: bool,
+    next: WidgetId,
+    dialog_start: Option<WidgetId>,
+    dialog: Option<(WidgetId, WidgetId)>,
+    status: Status,
+}
+impl<'a, C: Canvas, F: Font> Frame<'a, C, F> {
+    fn check(&mut self, status: Status) {
+        if self.status == Status::SUCCESS {
+            self.status = status;
+        }
+    }
+
+    fn theme(&self) -> Theme {
+        self.gui.theme
+    }
+
+    ///Returns true if a dialog is open and the widget being drawn is not part of it.
+    fn blocked(&self) -> bool {
+        self.gui.dialog.is_some() && self.dialog_start.is_none()
+    }
+
+    fn interact(&mut self, rect: Rect) -> Interaction {
+        let id = self.next;
+        self.next += 1;
+        let enabled = !self.blocked();
+        let hovered = enabled && rect.contains(self.gui.pointer.position);
+        if hovered && self.pressed {
+            self.gui.active = Some(id);
+            self.gui.focus = Some(id);
+        }
+        let active = self.gui.active == Some(id);
+        Interaction {
+            id,
+            hovered,
+            held: active && self.gui.pointer.primary,
+            clicked: active && self.released && hovered,
+            focused: enabled && self.gui.focus == Some(id),
+        }
+    }
+
+    ///Takes the key pressed if the widget has the focus and accepts it.
+    fn take_key(&mut self, widget: &Interaction, accept: impl Fn(&InputKey) -> bool) -> Option<InputKey> {
+        match self.key {
+            Some(key) if widget.focused && accept(&key) => {
+                self.key = None;
+                Some(key)
+            }
+            _ => None,
+        }
+    }
+
+    fn activated(&mut self, widget: &Interaction) -> bool {
+        let activation = |key: &InputKey| key.unicode_char == CHAR_CARRIAGE_RETURN || key.unicode_char == ' ' as u16;
+        widget.clicked || self.take_key(widget, activation).is_some()
+    }
+
+    fn box_color(&self, widget: &Interaction) -> BltPixel {
+        let theme = self.theme();
+        if widget.held {
+            theme.pressed
+        } else if widget.hovered {
+            theme.hovered
+        } else {
+            theme.widget
+        }
+    }
+
+    fn draw_box(&mut self, rect: Rect, color: BltPixel, focused: bool) {
+        let theme = self.theme();
+        let status = self.painter.fill_rounded_rect(rect, theme.radius, color);
+        self.check(status);
+        let border = if focused { theme.focus } else { theme.border };
+        let status = self.painter.rounded_rect(rect, theme.radius, border);
+        self.check(status);
+    }
+
+    ///Draws a line of text in rect, vertically centered, cut to its width.
+    fn draw_text(&mut self, rect: Rect, text: &str, centered: bool) {
+        let theme = self.theme();
+        let cell = cell_size(self.font, theme.text_scale);
+        let fitting = rect.width / cell.x.max(1);
+        let count = text.chars().count().min(fitting);
+        let x = if centered {
+            rect.x + (rect.width - count * cell.x) / 2
+        } else {
+            rect.x
+        };
+        let y = rect.y + rect.height.saturating_sub(cell.y) / 2;
+        if y + cell.y > rect.bottom() {
+            return;
+        }
+        let style = TextStyle::new(theme.text, None).scaled(theme.text_scale);
+        for (index, c) in text.chars().take(count).enumerate() {
+            let origin = Point {
+                x: x + index * cell.x,
+                y,
+            };
+            let status = draw_char(self.painter.canvas(), self.font, c, origin, &style);
+            self.check(status);
+        }
+    }
+
+    ///Fills the canvas with the background color of the theme.
+    pub fn clear(&mut self) {
+        let (bounds, background) = (self.painter.clip(), self.theme().background);
+        let status = self.painter.fill_rect(bounds, background);
+        self.check(status);
+    }
+
+    pub fn label(&mut self, rect: Rect, text: &str) {
+        self.draw_text(rect, text, false);
+    }
+
+    ///Returns true when the button is clicked, or activated from the keyboard.
+    pub fn button(&mut self, rect: Rect, text: &str) -> bool {
+        let widget = self.interact(rect);
+        let color = self.box_color(&widget);
+        self.draw_box(rect, color, widget.focused);
+        self.draw_text(rect, text, true);
+        self.activated(&widget)
+    }
+
+    ///A box followed by a label, both toggling checked. Returns true when it changed.
+    pub fn checkbox(&mut self, rect: Rect, text: &str, checked: &mut bool) -> bool {
+        let widget = self.interact(rect);
+        let changed = self.activated(&widget);
+        if changed {
+            *checked = !*checked;
+        }
+        let side = rect.height.min(rect.width);
+        let square = Rect::new(rect.x, rect.y, side, side);
+        let color = self.box_color(&widget);
+        self.draw_box(square, color, widget.focused);
+        if *checked && side > 2 * PADDING {
+            let mark = Rect::new(
+                square.x + PADDING,
+                square.y + PADDING,
+                side - 2 * PADDING,
+                side - 2 * PADDING,
+            );
+            let accent = self.theme().accent;
+            let status = self.painter.fill_rect(mark, accent);
+            self.check(status);
+        }
+        let label = Rect::new(
+            rect.x + side + 2 * PADDING,
+            rect.y,
+            rect.width.saturating_sub(side + 2 * PADDING),
+            rect.height,
+        );
+        self.draw_text(label, text, false);
+        changed
+    }
+
+    ///A single line text field. Printable keys are inserted at the cursor, which Left, Right, Home
+    ///and End move. Returns true when the text changed.
+    pub fn text_field<const N: usize>(&mut self, rect: Rect, text: &mut TextBuffer<N>) -> bool {
+        let widget = self.interact(rect);
+        let mut cursor = match self.gui.text_cursor {
+            Some((owner, cursor)) if owner == widget.id && cursor <= text.len() => cursor,
+            _ => text.len(),
+        };
+        let editing = |key: &InputKey| {
+            let scan = key.scan_code;
+            let c = key.unicode_char;
+            c == CHAR_BACKSPACE
+                || (c >= 0x20 && c != 0x7F)
+                || [
+                    ScanCode::Left,
+                    ScanCode::Right,
+                    ScanCode::Home,
+                    ScanCode::End,
+                    ScanCode::Delete,
+                ]
+                .iter()
+                .any(|&code| scan == code as u16)
+        };
+        let mut changed = false;
+        if let Some(key) = self.take_key(&widget, editing) {
+            match key.unicode_char {
+                CHAR_BACKSPACE if cursor > 0 => {
+                    cursor = text.previous(cursor);
+                    changed = text.remove(cursor) > 0;
+                }
+                CHAR_BACKSPACE => {}
+                0 => match key.scan_code {
+                    scan if scan == ScanCode::Left as u16 => cursor = text.previous(cursor),
+                    scan if scan == ScanCode::Right as u16 => cursor = text.next(cursor),
+                    scan if scan == ScanCode::Home as u16 => cursor = 0,
+                    scan if scan == ScanCode::End as u16 => cursor = text.len(),
+                    _ => changed = text.remove(cursor) > 0,
+                },
+                unit => {
+                    let c = char::from_u32(unit as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+                    if text.insert(cursor, c) {
+                        cursor += c.len_utf8();
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if widget.focused {
+            self.gui.text_cursor = Some((widget.id, cursor));
+        }
+        let widget_color = self.theme().widget;
+        self.draw_box(rect, widget_color, widget.focused);
+        let inner = Rect::new(
+            rect.x + PADDING,
+            rect.y,
+            rect.width.saturating_sub(2 * PADDING),
+            rect.height,
+        );
+        let cell = cell_size(self.font, self.theme().text_scale);
+        // Scroll so the cursor stays visible.
+        let fitting = (inner.width / cell.x.max(1)).max(1);
+        let cursor_chars = text.as_str()[..cursor].chars().count();
+        let skipped = (cursor_chars + 1).saturating_sub(fitting);
+        let visible = text
+            .as_str()
+            .char_indices()
+            .nth(skipped)
+            .map_or("", |(start, _)| &text.as_str()[start..]);
+        self.draw_text(inner, visible, false);
+        if widget.focused {
+            let x = inner.x + (cursor_chars - skipped) * cell.x;
+            let y = inner.y + inner.height.saturating_sub(cell.y) / 2;
+            let caret = Rect::new(x, y, 1.max(cell.x / 8), cell.y);
+            let focus = self.theme().focus;
+            let status = self.painter.fill_rect(caret, focus);
+            self.check(status);
+        }
+        changed
+    }
+
+    ///A list of items, one of them selected, scrolled to keep it visible. Up, Down, Home and End
+    ///change the selection when it has the focus. Returns true when the selection changed.
+    pub fn list(&mut self, rect: Rect, items: &[&str], selected: &mut usize) -> bool {
+        let widget = self.interact(rect);
+        let row_height = cell_size(self.font, self.theme().text_scale).y + PADDING;
+        let rows = (rect.height.saturating_sub(2) / row_height).max(1);
+        let previous = *selected;
+        let navigation = |key: &InputKey| {
+            [ScanCode::Up, ScanCode::Down, ScanCode::Home, ScanCode::End]
+                .iter()
+                .any(|&code| key.scan_code == code as u16)
+        };
+        if let Some(key) = self.take_key(&widget, navigation) {
+            *selected = match key.scan_code {
+                scan if scan == ScanCode::Up as u16 => selected.saturating_sub(1),
+                scan if scan == ScanCode::Down as u16 => *selected + 1,
+                scan if scan == ScanCode::Home as u16 => 0,
+                _ => items.len().saturating_sub(1),
+            };
+        }
+        *selected = (*selected).min(items.len().saturating_sub(1));
+        let first = (*selected + 1).saturating_sub(rows);
+        if widget.held && self.pressed {
+            let row = (self.gui.pointer.position.y - rect.y).saturating_sub(1) / row_height;
+            if first + row < items.len() {
+                *selected = first + row;
+            }
+        }
+        let widget_color = self.theme().widget;
+        self.draw_box(rect, widget_color, widget.focused);
+        for (row, item) in items.iter().enumerate().skip(first).take(rows) {
+            let line = Rect::new(
+                rect.x + 1,
+                rect.y + 1 + (row - first) * row_height,
+                rect.width.saturating_sub(2),
+                row_height,
+            );
+            if row == *selected {
+                let accent = self.theme().accent;
+                let status = self.painter.fill_rect(line, accent);
+                self.check(status);
+            }
+            let text = Rect::new(
+                line.x + PADDING,
+                line.y,
+                line.width.saturating_sub(2 * PADDING),
+                line.height,
+            );
+            self.draw_text(text, item, false);
+        }
+        *selected != previous && !items.is_empty()
+    }
+
+    ///A horizontal slider between min and max, dragged with the pointer or moved by a twentieth of
+    ///the range with Left and Right. Returns true when value changed.
+    pub fn slider(&mut self, rect: Rect, value: &mut i32, min: i32, max: i32) -> bool {
+        let widget = self.interact(rect);
+        let (min, max) = (min.min(max), min.max(max));
+        let previous = *value;
+        let step = ((max as i64 - min as i64) / 20).max(1);
+        let arrows = |key: &InputKey| key.scan_code == ScanCode::Left as u16 || key.scan_code == ScanCode::Right as u16;
+        if let Some(key) = self.take_key(&widget, arrows) {
+            let delta = if key.scan_code == ScanCode::Left as u16 {
+                -step
+            } else {
+                step
+            };
+            *value = (*value as i64 + delta).clamp(min as i64, max as i64) as i32;
+        }
+        let range = max as i64 - min as i64;
+        if widget.held && rect.width > 1 {
+            let offset = self.gui.pointer.position.x.clamp(rect.x, rect.right() - 1) - rect.x;
+            *value =
+                (min as i64 + (offset as i64 * range + (rect.width as i64 - 1) / 2) / (rect.width as i64 - 1)) as i32;
+        }
+        *value = (*value).clamp(min, max);
+        let color = self.box_color(&widget);
+        self.draw_box(rect, color, widget.focused);
+        if range > 0 {
+            let filled = ((*value as i64 - min as i64) * rect.width.saturating_sub(2) as i64 / range) as usize;
+            let bar = Rect::new(rect.x + 1, rect.y + 1, filled, rect.height.saturating_sub(2));
+            let accent = self.theme().accent;
+            let status = self.painter.fill_rect(bar, accent);
+            self.check(status);
+        }
+        *value != previous
+    }
+
+    ///Starts a modal dialog of the given size, centered on the canvas, and returns the area for
+    ///its widgets. While it is drawn, the widgets outside it get no input and the focus stays in
+    ///it. Widgets must be drawn between begin_dialog() and end_dialog().
+    pub fn begin_dialog(&mut self, title: &str, size: Coordinate) -> Rect {
+        let bounds = self.painter.canvas().bounds();
+        let (width, height) = (size.x.min(bounds.width), size.y.min(bounds.height));
+        let rect = Rect::new(
+            bounds.x + (bounds.width - width) / 2,
+            bounds.y + (bounds.height - height) / 2,
+            width,
+            height,
+        );
+        self.dialog_start = Some(self.next);
+        let (panel, border) = (self.theme().panel, self.theme().border);
+        let status = self.painter.fill_rect(rect, panel);
+        self.check(status);
+        let status = self.painter.rect(rect, border);
+        self.check(status);
+        let title_height = cell_size(self.font, self.theme().text_scale).y + 2 * PADDING;
+        let title_bar = Rect::new(rect.x, rect.y, rect.width, title_height.min(rect.height));
+        let status = self.painter.fill_rect(title_bar, border);
+        self.check(status);
+        self.draw_text(
+            Rect::new(
+                title_bar.x + PADDING,
+                title_bar.y,
+                title_bar.width.saturating_sub(2 * PADDING),
+                title_bar.height,
+            ),
+            title,
+            false,
+        );
+        Rect::new(
+            rect.x + PADDING,
+            title_bar.bottom() + PADDING,
+            rect.width.saturating_sub(2 * PADDING),
+            rect.bottom().saturating_sub(title_bar.bottom() + 2 * PADDING),
+        )
+    }
+
+    pub fn end_dialog(&mut self) {
+        if let Some(start) = self.dialog_start.take() {
+            self.dialog = Some((start, self.next));
+        }
+    }
+
+    ///Draws an arrow at the pointer position, for pointing devices without a cursor of their own.
+    pub fn pointer_cursor(&mut self) {
+        let position = self.gui.pointer.position;
+        let at = |(x, y): Vertex| (position.x as isize + x, position.y as isize + y);
+        let mut arrow = ARROW;
+        arrow.iter_mut().for_each(|vertex| *vertex = at(*vertex));
+        let (text, background) = (self.theme().text, self.theme().background);
+        let status = self.painter.fill_polygon(&arrow, text);
+        self.check(status);
+        let status = self.painter.polygon(&arrow, background);
+        self.check(status);
+    }
+
+    ///Ends the frame: moves the focus with the navigation keys no widget used, and returns the
+    ///first drawing error.
+    pub fn end(self) -> Status {
+        let gui = self.gui;
+        gui.dialog = self.dialog;
+        if !gui.pointer.primary {
+            gui.active = None;
+        }
+        // The focusable range: the dialog, or every widget.
+        let (first, end) = self.dialog.unwrap_or((0, self.next));
+        if end <= first {
+            gui.focus = None;
+            return self.status;
+        }
+        if !matches!(gui.focus, Some(focus) if focus >= first && focus < end) {
+            gui.focus = if self.dialog.is_some() { Some(first) } else { None };
+        }
+        if let Some(key) = self.key {
+            let forward = key.unicode_char == CHAR_TAB || key.scan_code == ScanCode::Down as u16;
+            let backward = key.scan_code == ScanCode::Up as u16;
+            gui.focus = match (gui.focus, forward, backward) {
+                (None, true, _) => Some(first),
+                (None, _, true) => Some(end - 1),
+                (Some(focus), true, _) => Some(if focus + 1 < end { focus + 1 } else { first }),
+                (Some(focus), _, true) => Some(if focus > first { focus - 1 } else { end - 1 }),
+                (focus, _, _) => focus,
+            };
+        }
+        self.status
+    }
+}
@@ -0,0 +1,266 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use super::{allocate, Image};
+
+pub const MAGIC: [u8; 2] = *b"BM";
+
+const FILE_HEADER_SIZE: usize = 14;
+///Size of the file and BITMAPINFOHEADER headers written by header().
+pub const HEADER_SIZE: usize = FILE_HEADER_SIZE + 40;
+const RGB: u32 = 0;
+const RLE8: u32 = 1;
+const RLE4: u32 = 2;
+const BITFIELDS: u32 = 3;
+const ALPHA_BITFIELDS: u32 = 6;
+
+const TRANSPARENT: BltPixel = BltPixel {
+    blue: 0,
+    green: 0,
+    red: 0,
+    reserved: 0,
+};
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, Status> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or(Status::COMPROMISED_DATA)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, Status> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(Status::COMPROMISED_DATA)
+}
+
+///Extracts the channel of mask from a pixel, scaled to 8 bits.
+fn channel(pixel: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 255;
+    }
+    let max = mask >> mask.trailing_zeros();
+    (((pixel & mask) >> mask.trailing_zeros()) as u64 * 255 / max as u64) as u8
+}
+
+struct Bitmap<'a> {
+    data: &'a [u8],
+    width: usize,
+    height: usize,
+    top_down: bool,
+    bits: u16,
+    ///Red, green, blue and alpha masks.
+    masks: [u32; 4],
+    palette: &'a [u8],
+}
+impl<'a> Bitmap<'a> {
+    fn color(&self, index: usize) -> BltPixel {
+        match self.palette.get(index * 4..index * 4 + 3) {
+            Some(bgr) => BltPixel {
+                blue: bgr[0],
+                green: bgr[1],
+                red: bgr[2],
+                reserved: 255,
+            },
+            None => TRANSPARENT,
+        }
+    }
+
+    ///Index of pixel (x, y) of the image in the pixel buffer: BMP lines are usually stored bottom up.
+    fn target(&self, x: usize, line: usize) -> usize {
+        let y = if self.top_down { line } else { self.height - 1 - line };
+        y * self.width + x
+    }
+
+    fn decode(&self, pixels: &mut [BltPixel]) -> Result<(), Status> {
+        let stride = (self.width * self.bits as usize).div_ceil(32) * 4;
+        for line in 0..self.height {
+            let row = self
+                .data
+                .get(line * stride..line * stride + stride)
+                .ok_or(Status::COMPROMISED_DATA)?;
+            for x in 0..self.width {
+                let pixel = match self.bits {
+                    1 | 2 | 4 | 8 => {
+                        let bit = x * self.bits as usize;
+                        let shift = 8 - self.bits as usize - bit % 8;
+                        self.color(((row[bit / 8] >> shift) & (0xFF >> (8 - self.bits))) as usize)
+                    }
+                    16 | 32 => {
+                        let value = if self.bits == 16 {
+                            u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]) as u32
+                        } else {
+                            u32::from_le_bytes([row[x * 4], row[x * 4 + 1], row[x * 4 + 2], row[x * 4 + 3]])
+                        };
+                        let [red, green, blue, alpha] = self.masks;
+                        BltPixel {
+                            blue: channel(value, blue),
+                            green: channel(value, green),
+                            red: channel(value, red),
+                            reserved: channel(value, alpha),
+                        }
+                    }
+                    _ => BltPixel {
+                        blue: row[x * 3],
+                        green: row[x * 3 + 1],
+                        red: row[x * 3 + 2],
+                        reserved: 255,
+                    },
+                };
+                pixels[self.target(x, line)] = pixel;
+            }
+        }
+        Ok(())
+    }
+
+    ///Decodes RLE8 or RLE4 data. Pixels skipped by delta or end of line codes stay transparent.
+    fn decode_rle(&self, pixels: &mut [BltPixel]) -> Result<(), Status> {
+        let four_bits = self.bits == 4;
+        let (mut x, mut line, mut position) = (0usize, 0usize, 0usize);
+        let mut next = || {
+            let byte = self.data.get(position).copied().ok_or(Status::COMPROMISED_DATA);
+            position += 1;
+            byte
+        };
+        let mut put = |x: &mut usize, line: usize, index: u8| {
+            if *x < self.width && line < self.height {
+                pixels[self.target(*x, line)] = self.color(index as usize);
+            }
+            *x += 1;
+        };
+        loop {
+            let (count, value) = (next()?, next()?);
+            if count > 0 {
+                for i in 0..count {
+                    let index = match four_bits {
+                        true if i % 2 == 0 => value >> 4,
+                        true => value & 0x0F,
+                        false => value,
+                    };
+                    put(&mut x, line, index);
+                }
+                continue;
+            }
+            match value {
+                0 => {
+                    x = 0;
+                    line += 1;
+                }
+                1 => return Ok(()),
+                2 => {
+                    x += next()? as usize;
+                    line += next()? as usize;
+                }
+                count => {
+                    // Absolute run, padded to 16 bits.
+                    let bytes = if four_bits { (count as usize).div_ceil(2) } else { count as usize };
+                    let mut byte = 0;
+                    for i in 0..count {
+                        let index = match four_bits {
+                            true if i % 2 == 0 => {
+                                byte = next()?;
+                                byte >> 4
+                            }
+                            true => byte & 0x0F,
+                            false => next()?,
+                        };
+                        put(&mut x, line, index);
+                    }
+                    if bytes % 2 == 1 {
+                        next()?;
+                    }
+                }
+            }
+            if line >= self.height {
+                return Ok(());
+            }
+        }
+    }
+}
+
+///Decodes a Windows bitmap: 1, 2, 4 and 8-bit palettes, uncompressed or RLE, and 16, 24 and
+///32-bit pixels, with or without bit fields.
+pub fn decode(st: *mut SystemTable, data: &[u8]) -> Result<Image, Status> {
+    if !data.starts_with(&MAGIC) {
+        return Err(Status::UNSUPPORTED);
+    }
+    let pixels_offset = u32_at(data, 10)? as usize;
+    let header_size = u32_at(data, 14)? as usize;
+    // BITMAPCOREHEADER (OS/2) has 16-bit dimensions and is not supported.
+    if header_size < 40 {
+        return Err(Status::UNSUPPORTED);
+    }
+    let width = u32_at(data, 18)? as i32;
+    let height = u32_at(data, 22)? as i32;
+    let bits = u16_at(data, 28)?;
+    let compression = u32_at(data, 30)?;
+    let colors = u32_at(data, 46)? as usize;
+    if width <= 0 || height == 0 {
+        return Err(Status::COMPROMISED_DATA);
+    }
+    let masks = match (compression, bits) {
+        (BITFIELDS | ALPHA_BITFIELDS, 16 | 32) => {
+            // The masks follow the 40-byte header, or are part of the larger ones.
+            let has_alpha = compression == ALPHA_BITFIELDS || header_size >= 56;
+            let alpha = if has_alpha { u32_at(data, 66)? } else { 0 };
+            [u32_at(data, 54)?, u32_at(data, 58)?, u32_at(data, 62)?, alpha]
+        }
+        (RGB, 16) => [0x7C00, 0x03E0, 0x001F, 0],
+        (RGB, 32) => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+        (RGB, 1 | 2 | 4 | 8 | 24) | (RLE8, 8) | (RLE4, 4) => [0; 4],
+        _ => return Err(Status::UNSUPPORTED),
+    };
+    let palette_start = FILE_HEADER_SIZE + header_size + if compression == BITFIELDS && header_size == 40 { 12 } else { 0 };
+    let palette_size = if bits <= 8 {
+        (if colors == 0 { 1 << bits } else { colors.min(256) }) * 4
+    } else {
+        0
+    };
+    let bitmap = Bitmap {
+        data: data.get(pixels_offset..).ok_or(Status::COMPROMISED_DATA)?,
+        width: width as usize,
+        height: height.unsigned_abs() as usize,
+        top_down: height < 0,
+        bits,
+        masks,
+        palette: data
+            .get(palette_start..palette_start + palette_size)
+            .ok_or(Status::COMPROMISED_DATA)?,
+    };
+    let mut image = allocate(st, bitmap.width, bitmap.height)?;
+    match compression {
+        RLE8 | RLE4 => {
+            image.pixels_mut().fill(TRANSPARENT);
+            bitmap.decode_rle(image.pixels_mut())?;
+        }
+        _ => bitmap.decode(image.pixels_mut())?,
+    }
+    Ok(image)
+}
+
+///Bytes of a line of 24-bit pixels, padded to 4 bytes.
+pub fn line_size(width: usize) -> usize {
+    (width * 3).div_ceil(4) * 4
+}
+
+///Headers of an uncompressed 24-bit bitmap, to be followed by its lines, bottom up.
+pub fn header(width: usize, height: usize) -> [u8; HEADER_SIZE] {
+    let image_size = (line_size(width) * height) as u32;
+    let mut header = [0u8; HEADER_SIZE];
+    let mut put = |offset: usize, bytes: &[u8]| header[offset..offset + bytes.len()].copy_from_slice(bytes);
+    put(0, &MAGIC);
+    put(2, &(HEADER_SIZE as u32 + image_size).to_le_bytes());
+    put(10, &(HEADER_SIZE as u32).to_le_bytes());
+    put(14, &40u32.to_le_bytes());
+    put(18, &(width as u32).to_le_bytes());
+    put(22, &(height as u32).to_le_bytes());
+    put(26, &1u16.to_le_bytes());
+    put(28, &24u16.to_le_bytes());
+    put(30, &RGB.to_le_bytes());
+    put(34, &image_size.to_le_bytes());
+    // 2835 pixels per meter: 72 DPI.
+    put(38, &2835u32.to_le_bytes());
+    put(42, &2835u32.to_le_bytes());
+    header
+}
@@ -0,0 +1,284 @@
+// DEFLATE (RFC 1951) and zlib (RFC 1950) decompression into a caller provided buffer.
+// Huffman codes are decoded one bit at a time, the canonical way, which is slow but small.
+
+use lib_efi::efi::Status;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097,
+    6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+///Order in which the code length code lengths are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+const MAX_BITS: usize = 15;
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+impl<'a> BitReader<'a> {
+    fn bits(&mut self, count: u32) -> Result<u32, Status> {
+        while self.count < count {
+            let byte = *self.data.get(self.position).ok_or(Status::COMPROMISED_DATA)?;
+            self.position += 1;
+            self.buffer |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u64 << count) - 1) as u32;
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    ///Drops the bits left in the current byte.
+    fn align(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+///A canonical Huffman code: how many codes have each length, and the symbols by code.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: [u16; 288],
+}
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Huffman, Status> {
+        let mut huffman = Huffman {
+            counts: [0; MAX_BITS + 1],
+            symbols: [0; 288],
+        };
+        for &length in lengths {
+            huffman.counts[length as usize] += 1;
+        }
+        // Reject over-subscribed codes. Incomplete ones are valid (a single distance code).
+        let mut left = 1i32;
+        for &count in &huffman.counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(Status::COMPROMISED_DATA);
+            }
+        }
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for length in 1..MAX_BITS {
+            offsets[length + 1] = offsets[length] + huffman.counts[length];
+        }
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                huffman.symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(huffman)
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, Status> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(Status::COMPROMISED_DATA)
+    }
+}
+
+struct Output<'a> {
+    data: &'a mut [u8],
+    len: usize,
+}
+impl<'a> Output<'a> {
+    fn push(&mut self, byte: u8) -> Result<(), Status> {
+        *self.data.get_mut(self.len).ok_or(Status::BUFFER_TOO_SMALL)? = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn copy(&mut self, distance: usize, length: usize) -> Result<(), Status> {
+        if distance > self.len {
+            return Err(Status::COMPROMISED_DATA);
+        }
+        if self.len + length > self.data.len() {
+            return Err(Status::BUFFER_TOO_SMALL);
+        }
+        // Byte by byte: the source may overlap the bytes being written.
+        for _ in 0..length {
+            self.data[self.len] = self.data[self.len - distance];
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+fn stored(reader: &mut BitReader, output: &mut Output) -> Result<(), Status> {
+    reader.align();
+    let header = reader
+        .data
+        .get(reader.position..reader.position + 4)
+        .ok_or(Status::COMPROMISED_DATA)?;
+    let length = u16::from_le_bytes([header[0], header[1]]);
+    if length != !u16::from_le_bytes([header[2], header[3]]) {
+        return Err(Status::COMPROMISED_DATA);
+    }
+    let start = reader.position + 4;
+    let bytes = reader
+        .data
+        .get(start..start + length as usize)
+        .ok_or(Status::COMPROMISED_DATA)?;
+    for &byte in bytes {
+        output.push(byte)?;
+    }
+    reader.position = start + length as usize;
+    Ok(())
+}
+
+fn codes(reader: &mut BitReader, output: &mut Output, literals: &Huffman, distances: &Huffman) -> Result<(), Status> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        match symbol {
+            0..=255 => output.push(symbol as u8)?,
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+                let index = distances.decode(reader)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err(Status::COMPROMISED_DATA);
+                }
+                let distance = DISTANCE_BASE[index] as usize + reader.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                output.copy(distance, length)?;
+            }
+            _ => return Err(Status::COMPROMISED_DATA),
+        }
+    }
+}
+
+fn fixed_codes() -> Result<(Huffman, Huffman), Status> {
+    let mut lengths = [0u8; 288];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), Status> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_count = reader.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(Status::COMPROMISED_DATA);
+    }
+    let mut lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_count] {
+        lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&lengths)?;
+    let mut lengths = [0u8; 286 + 30];
+    let total = literal_count + distance_count;
+    let mut index = 0;
+    while index < total {
+        let symbol = code_lengths.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 if index > 0 => (lengths[index - 1], 3 + reader.bits(2)? as usize),
+            17 => (0, 3 + reader.bits(3)? as usize),
+            18 => (0, 11 + reader.bits(7)? as usize),
+            _ => return Err(Status::COMPROMISED_DATA),
+        };
+        if index + repeat > total {
+            return Err(Status::COMPROMISED_DATA);
+        }
+        lengths[index..index + repeat].fill(value);
+        index += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(Status::COMPROMISED_DATA);
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..total])?,
+    ))
+}
+
+///Decompresses a raw DEFLATE stream into output. Returns the number of bytes written and the
+///number of bytes of data consumed.
+pub fn inflate(data: &[u8], output: &mut [u8]) -> Result<(usize, usize), Status> {
+    let mut reader = BitReader {
+        data,
+        position: 0,
+        buffer: 0,
+        count: 0,
+    };
+    let mut output = Output { data: output, len: 0 };
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => stored(&mut reader, &mut output)?,
+            1 => {
+                let (literals, distances) = fixed_codes()?;
+                codes(&mut reader, &mut output, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(&mut reader)?;
+                codes(&mut reader, &mut output, &literals, &distances)?;
+            }
+            _ => return Err(Status::COMPROMISED_DATA),
+        }
+        if last {
+            return Ok((output.len, reader.position));
+        }
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most that can be summed before b overflows.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+///Decompresses a zlib stream into output and checks its checksum. Returns the number of bytes written.
+pub fn zlib_decompress(data: &[u8], output: &mut [u8]) -> Result<usize, Status> {
+    let (method, flags) = match data {
+        [method, flags, ..] => (*method, *flags),
+        _ => return Err(Status::COMPROMISED_DATA),
+    };
+    // Deflate, no preset dictionary.
+    if method & 0x0F != 8 || ((method as u16) << 8 | flags as u16) % 31 != 0 || flags & 0x20 != 0 {
+        return Err(Status::UNSUPPORTED);
+    }
+    let (len, consumed) = inflate(&data[2..], output)?;
+    let checksum = data
+        .get(2 + consumed..2 + consumed + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(Status::COMPROMISED_DATA)?;
+    if checksum != adler32(&output[..len]) {
+        return Err(Status::CRC_ERROR);
+    }
+    Ok(len)
+}
@@ -0,0 +1,253 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use crate::{
+    protocols::{
+        console::graphic_output::{Coordinate, GraphicOutput, ModePolicy, Point, Rect},
+        media::file::File,
+    },
+    services::pool::PoolBuffer,
+};
+
+use super::canvas::BltBuffer;
+
+pub mod bmp;
+pub mod inflate;
+pub mod png;
+pub mod qoi;
+
+///A decoded image. The alpha channel is kept in BltPixel::reserved, 255 being opaque, until
+///flatten() is called.
+pub type Image = BltBuffer<PoolBuffer<BltPixel>>;
+
+///Pixels blended at once when drawing an image with an alpha channel.
+const ROW_CHUNK: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Bmp,
+    Qoi,
+    Png,
+}
+impl Format {
+    ///Recognizes an image from its first bytes.
+    pub fn detect(data: &[u8]) -> Option<Format> {
+        if data.starts_with(&png::SIGNATURE) {
+            Some(Format::Png)
+        } else if data.starts_with(&qoi::MAGIC) {
+            Some(Format::Qoi)
+        } else if data.starts_with(&bmp::MAGIC) {
+            Some(Format::Bmp)
+        } else {
+            None
+        }
+    }
+}
+
+///Allocates a width * height image, all transparent black.
+pub fn allocate(st: *mut SystemTable, width: usize, height: usize) -> Result<Image, Status> {
+    if width == 0 || height == 0 {
+        return Err(Status::INVALID_PARAMETER);
+    }
+    let len = width.checked_mul(height).ok_or(Status::OUT_OF_RESOURCES)?;
+    let transparent = BltPixel {
+        blue: 0,
+        green: 0,
+        red: 0,
+        reserved: 0,
+    };
+    BltBuffer::new(PoolBuffer::new(st, len, transparent)?, width, height)
+}
+
+///Decodes a BMP, QOI or PNG image, such as one embedded with include_bytes!().
+pub fn decode(st: *mut SystemTable, data: &[u8]) -> Result<Image, Status> {
+    match Format::detect(data) {
+        Some(Format::Bmp) => bmp::decode(st, data),
+        Some(Format::Qoi) => qoi::decode(st, data),
+        Some(Format::Png) => png::decode(st, data),
+        None => Err(Status::UNSUPPORTED),
+    }
+}
+
+///Reads and decodes an image file.
+pub fn load(st: *mut SystemTable, file: &File) -> Result<Image, Status> {
+    decode(st, &PoolBuffer::read_file(st, file)?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    ///Repeats or drops pixels: fast, and keeps pixel art sharp.
+    Nearest,
+    ///Interpolates between the four nearest pixels: smoother, for photos and logos.
+    Bilinear,
+}
+
+///Blends a and b, weight being the part of b out of 256.
+fn mix(a: u8, b: u8, weight: u32) -> u8 {
+    ((a as u32 * (256 - weight) + b as u32 * weight) >> 8) as u8
+}
+
+fn mix_pixel(a: BltPixel, b: BltPixel, weight: u32) -> BltPixel {
+    BltPixel {
+        blue: mix(a.blue, b.blue, weight),
+        green: mix(a.green, b.green, weight),
+        red: mix(a.red, b.red, weight),
+        reserved: mix(a.reserved, b.reserved, weight),
+    }
+}
+
+///Position in the source of destination pixel index, in 1/256 of pixels, for pixel centers to line up.
+fn source_position(index: usize, source: usize, destination: usize) -> usize {
+    let position = ((2 * index + 1) * source * 256 / (2 * destination)) as isize - 128;
+    position.clamp(0, ((source - 1) * 256) as isize) as usize
+}
+
+///Returns a copy of image resized to width * height.
+pub fn scale<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(
+    st: *mut SystemTable,
+    image: &BltBuffer<S>,
+    width: usize,
+    height: usize,
+    filter: Filter,
+) -> Result<Image, Status> {
+    let mut scaled = allocate(st, width, height)?;
+    let (source_width, source_height) = (image.width(), image.height());
+    if source_width == 0 || source_height == 0 {
+        return Ok(scaled);
+    }
+    let source = image.pixels();
+    let at = |x: usize, y: usize| source[y * source_width + x];
+    for (y, line) in scaled.pixels_mut().chunks_exact_mut(width).enumerate() {
+        match filter {
+            Filter::Nearest => {
+                let sy = y * source_height / height;
+                for (x, pixel) in line.iter_mut().enumerate() {
+                    *pixel = at(x * source_width / width, sy);
+                }
+            }
+            Filter::Bilinear => {
+                let fy = source_position(y, source_height, height);
+                let (y0, wy) = (fy / 256, (fy % 256) as u32);
+                let y1 = (y0 + 1).min(source_height - 1);
+                for (x, pixel) in line.iter_mut().enumerate() {
+                    let fx = source_position(x, source_width, width);
+                    let (x0, wx) = (fx / 256, (fx % 256) as u32);
+                    let x1 = (x0 + 1).min(source_width - 1);
+                    let top = mix_pixel(at(x0, y0), at(x1, y0), wx);
+                    let bottom = mix_pixel(at(x0, y1), at(x1, y1), wx);
+                    *pixel = mix_pixel(top, bottom, wy);
+                }
+            }
+        }
+    }
+    Ok(scaled)
+}
+
+///Largest size with the aspect ratio of size that fits in area.
+pub fn fit(size: Coordinate, area: Coordinate) -> Coordinate {
+    if size.x == 0 || size.y == 0 {
+        return Coordinate::default();
+    }
+    if area.x * size.y <= area.y * size.x {
+        Coordinate {
+            x: area.x,
+            y: (size.y * area.x / size.x).max(1),
+        }
+    } else {
+        Coordinate {
+            x: (size.x * area.y / size.y).max(1),
+            y: area.y,
+        }
+    }
+}
+
+///Position of an object of the given size centered in area. It is aligned on the top left
+///corner of area when larger.
+pub fn center(size: Coordinate, area: Rect) -> Point {
+    Point {
+        x: area.x + area.width.saturating_sub(size.x) / 2,
+        y: area.y + area.height.saturating_sub(size.y) / 2,
+    }
+}
+
+///Blends a pixel over an opaque background using its alpha channel.
+pub fn blend(pixel: BltPixel, background: BltPixel) -> BltPixel {
+    let alpha = pixel.reserved as u32;
+    let over = |color: u8, under: u8| ((color as u32 * alpha + under as u32 * (255 - alpha) + 127) / 255) as u8;
+    BltPixel {
+        blue: over(pixel.blue, background.blue),
+        green: over(pixel.green, background.green),
+        red: over(pixel.red, background.red),
+        reserved: 0,
+    }
+}
+
+///Blends every pixel of image over background, leaving it opaque.
+pub fn flatten<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(image: &mut BltBuffer<S>, background: BltPixel) {
+    for pixel in image.pixels_mut() {
+        *pixel = blend(*pixel, background);
+    }
+}
+
+///Draws an image with an alpha channel over a solid background, without modifying it.
+pub fn draw_blended<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(
+    gop: &GraphicOutput,
+    image: &BltBuffer<S>,
+    destination: Point,
+    background: BltPixel,
+) -> Status {
+    let mut buffer = [background; ROW_CHUNK];
+    for (y, line) in image.pixels().chunks_exact(image.width()).enumerate() {
+        for (index, chunk) in line.chunks(ROW_CHUNK).enumerate() {
+            for (target, &pixel) in buffer.iter_mut().zip(chunk) {
+                *target = blend(pixel, background);
+            }
+            let target = Point {
+                x: destination.x + index * ROW_CHUNK,
+                y: destination.y + y,
+            };
+            let status = gop.copy_to_screen(&buffer, Rect::new(0, 0, chunk.len(), 1), target, ROW_CHUNK);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+    }
+    Status::SUCCESS
+}
+
+///Switches to the mode with the most pixels, clears the screen to background and draws image
+///centered on it, scaled down to fit if it is too large.
+pub fn show_splash<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(
+    st: *mut SystemTable,
+    gop: &GraphicOutput,
+    image: &BltBuffer<S>,
+    background: BltPixel,
+) -> Status {
+    if let Some((mode, _)) = gop.find_mode(st, ModePolicy::MaxArea) {
+        if mode != gop.mode().mode {
+            let status = gop.set_mode(mode);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+    }
+    let screen = gop.screen();
+    let status = gop.fill(screen, background);
+    if status != Status::SUCCESS {
+        return status;
+    }
+    let size = Coordinate {
+        x: image.width(),
+        y: image.height(),
+    };
+    if size.x <= screen.width && size.y <= screen.height {
+        return draw_blended(gop, image, center(size, screen), background);
+    }
+    let fitted = fit(size, screen.size());
+    match scale(st, image, fitted.x, fitted.y, Filter::Bilinear) {
+        Ok(scaled) => draw_blended(gop, &scaled, center(fitted, screen), background),
+        Err(status) => status,
+    }
+}
@@ -0,0 +1,316 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use crate::services::pool::PoolBuffer;
+
+use super::{allocate, inflate::zlib_decompress, Image};
+
+pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+///Origin and spacing of the pixels of each Adam7 pass: x, y, dx, dy.
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+const GRAY: u8 = 0;
+const RGB: u8 = 2;
+const PALETTE: u8 = 3;
+const GRAY_ALPHA: u8 = 4;
+const RGB_ALPHA: u8 = 6;
+
+struct Header {
+    width: usize,
+    height: usize,
+    depth: u8,
+    color_type: u8,
+    interlaced: bool,
+}
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            RGB => 3,
+            GRAY_ALPHA => 2,
+            RGB_ALPHA => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.depth as usize
+    }
+
+    ///Bytes of a line of width pixels, without its filter type byte.
+    fn line_bytes(&self, width: usize) -> usize {
+        (width * self.bits_per_pixel()).div_ceil(8)
+    }
+
+    ///Size of a pass, or of the image when not interlaced.
+    fn pass_size(&self, pass: usize) -> (usize, usize) {
+        if !self.interlaced {
+            return (self.width, self.height);
+        }
+        let (x, y, dx, dy) = ADAM7[pass];
+        let count = |size: usize, start: usize, step: usize| if size > start { (size - start).div_ceil(step) } else { 0 };
+        (count(self.width, x, dx), count(self.height, y, dy))
+    }
+
+    fn passes(&self) -> usize {
+        if self.interlaced {
+            ADAM7.len()
+        } else {
+            1
+        }
+    }
+
+    ///Size of the decompressed data: every line of every pass, each with its filter type byte.
+    fn data_size(&self) -> Option<usize> {
+        let mut size = 0usize;
+        for pass in 0..self.passes() {
+            let (width, height) = self.pass_size(pass);
+            if width > 0 && height > 0 {
+                size = size.checked_add((self.line_bytes(width) + 1).checked_mul(height)?)?;
+            }
+        }
+        Some(size)
+    }
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, Status> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(Status::COMPROMISED_DATA)
+}
+
+///Iterates over (type, data) of the chunks following the signature.
+fn chunks(data: &[u8]) -> impl Iterator<Item = Result<([u8; 4], &[u8]), Status>> {
+    let mut offset = SIGNATURE.len();
+    core::iter::from_fn(move || {
+        if offset >= data.len() {
+            return None;
+        }
+        let chunk = u32_at(data, offset).and_then(|length| {
+            let start = offset + 8;
+            let body = data
+                .get(start..start + length as usize)
+                .ok_or(Status::COMPROMISED_DATA)?;
+            let kind = [data[offset + 4], data[offset + 5], data[offset + 6], data[offset + 7]];
+            // Skip the CRC: the zlib stream has its own checksum.
+            offset = start + length as usize + 4;
+            Ok((kind, body))
+        });
+        if chunk.is_err() {
+            offset = data.len();
+        }
+        Some(chunk)
+    })
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+///Reverses the filter of each line of a pass in place. pixel_bytes is the filter distance.
+fn unfilter(data: &mut [u8], line_bytes: usize, height: usize, pixel_bytes: usize) -> Result<(), Status> {
+    let stride = line_bytes + 1;
+    for y in 0..height {
+        let (before, rest) = data.split_at_mut(y * stride);
+        let previous = if y > 0 { Some(&before[before.len() - line_bytes..]) } else { None };
+        let filter = rest[0];
+        let line = &mut rest[1..stride];
+        for x in 0..line_bytes {
+            let a = if x >= pixel_bytes { line[x - pixel_bytes] } else { 0 };
+            let b = previous.map_or(0, |previous| previous[x]);
+            let c = match previous {
+                Some(previous) if x >= pixel_bytes => previous[x - pixel_bytes],
+                _ => 0,
+            };
+            line[x] = line[x].wrapping_add(match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(Status::COMPROMISED_DATA),
+            });
+        }
+    }
+    Ok(())
+}
+
+///Sample index of a line of samples of depth bits.
+fn sample(line: &[u8], index: usize, depth: u8) -> u16 {
+    match depth {
+        16 => u16::from_be_bytes([line[index * 2], line[index * 2 + 1]]),
+        8 => line[index] as u16,
+        _ => {
+            let bit = index * depth as usize;
+            let shift = 8 - depth as usize - bit % 8;
+            ((line[bit / 8] >> shift) & ((1 << depth) - 1)) as u16
+        }
+    }
+}
+
+///Scales a sample to 8 bits.
+fn to_u8(value: u16, depth: u8) -> u8 {
+    match depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        _ => (value as u32 * 255 / ((1 << depth) - 1)) as u8,
+    }
+}
+
+struct Palette {
+    colors: [[u8; 3]; 256],
+    alpha: [u8; 256],
+    ///Transparent gray or RGB value, for images without palette nor alpha channel.
+    key: Option<[u16; 3]>,
+}
+
+fn pixel(header: &Header, palette: &Palette, line: &[u8], x: usize) -> BltPixel {
+    let depth = header.depth;
+    let channels = header.channels();
+    let s = |channel: usize| sample(line, x * channels + channel, depth);
+    let (red, green, blue, alpha) = match header.color_type {
+        GRAY => {
+            let value = s(0);
+            let gray = to_u8(value, depth);
+            let alpha = if palette.key.map(|key| key[0]) == Some(value) { 0 } else { 255 };
+            (gray, gray, gray, alpha)
+        }
+        RGB => {
+            let values = [s(0), s(1), s(2)];
+            let alpha = if palette.key == Some(values) { 0 } else { 255 };
+            (to_u8(values[0], depth), to_u8(values[1], depth), to_u8(values[2], depth), alpha)
+        }
+        PALETTE => {
+            let index = s(0) as usize;
+            let [red, green, blue] = palette.colors[index];
+            (red, green, blue, palette.alpha[index])
+        }
+        GRAY_ALPHA => {
+            let gray = to_u8(s(0), depth);
+            (gray, gray, gray, to_u8(s(1), depth))
+        }
+        _ => (to_u8(s(0), depth), to_u8(s(1), depth), to_u8(s(2), depth), to_u8(s(3), depth)),
+    };
+    BltPixel {
+        blue,
+        green,
+        red,
+        reserved: alpha,
+    }
+}
+
+///Decodes a PNG image of any color type and bit depth, interlaced or not.
+pub fn decode(st: *mut SystemTable, data: &[u8]) -> Result<Image, Status> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err(Status::UNSUPPORTED);
+    }
+    let mut header = None;
+    let mut palette = Palette {
+        colors: [[0; 3]; 256],
+        alpha: [255; 256],
+        key: None,
+    };
+    let mut compressed_size = 0usize;
+    for chunk in chunks(data) {
+        let (kind, body) = chunk?;
+        match &kind {
+            b"IHDR" => {
+                let (depth, color_type) = (*body.get(8).unwrap_or(&0), *body.get(9).unwrap_or(&0));
+                let valid = match color_type {
+                    GRAY => matches!(depth, 1 | 2 | 4 | 8 | 16),
+                    PALETTE => matches!(depth, 1 | 2 | 4 | 8),
+                    RGB | GRAY_ALPHA | RGB_ALPHA => matches!(depth, 8 | 16),
+                    _ => false,
+                };
+                if !valid || body.get(10..12) != Some(&[0, 0]) || !matches!(body.get(12), Some(0 | 1)) {
+                    return Err(Status::UNSUPPORTED);
+                }
+                header = Some(Header {
+                    width: u32_at(body, 0)? as usize,
+                    height: u32_at(body, 4)? as usize,
+                    depth,
+                    color_type,
+                    interlaced: body[12] == 1,
+                });
+            }
+            b"PLTE" => {
+                for (color, rgb) in palette.colors.iter_mut().zip(body.chunks_exact(3)) {
+                    *color = [rgb[0], rgb[1], rgb[2]];
+                }
+            }
+            b"tRNS" => match header.as_ref().map(|h| h.color_type) {
+                Some(PALETTE) => palette.alpha[..body.len().min(256)].copy_from_slice(&body[..body.len().min(256)]),
+                Some(GRAY) if body.len() >= 2 => palette.key = Some([u16::from_be_bytes([body[0], body[1]]); 3]),
+                Some(RGB) if body.len() >= 6 => {
+                    let value = |i: usize| u16::from_be_bytes([body[i], body[i + 1]]);
+                    palette.key = Some([value(0), value(2), value(4)]);
+                }
+                _ => {}
+            },
+            b"IDAT" => compressed_size += body.len(),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+    let header = header.ok_or(Status::COMPROMISED_DATA)?;
+    // The zlib stream is split across the IDAT chunks: join them.
+    let mut compressed = PoolBuffer::new(st, compressed_size, 0u8)?;
+    let mut offset = 0;
+    for chunk in chunks(data) {
+        match chunk? {
+            (kind, body) if &kind == b"IDAT" => {
+                compressed[offset..offset + body.len()].copy_from_slice(body);
+                offset += body.len();
+            }
+            (kind, _) if &kind == b"IEND" => break,
+            _ => {}
+        }
+    }
+    let size = header.data_size().ok_or(Status::OUT_OF_RESOURCES)?;
+    let mut raw = PoolBuffer::new(st, size, 0u8)?;
+    if zlib_decompress(&compressed, &mut raw)? != size {
+        return Err(Status::COMPROMISED_DATA);
+    }
+    drop(compressed);
+    let mut image = allocate(st, header.width, header.height)?;
+    let width = header.width;
+    let pixels = image.pixels_mut();
+    let pixel_bytes = header.bits_per_pixel().div_ceil(8);
+    let mut offset = 0;
+    for (pass, &layout) in ADAM7.iter().enumerate().take(header.passes()) {
+        let (pass_width, pass_height) = header.pass_size(pass);
+        if pass_width == 0 || pass_height == 0 {
+            continue;
+        }
+        let (x0, y0, dx, dy) = if header.interlaced { layout } else { (0, 0, 1, 1) };
+        let line_bytes = header.line_bytes(pass_width);
+        let data = &mut raw[offset..offset + (line_bytes + 1) * pass_height];
+        unfilter(data, line_bytes, pass_height, pixel_bytes)?;
+        for (row, line) in data.chunks_exact(line_bytes + 1).enumerate() {
+            let y = y0 + row * dy;
+            for column in 0..pass_width {
+                pixels[y * width + x0 + column * dx] = pixel(&header, &palette, &line[1..], column);
+            }
+        }
+        offset += (line_bytes + 1) * pass_height;
+    }
+    Ok(image)
+}
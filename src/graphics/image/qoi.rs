@@ -0,0 +1,88 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use super::{allocate, Image};
+
+pub const MAGIC: [u8; 4] = *b"qoif";
+
+const HEADER_SIZE: usize = 14;
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+
+fn hash(pixel: &BltPixel) -> usize {
+    (pixel.red as usize * 3 + pixel.green as usize * 5 + pixel.blue as usize * 7 + pixel.reserved as usize * 11) % 64
+}
+
+///Decodes a QOI ("Quite OK Image") image.
+pub fn decode(st: *mut SystemTable, data: &[u8]) -> Result<Image, Status> {
+    if data.len() < HEADER_SIZE || !data.starts_with(&MAGIC) {
+        return Err(Status::UNSUPPORTED);
+    }
+    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    let mut image = allocate(st, width, height)?;
+    let mut seen = [BltPixel {
+        blue: 0,
+        green: 0,
+        red: 0,
+        reserved: 0,
+    }; 64];
+    let mut pixel = BltPixel {
+        blue: 0,
+        green: 0,
+        red: 0,
+        reserved: 255,
+    };
+    let mut position = HEADER_SIZE;
+    let mut next = || {
+        let byte = data.get(position).copied().ok_or(Status::COMPROMISED_DATA);
+        position += 1;
+        byte
+    };
+    let pixels = image.pixels_mut();
+    let mut index = 0;
+    while index < pixels.len() {
+        let op = next()?;
+        let mut run = 1;
+        match op {
+            OP_RGB => {
+                pixel.red = next()?;
+                pixel.green = next()?;
+                pixel.blue = next()?;
+            }
+            OP_RGBA => {
+                pixel.red = next()?;
+                pixel.green = next()?;
+                pixel.blue = next()?;
+                pixel.reserved = next()?;
+            }
+            _ => match op & 0xC0 {
+                OP_INDEX => pixel = seen[op as usize],
+                OP_DIFF => {
+                    pixel.red = pixel.red.wrapping_add((op >> 4) & 0x03).wrapping_sub(2);
+                    pixel.green = pixel.green.wrapping_add((op >> 2) & 0x03).wrapping_sub(2);
+                    pixel.blue = pixel.blue.wrapping_add(op & 0x03).wrapping_sub(2);
+                }
+                OP_LUMA => {
+                    let green = (op & 0x3F).wrapping_sub(32);
+                    let byte = next()?;
+                    pixel.red = pixel.red.wrapping_add(green).wrapping_add(byte >> 4).wrapping_sub(8);
+                    pixel.green = pixel.green.wrapping_add(green);
+                    pixel.blue = pixel.blue.wrapping_add(green).wrapping_add(byte & 0x0F).wrapping_sub(8);
+                }
+                // 0xC0: run of the previous pixel.
+                _ => run = (op & 0x3F) as usize + 1,
+            },
+        }
+        seen[hash(&pixel)] = pixel;
+        let end = (index + run).min(pixels.len());
+        pixels[index..end].fill(pixel);
+        index = end;
+    }
+    Ok(image)
+}
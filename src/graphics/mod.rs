@@ -0,0 +1,13 @@
+pub mod animation;
+pub mod builtin_font;
+pub mod canvas;
+pub mod compositor;
+#[cfg(feature = "embedded-graphics")]
+pub mod embedded;
+pub mod font;
+pub mod gui;
+pub mod image;
+pub mod on_screen_keyboard;
+pub mod painter;
+pub mod terminal;
+pub mod truetype;
@@ -0,0 +1,423 @@
+use lib_efi::{
+    efi::Status,
+    protocols::{
+        graphics_output::BltPixel,
+        simple_text_input::InputKey,
+    },
+};
+
+use crate::protocols::console::{
+    graphic_output::{Coordinate, GraphicOutput, Rect},
+    pointer::{PointerSample, PointerSource},
+    text_input::{ScanCode, CHAR_BACKSPACE, CHAR_CARRIAGE_RETURN, CHAR_NULL},
+};
+
+use super::builtin_font;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Action {
+    ///Characters produced on the lowercase, uppercase and symbol layers.
+    Char(char, char, char),
+    Shift,
+    Symbols,
+    Backspace,
+    Enter,
+    Escape,
+    Space,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    Lower,
+    Upper,
+    Symbols,
+}
+
+struct Key {
+    action: Action,
+    ///Width in half keys. Every row is 20 half keys wide.
+    width: usize,
+}
+
+const fn char_key(lower: char, upper: char, symbol: char) -> Key {
+    Key {
+        action: Action::Char(lower, upper, symbol),
+        width: 2,
+    }
+}
+
+const ROW_UNITS: usize = 20;
+
+static ROWS: [&[Key]; 4] = [
+    &[
+        char_key('q', 'Q', '1'),
+        char_key('w', 'W', '2'),
+        char_key('e', 'E', '3'),
+        char_key('r', 'R', '4'),
+        char_key('t', 'T', '5'),
+        char_key('y', 'Y', '6'),
+        char_key('u', 'U', '7'),
+        char_key('i', 'I', '8'),
+        char_key('o', 'O', '9'),
+        char_key('p', 'P', '0'),
+    ],
+    &[
+        char_key('a', 'A', '@'),
+        char_key('s', 'S', '#'),
+        char_key('d', 'D', '$'),
+        char_key('f', 'F', '%'),
+        char_key('g', 'G', '&'),
+        char_key('h', 'H', '*'),
+        char_key('j', 'J', '-'),
+        char_key('k', 'K', '+'),
+        char_key('l', 'L', '('),
+        char_key('\'', '"', ')'),
+    ],
+    &[
+        Key { action: Action::Shift, width: 3 },
+        char_key('z', 'Z', '!'),
+        char_key('x', 'X', '?'),
+        char_key('c', 'C', ':'),
+        char_key('v', 'V', ';'),
+        char_key('b', 'B', '_'),
+        char_key('n', 'N', '='),
+        char_key('m', 'M', '/'),
+        Key { action: Action::Backspace, width: 3 },
+    ],
+    &[
+        Key { action: Action::Symbols, width: 3 },
+        Key { action: Action::Escape, width: 2 },
+        char_key(',', '<', '['),
+        Key { action: Action::Space, width: 6 },
+        char_key('.', '>', ']'),
+        Key { action: Action::Enter, width: 5 },
+    ],
+];
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub background: BltPixel,
+    pub key: BltPixel,
+    pub special_key: BltPixel,
+    pub pressed_key: BltPixel,
+    pub label: BltPixel,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            background: BltPixel { blue: 0x20, green: 0x20, red: 0x20, reserved: 0 },
+            key: BltPixel { blue: 0x50, green: 0x50, red: 0x50, reserved: 0 },
+            special_key: BltPixel { blue: 0x38, green: 0x38, red: 0x38, reserved: 0 },
+            pressed_key: BltPixel { blue: 0xC0, green: 0x80, red: 0x30, reserved: 0 },
+            label: BltPixel { blue: 0xF0, green: 0xF0, red: 0xF0, reserved: 0 },
+        }
+    }
+}
+
+///On-screen keyboard drawn through the Graphics Output Protocol and driven by a pointing device.
+///Taps are turned into the EFI_INPUT_KEY values TextInput::read_key_stroke() would return.
+pub struct OnScreenKeyboard<'a> {
+    gop: &'a GraphicOutput,
+    origin: Coordinate,
+    size: Coordinate,
+    theme: Theme,
+    layer: Layer,
+    pressed: Option<(usize, usize)>,
+    button_down: bool,
+}
+impl<'a> OnScreenKeyboard<'a> {
+    ///Creates a keyboard covering the rectangle at origin, size.x pixels wide and size.y pixels high.
+    pub fn new(gop: &'a GraphicOutput, origin: Coordinate, size: Coordinate) -> OnScreenKeyboard<'a> {
+        OnScreenKeyboard {
+            gop,
+            origin,
+            size,
+            theme: Theme::default(),
+            layer: Layer::Lower,
+            pressed: None,
+            button_down: false,
+        }
+    }
+
+    ///Creates a keyboard across the bottom of the screen, using the lower 40% of the current mode.
+    pub fn docked(gop: &'a GraphicOutput) -> Result<OnScreenKeyboard<'a>, Status> {
+        let info = gop.query_mode(gop.mode().mode)?;
+        let width = info.horizontal_resolution as usize;
+        let height = info.vertical_resolution as usize;
+        let keyboard_height = height * 2 / 5;
+        Ok(OnScreenKeyboard::new(
+            gop,
+            Coordinate {
+                x: 0,
+                y: height - keyboard_height,
+            },
+            Coordinate {
+                x: width,
+                y: keyboard_height,
+            },
+        ))
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    ///Returns true if the point lies on the keyboard.
+    pub fn contains(&self, point: Coordinate) -> bool {
+        point.x >= self.origin.x
+            && point.y >= self.origin.y
+            && point.x < self.origin.x + self.size.x
+            && point.y < self.origin.y + self.size.y
+    }
+
+    ///Draws the whole keyboard.
+    pub fn draw(&self) -> Status {
+        let status = fill(self.gop, self.origin, self.size, self.theme.background);
+        if status != Status::SUCCESS {
+            return status;
+        }
+        for (row, keys) in ROWS.iter().enumerate() {
+            for column in 0..keys.len() {
+                let status = self.draw_key(row, column);
+                if status != Status::SUCCESS {
+                    return status;
+                }
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Feeds a pointer sample to the keyboard. A key is produced when the button or finger is released.
+    pub fn handle(&mut self, sample: &PointerSample) -> Result<Option<InputKey>, Status> {
+        let under = self.key_at(sample.position);
+        match (sample.primary, self.button_down) {
+            (true, false) => {
+                self.button_down = true;
+                self.press(under)?;
+                Ok(None)
+            }
+            (true, true) => {
+                if under != self.pressed {
+                    self.press(under)?;
+                }
+                Ok(None)
+            }
+            (false, true) => {
+                self.button_down = false;
+                // The position reported on release is unreliable on some panels: commit the highlighted key.
+                let key = self.pressed;
+                self.press(None)?;
+                match key {
+                    Some((row, column)) => self.activate(ROWS[row][column].action),
+                    None => Ok(None),
+                }
+            }
+            (false, false) => Ok(None),
+        }
+    }
+
+    ///Reads the next keystroke typed on the keyboard, polling the pointer once.
+    ///Returns EFI_NOT_READY when no key was completed, like TextInput::read_key_stroke().
+    pub fn read_key_stroke<P: PointerSource>(&mut self, pointer: &mut P) -> Result<InputKey, Status> {
+        match pointer.poll()? {
+            Some(sample) => match self.handle(&sample)? {
+                Some(key) => Ok(key),
+                None => Err(Status::NOT_READY),
+            },
+            None => Err(Status::NOT_READY),
+        }
+    }
+
+    fn press(&mut self, key: Option<(usize, usize)>) -> Result<(), Status> {
+        let previous = self.pressed;
+        self.pressed = key;
+        for (row, column) in previous.into_iter().chain(key) {
+            let status = self.draw_key(row, column);
+            if status != Status::SUCCESS {
+                return Err(status);
+            }
+        }
+        Ok(())
+    }
+
+    fn activate(&mut self, action: Action) -> Result<Option<InputKey>, Status> {
+        let key = match action {
+            Action::Char(lower, upper, symbol) => {
+                let c = match self.layer {
+                    Layer::Lower => lower,
+                    Layer::Upper => upper,
+                    Layer::Symbols => symbol,
+                };
+                if self.layer == Layer::Upper {
+                    self.set_layer(Layer::Lower)?;
+                }
+                unicode_key(c as u16)
+            }
+            Action::Shift => {
+                let layer = if self.layer == Layer::Upper { Layer::Lower } else { Layer::Upper };
+                self.set_layer(layer)?;
+                return Ok(None);
+            }
+            Action::Symbols => {
+                let layer = if self.layer == Layer::Symbols { Layer::Lower } else { Layer::Symbols };
+                self.set_layer(layer)?;
+                return Ok(None);
+            }
+            Action::Backspace => unicode_key(CHAR_BACKSPACE),
+            Action::Enter => unicode_key(CHAR_CARRIAGE_RETURN),
+            Action::Space => unicode_key(' ' as u16),
+            Action::Escape => InputKey {
+                scan_code: ScanCode::Esc as u16,
+                unicode_char: CHAR_NULL,
+            },
+        };
+        Ok(Some(key))
+    }
+
+    fn set_layer(&mut self, layer: Layer) -> Result<(), Status> {
+        self.layer = layer;
+        match self.draw() {
+            Status::SUCCESS => Ok(()),
+            status => Err(status),
+        }
+    }
+
+    fn key_bounds(&self, row: usize, column: usize) -> (Coordinate, Coordinate) {
+        let start: usize = ROWS[row][..column].iter().map(|key| key.width).sum();
+        let end = start + ROWS[row][column].width;
+        let x0 = self.origin.x + start * self.size.x / ROW_UNITS;
+        let x1 = self.origin.x + end * self.size.x / ROW_UNITS;
+        let y0 = self.origin.y + row * self.size.y / ROWS.len();
+        let y1 = self.origin.y + (row + 1) * self.size.y / ROWS.len();
+        (Coordinate { x: x0, y: y0 }, Coordinate { x: x1 - x0, y: y1 - y0 })
+    }
+
+    fn key_at(&self, point: Coordinate) -> Option<(usize, usize)> {
+        if !self.contains(point) {
+            return None;
+        }
+        let row = (point.y - self.origin.y) * ROWS.len() / self.size.y;
+        let unit = (point.x - self.origin.x) * ROW_UNITS / self.size.x;
+        let mut start = 0;
+        for (column, key) in ROWS[row].iter().enumerate() {
+            if unit < start + key.width {
+                return Some((row, column));
+            }
+            start += key.width;
+        }
+        None
+    }
+
+    fn draw_key(&self, row: usize, column: usize) -> Status {
+        const MARGIN: usize = 2;
+        let (origin, size) = self.key_bounds(row, column);
+        if size.x <= 2 * MARGIN || size.y <= 2 * MARGIN {
+            return Status::SUCCESS;
+        }
+        let action = ROWS[row][column].action;
+        let color = if self.pressed == Some((row, column)) {
+            self.theme.pressed_key
+        } else {
+            match action {
+                Action::Char(..) | Action::Space => self.theme.key,
+                Action::Shift if self.layer == Layer::Upper => self.theme.pressed_key,
+                _ => self.theme.special_key,
+            }
+        };
+        let origin = Coordinate {
+            x: origin.x + MARGIN,
+            y: origin.y + MARGIN,
+        };
+        let size = Coordinate {
+            x: size.x - 2 * MARGIN,
+            y: size.y - 2 * MARGIN,
+        };
+        let status = fill(self.gop, origin, size, color);
+        if status != Status::SUCCESS {
+            return status;
+        }
+        let mut buffer = [0u8; 4];
+        let label = match action {
+            Action::Char(lower, upper, symbol) => match self.layer {
+                Layer::Lower => lower,
+                Layer::Upper => upper,
+                Layer::Symbols => symbol,
+            }
+            .encode_utf8(&mut buffer),
+            Action::Shift => "Shift",
+            Action::Symbols if self.layer == Layer::Symbols => "abc",
+            Action::Symbols => "?123",
+            Action::Backspace => "\u{232B}",
+            Action::Enter => "\u{21B5}",
+            Action::Escape => "Esc",
+            Action::Space => "",
+        };
+        draw_label(self.gop, label, origin, size, self.theme.label, color)
+    }
+}
+
+fn unicode_key(unicode_char: u16) -> InputKey {
+    InputKey {
+        scan_code: ScanCode::Null as u16,
+        unicode_char,
+    }
+}
+
+fn fill(gop: &GraphicOutput, origin: Coordinate, size: Coordinate, color: BltPixel) -> Status {
+    gop.fill(Rect::from_points(origin, size), color)
+}
+
+///Draws a label centered in a key with the built-in font, doubled in size when it fits.
+fn draw_label(
+    gop: &GraphicOutput,
+    label: &str,
+    origin: Coordinate,
+    size: Coordinate,
+    foreground: BltPixel,
+    background: BltPixel,
+) -> Status {
+    const MAX_SCALE: usize = 2;
+    let length = label.chars().count();
+    if length == 0 {
+        return Status::SUCCESS;
+    }
+    let scale = if length * builtin_font::WIDTH * MAX_SCALE <= size.x && builtin_font::HEIGHT * MAX_SCALE <= size.y {
+        MAX_SCALE
+    } else {
+        1
+    };
+    let glyph_size = Coordinate {
+        x: builtin_font::WIDTH * scale,
+        y: builtin_font::HEIGHT * scale,
+    };
+    if length * glyph_size.x > size.x || glyph_size.y > size.y {
+        return Status::SUCCESS;
+    }
+    let mut position = Coordinate {
+        x: origin.x + (size.x - length * glyph_size.x) / 2,
+        y: origin.y + (size.y - glyph_size.y) / 2,
+    };
+    let mut pixels = [background; builtin_font::WIDTH * builtin_font::HEIGHT * MAX_SCALE * MAX_SCALE];
+    for c in label.chars() {
+        let glyph = builtin_font::glyph(c).unwrap_or_else(builtin_font::replacement);
+        for y in 0..glyph_size.y {
+            let bits = glyph[y / scale];
+            for x in 0..glyph_size.x {
+                let set = bits & (0x80 >> (x / scale)) != 0;
+                pixels[y * glyph_size.x + x] = if set { foreground } else { background };
+            }
+        }
+        let status = gop.copy_to_screen(
+            &pixels,
+            Rect::new(0, 0, glyph_size.x, glyph_size.y),
+            position,
+            glyph_size.x,
+        );
+        if status != Status::SUCCESS {
+            return status;
+        }
+        position.x += glyph_size.x;
+    }
+    Status::SUCCESS
+}
@@ -0,0 +1,332 @@
+use lib_efi::{efi::Status, protocols::graphics_output::BltPixel};
+
+use crate::protocols::console::graphic_output::Rect;
+
+use super::canvas::Canvas;
+
+///Depth of the clipping stack.
+pub const MAX_CLIPS: usize = 16;
+///Most edges a filled polygon may cross on one line.
+const MAX_CROSSINGS: usize = 64;
+
+///A point of a shape. Shapes may extend past the canvas: they are clipped.
+pub type Vertex = (isize, isize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientDirection {
+    ///From the left edge to the right edge.
+    Horizontal,
+    ///From the top edge to the bottom edge.
+    Vertical,
+}
+
+///Draws shapes on a canvas, clipped to the rectangle on top of the clipping stack.
+pub struct Painter<'a, C: Canvas> {
+    canvas: &'a mut C,
+    clips: [Rect; MAX_CLIPS],
+    depth: usize,
+}
+impl<'a, C: Canvas> Painter<'a, C> {
+    pub fn new(canvas: &'a mut C) -> Painter<'a, C> {
+        let bounds = canvas.bounds();
+        Painter {
+            canvas,
+            clips: [bounds; MAX_CLIPS],
+            depth: 0,
+        }
+    }
+
+    pub fn canvas(&mut self) -> &mut C {
+        self.canvas
+    }
+
+    ///The area drawing is currently limited to.
+    pub fn clip(&self) -> Rect {
+        self.clips[self.depth]
+    }
+
+    ///Restricts drawing to rect, inside the current clip. Fails when the stack is full.
+    pub fn push_clip(&mut self, rect: Rect) -> Status {
+        if self.depth + 1 == MAX_CLIPS {
+            return Status::OUT_OF_RESOURCES;
+        }
+        let clip = self.clip().intersection(&rect).unwrap_or_default();
+        self.depth += 1;
+        self.clips[self.depth] = clip;
+        Status::SUCCESS
+    }
+
+    ///Restores the clip active before the last push_clip().
+    pub fn pop_clip(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    ///Fills the pixels x0..=x1 of line y, within the clip.
+    fn span(&mut self, x0: isize, x1: isize, y: isize, color: BltPixel) -> Status {
+        let clip = self.clip();
+        let (x0, x1) = (x0.min(x1).max(clip.x as isize), x0.max(x1).min(clip.right() as isize - 1));
+        if y < clip.y as isize || y >= clip.bottom() as isize || x0 > x1 {
+            return Status::SUCCESS;
+        }
+        self.canvas.fill_rect(Rect::new(x0 as usize, y as usize, (x1 - x0 + 1) as usize, 1), color)
+    }
+
+    pub fn pixel(&mut self, point: Vertex, color: BltPixel) -> Status {
+        self.span(point.0, point.0, point.1, color)
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: BltPixel) -> Status {
+        match self.clip().intersection(&rect) {
+            Some(visible) => self.canvas.fill_rect(visible, color),
+            None => Status::SUCCESS,
+        }
+    }
+
+    pub fn rect(&mut self, rect: Rect, color: BltPixel) -> Status {
+        if rect.is_empty() {
+            return Status::SUCCESS;
+        }
+        let (left, top) = (rect.x, rect.y);
+        let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+        let sides = [
+            Rect::new(left, top, rect.width, 1),
+            Rect::new(left, bottom, rect.width, 1),
+            Rect::new(left, top, 1, rect.height),
+            Rect::new(right, top, 1, rect.height),
+        ];
+        for side in sides {
+            let status = self.fill_rect(side, color);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Draws a one pixel wide line with Bresenham's algorithm. Horizontal runs are drawn as spans.
+    pub fn line(&mut self, from: Vertex, to: Vertex, color: BltPixel) -> Status {
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let step_x = if x < to.0 { 1 } else { -1 };
+        let step_y = if y < to.1 { 1 } else { -1 };
+        let mut error = dx + dy;
+        let mut run_start = x;
+        loop {
+            if (x, y) == to {
+                return self.span(run_start, x, y, color);
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                // Leaving the line: draw the run of pixels on it.
+                let status = self.span(run_start, if doubled >= dy { x - step_x } else { x }, y, color);
+                if status != Status::SUCCESS {
+                    return status;
+                }
+                error += dx;
+                y += step_y;
+                run_start = x;
+            }
+        }
+    }
+
+    pub fn ellipse(&mut self, center: Vertex, radius_x: usize, radius_y: usize, color: BltPixel) -> Status {
+        let (rect, inset) = ellipse_insets(center, radius_x, radius_y);
+        self.outline(rect, inset, color)
+    }
+
+    pub fn fill_ellipse(&mut self, center: Vertex, radius_x: usize, radius_y: usize, color: BltPixel) -> Status {
+        let (rect, inset) = ellipse_insets(center, radius_x, radius_y);
+        self.fill_insets(rect, inset, color)
+    }
+
+    pub fn circle(&mut self, center: Vertex, radius: usize, color: BltPixel) -> Status {
+        self.ellipse(center, radius, radius, color)
+    }
+
+    pub fn fill_circle(&mut self, center: Vertex, radius: usize, color: BltPixel) -> Status {
+        self.fill_ellipse(center, radius, radius, color)
+    }
+
+    pub fn rounded_rect(&mut self, rect: Rect, radius: usize, color: BltPixel) -> Status {
+        let radius = radius.min(rect.width / 2).min(rect.height / 2);
+        self.outline(signed(rect), |row| corner_inset(row, rect.height, radius), color)
+    }
+
+    pub fn fill_rounded_rect(&mut self, rect: Rect, radius: usize, color: BltPixel) -> Status {
+        let radius = radius.min(rect.width / 2).min(rect.height / 2);
+        self.fill_insets(signed(rect), |row| corner_inset(row, rect.height, radius), color)
+    }
+
+    ///Draws the edges of a closed polygon.
+    pub fn polygon(&mut self, points: &[Vertex], color: BltPixel) -> Status {
+        for (i, &from) in points.iter().enumerate() {
+            let to = points[(i + 1) % points.len()];
+            let status = self.line(from, to, color);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Fills a closed polygon with the even-odd rule, sampling each line at its top edge.
+    pub fn fill_polygon(&mut self, points: &[Vertex], color: BltPixel) -> Status {
+        if points.len() < 3 {
+            return self.polygon(points, color);
+        }
+        let clip = self.clip();
+        let top = points.iter().map(|p| p.1).min().unwrap_or(0).max(clip.y as isize);
+        let bottom = points.iter().map(|p| p.1).max().unwrap_or(0).min(clip.bottom() as isize - 1);
+        let mut crossings = [0isize; MAX_CROSSINGS];
+        for y in top..=bottom {
+            let mut count = 0;
+            for (i, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(i + 1) % points.len()];
+                if (y0 <= y && y < y1) || (y1 <= y && y < y0) {
+                    if count == MAX_CROSSINGS {
+                        return Status::OUT_OF_RESOURCES;
+                    }
+                    crossings[count] = x0 + (y - y0) * (x1 - x0) / (y1 - y0);
+                    count += 1;
+                }
+            }
+            crossings[..count].sort_unstable();
+            for pair in crossings[..count].chunks_exact(2) {
+                let status = self.span(pair[0], pair[1], y, color);
+                if status != Status::SUCCESS {
+                    return status;
+                }
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Fills rect with colors blended from start to end.
+    pub fn fill_gradient(&mut self, rect: Rect, start: BltPixel, end: BltPixel, direction: GradientDirection) -> Status {
+        let steps = match direction {
+            GradientDirection::Horizontal => rect.width,
+            GradientDirection::Vertical => rect.height,
+        };
+        for i in 0..steps {
+            let color = blend(start, end, i, steps.saturating_sub(1).max(1));
+            let band = match direction {
+                GradientDirection::Horizontal => Rect::new(rect.x + i, rect.y, 1, rect.height),
+                GradientDirection::Vertical => Rect::new(rect.x, rect.y + i, rect.width, 1),
+            };
+            let status = self.fill_rect(band, color);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Draws the outline of a shape symmetric around its vertical middle, given the inset of each
+    ///of its lines from the left and right sides of rect. Each line is joined to the line closer
+    ///to the top or bottom edge, so steep parts of the outline have no holes.
+    fn outline(&mut self, rect: SignedRect, inset: impl Fn(usize) -> usize, color: BltPixel) -> Status {
+        let (width, height) = (rect.2, rect.3);
+        if width == 0 || height == 0 {
+            return Status::SUCCESS;
+        }
+        let (left, right) = (rect.0, rect.0 + width as isize - 1);
+        for row in 0..height {
+            let y = rect.1 + row as isize;
+            let outer = if row < height / 2 { row.checked_sub(1) } else { Some(row + 1).filter(|&r| r < height) };
+            let current = inset(row) as isize;
+            let end = match outer {
+                None => (right - left) / 2,
+                Some(outer) => (inset(outer) as isize - 1).max(current),
+            };
+            let status = self.span(left + current, left + end, y, color);
+            if status != Status::SUCCESS {
+                return status;
+            }
+            let status = self.span(right - end, right - current, y, color);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Fills a shape given the inset of each of its lines, like outline().
+    fn fill_insets(&mut self, rect: SignedRect, inset: impl Fn(usize) -> usize, color: BltPixel) -> Status {
+        for row in 0..rect.3 {
+            let current = inset(row) as isize;
+            let status = self.span(rect.0 + current, rect.0 + rect.2 as isize - 1 - current, rect.1 + row as isize, color);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
+}
+
+///Left, top, width and height of a box that may start off the canvas.
+type SignedRect = (isize, isize, usize, usize);
+
+fn signed(rect: Rect) -> SignedRect {
+    (rect.x as isize, rect.y as isize, rect.width, rect.height)
+}
+
+///Bounding box of an ellipse, and the inset of each of its lines.
+fn ellipse_insets(center: Vertex, radius_x: usize, radius_y: usize) -> (SignedRect, impl Fn(usize) -> usize) {
+    let rect = (
+        center.0 - radius_x as isize,
+        center.1 - radius_y as isize,
+        2 * radius_x + 1,
+        2 * radius_y + 1,
+    );
+    let inset = move |row: usize| {
+        if radius_y == 0 {
+            return 0;
+        }
+        let dy = row.abs_diff(radius_y) as u64;
+        let (rx, ry) = (radius_x as u64, radius_y as u64);
+        // Half width of the line: rx * sqrt(1 - dy² / ry²).
+        let half = isqrt(rx * rx * (ry * ry - dy * dy) / (ry * ry)) as usize;
+        radius_x - half.min(radius_x)
+    };
+    (rect, inset)
+}
+
+///Inset of a line of a rounded rectangle of the given height and corner radius.
+fn corner_inset(row: usize, height: usize, radius: usize) -> usize {
+    let from_edge = row.min(height - 1 - row);
+    if from_edge >= radius {
+        return 0;
+    }
+    let dy = (radius - from_edge) as u64;
+    let r = radius as u64;
+    radius - isqrt(r * r - dy * dy) as usize
+}
+
+fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+///Color step of step count from start to end.
+pub fn blend(start: BltPixel, end: BltPixel, step: usize, count: usize) -> BltPixel {
+    let mix = |a: u8, b: u8| (a as isize + (b as isize - a as isize) * step as isize / count as isize) as u8;
+    BltPixel {
+        blue: mix(start.blue, end.blue),
+        green: mix(start.green, end.green),
+        red: mix(start.red, end.red),
+        reserved: 0,
+    }
+}
@@ -0,0 +1,496 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::{graphics_output::BltPixel, simple_text_input::InputKey},
+};
+
+use crate::{
+    protocols::console::{
+        graphic_output::{Color, Coordinate, GraphicOutput, Point, Rect},
+        text_input::ScanCode,
+        text_output::{BackgroundColor, ForegroudColor, ScreenDimension},
+    },
+    services::pool::PoolBuffer,
+};
+
+use super::{
+    canvas::BltBuffer,
+    font::{cell_size, draw_char, Font, TextStyle},
+};
+
+///The 16 colors of EFI text attributes, as rendered by VGA text mode.
+pub const PALETTE: [Color; 16] = [
+    Color::rgb(0x00, 0x00, 0x00),
+    Color::rgb(0x00, 0x00, 0xAA),
+    Color::rgb(0x00, 0xAA, 0x00),
+    Color::rgb(0x00, 0xAA, 0xAA),
+    Color::rgb(0xAA, 0x00, 0x00),
+    Color::rgb(0xAA, 0x00, 0xAA),
+    Color::rgb(0xAA, 0x55, 0x00),
+    Color::rgb(0xAA, 0xAA, 0xAA),
+    Color::rgb(0x55, 0x55, 0x55),
+    Color::rgb(0x55, 0x55, 0xFF),
+    Color::rgb(0x55, 0xFF, 0x55),
+    Color::rgb(0x55, 0xFF, 0xFF),
+    Color::rgb(0xFF, 0x55, 0x55),
+    Color::rgb(0xFF, 0x55, 0xFF),
+    Color::rgb(0xFF, 0xFF, 0x55),
+    Color::rgb(0xFF, 0xFF, 0xFF),
+];
+
+///Colors after new() and for ForegroudColor::None: light gray on black, as firmware consoles.
+const DEFAULT_FOREGROUND: Color = PALETTE[7];
+const DEFAULT_BACKGROUND: Color = PALETTE[0];
+
+///Columns a tab advances to a multiple of.
+const TAB_SIZE: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+struct Cell {
+    c: char,
+    foreground: BltPixel,
+    background: BltPixel,
+}
+
+///A text console drawn with our own font on a GraphicOutput, with 24-bit colors and a scrollback
+///buffer. Its methods follow those of TextOutput, and it renders whole runs of characters at once:
+///scrolling is done by the GOP with a video to video blt.
+pub struct Terminal<'a, F: Font> {
+    gop: &'a GraphicOutput,
+    font: F,
+    scale: usize,
+    cell: Coordinate,
+    ///The screen part used: a whole number of cells, centered.
+    area: Rect,
+    columns: usize,
+    rows: usize,
+    ///Ring of rows + scrollback lines of columns cells.
+    cells: PoolBuffer<Cell>,
+    ///Index in the ring of the first line on screen.
+    top: usize,
+    ///Lines above top that can be scrolled back to.
+    history: usize,
+    scrollback: usize,
+    ///Lines the view is scrolled back by, 0 showing the current screen.
+    view: usize,
+    ///A line of cells, rendered before being copied to the screen at once.
+    line: BltBuffer<PoolBuffer<BltPixel>>,
+    cursor: ScreenDimension,
+    cursor_visible: bool,
+    foreground: BltPixel,
+    background: BltPixel,
+}
+impl<'a, F: Font> Terminal<'a, F> {
+    ///Covers the whole screen with cells of font, each font pixel drawn as scale * scale pixels,
+    ///keeping scrollback lines which went off the top of the screen.
+    pub fn new(
+        st: *mut SystemTable,
+        gop: &'a GraphicOutput,
+        font: F,
+        scale: usize,
+        scrollback: usize,
+    ) -> Result<Terminal<'a, F>, Status> {
+        let scale = scale.max(1);
+        let cell = cell_size(&font, scale);
+        let screen = gop.screen();
+        if cell.x == 0 || cell.y == 0 {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        let (columns, rows) = (screen.width / cell.x, screen.height / cell.y);
+        if columns == 0 || rows == 0 {
+            return Err(Status::UNSUPPORTED);
+        }
+        let area = Rect::new(
+            (screen.width - columns * cell.x) / 2,
+            (screen.height - rows * cell.y) / 2,
+            columns * cell.x,
+            rows * cell.y,
+        );
+        let blank = Cell {
+            c: ' ',
+            foreground: DEFAULT_FOREGROUND.to_blt(),
+            background: DEFAULT_BACKGROUND.to_blt(),
+        };
+        let lines = rows.checked_add(scrollback).ok_or(Status::OUT_OF_RESOURCES)?;
+        let len = lines.checked_mul(columns).ok_or(Status::OUT_OF_RESOURCES)?;
+        let cells = PoolBuffer::new(st, len, blank)?;
+        let line = BltBuffer::new(
+            PoolBuffer::new(st, area.width * cell.y, blank.background)?,
+            area.width,
+            cell.y,
+        )?;
+        let mut terminal = Terminal {
+            gop,
+            font,
+            scale,
+            cell,
+            area,
+            columns,
+            rows,
+            cells,
+            top: 0,
+            history: 0,
+            scrollback,
+            view: 0,
+            line,
+            cursor: ScreenDimension { columns: 0, rows: 0 },
+            cursor_visible: true,
+            foreground: blank.foreground,
+            background: blank.background,
+        };
+        let status = terminal.clear_screen();
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        // Nothing was on the screen before.
+        terminal.history = 0;
+        Ok(terminal)
+    }
+
+    ///Number of columns and rows of the screen.
+    pub fn size(&self) -> ScreenDimension {
+        ScreenDimension {
+            columns: self.columns,
+            rows: self.rows,
+        }
+    }
+
+    ///The cursor position.
+    pub fn cursor_position(&self) -> ScreenDimension {
+        ScreenDimension {
+            columns: self.cursor.columns,
+            rows: self.cursor.rows,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.rows + self.scrollback
+    }
+
+    ///Cells of screen row, counted from the top of the current screen, negative ones being in the
+    ///scrollback.
+    fn line_cells(&mut self, row: isize) -> &mut [Cell] {
+        let capacity = self.capacity() as isize;
+        let index = (self.top as isize + row).rem_euclid(capacity) as usize;
+        &mut self.cells[index * self.columns..(index + 1) * self.columns]
+    }
+
+    ///Renders columns start..end of the line shown at screen row, the cursor included.
+    fn render(&mut self, row: usize, start: usize, end: usize) -> Status {
+        let end = end.min(self.columns);
+        if start >= end || row >= self.rows {
+            return Status::SUCCESS;
+        }
+        let cursor = self.cursor_visible && self.view == 0 && self.cursor.rows == row;
+        let (cursor_column, cell) = (self.cursor.columns, self.cell);
+        let line = row as isize - self.view as isize;
+        for column in start..end {
+            let Cell {
+                c,
+                mut foreground,
+                mut background,
+            } = self.line_cells(line)[column];
+            if cursor && column == cursor_column {
+                core::mem::swap(&mut foreground, &mut background);
+            }
+            let style = TextStyle::new(foreground, Some(background)).scaled(self.scale);
+            let origin = Point {
+                x: column * cell.x,
+                y: 0,
+            };
+            let status = draw_char(&mut self.line, &self.font, c, origin, &style);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        let source = Rect::new(start * cell.x, 0, (end - start) * cell.x, cell.y);
+        let destination = Point {
+            x: self.area.x + start * cell.x,
+            y: self.area.y + row * cell.y,
+        };
+        self.gop
+            .copy_to_screen(self.line.pixels(), source, destination, self.line.width())
+    }
+
+    ///Renders the cell under the cursor, to show or hide it.
+    fn render_cursor(&mut self) -> Status {
+        let (row, column) = (self.cursor.rows, self.cursor.columns);
+        self.render(row, column, column + 1)
+    }
+
+    ///Redraws every row of the view.
+    fn redraw(&mut self) -> Status {
+        for row in 0..self.rows {
+            let status = self.render(row, 0, self.columns);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
+
+    fn clear_line(&mut self, row: isize) {
+        let blank = Cell {
+            c: ' ',
+            foreground: self.foreground,
+            background: self.background,
+        };
+        self.line_cells(row).fill(blank);
+    }
+
+    ///Scrolls the screen up by a line, the top one going to the scrollback.
+    fn new_line(&mut self) -> Status {
+        self.top = (self.top + 1) % self.capacity();
+        self.history = (self.history + 1).min(self.scrollback);
+        let bottom = self.rows - 1;
+        self.clear_line(bottom as isize);
+        let status = self.gop.scroll(self.area, -(self.cell.y as isize));
+        if status != Status::SUCCESS {
+            return status;
+        }
+        let line = Rect::new(
+            self.area.x,
+            self.area.y + bottom * self.cell.y,
+            self.area.width,
+            self.cell.y,
+        );
+        self.gop.fill(line, self.background)
+    }
+
+    ///Moves the cursor to the next line, scrolling at the bottom of the screen.
+    fn line_feed(&mut self) -> Status {
+        if self.cursor.rows + 1 < self.rows {
+            self.cursor.rows += 1;
+            return Status::SUCCESS;
+        }
+        self.new_line()
+    }
+
+    ///Brings the view back to the current screen before output.
+    fn follow(&mut self) -> Status {
+        if self.view == 0 {
+            return Status::SUCCESS;
+        }
+        self.view = 0;
+        self.redraw()
+    }
+
+    ///Writes characters at the cursor, rendering each run of them on a line in one go. The control
+    ///characters handled are those of OutputString(): backspace, line feed and carriage return,
+    ///plus tab.
+    fn write(&mut self, chars: impl Iterator<Item = char>) -> Status {
+        let status = self.follow();
+        if status != Status::SUCCESS {
+            return status;
+        }
+        let visible = self.cursor_visible;
+        self.cursor_visible = false;
+        let mut status = self.render_cursor();
+        // Columns of the cursor line written but not rendered yet.
+        let mut pending = self.cursor.columns..self.cursor.columns;
+        for c in chars {
+            if status != Status::SUCCESS {
+                break;
+            }
+            match c {
+                '\r' => self.cursor.columns = 0,
+                '\n' => {
+                    status = self.render(self.cursor.rows, pending.start, pending.end);
+                    if status == Status::SUCCESS {
+                        status = self.line_feed();
+                    }
+                    pending = self.cursor.columns..self.cursor.columns;
+                    continue;
+                }
+                '\x08' => self.cursor.columns = self.cursor.columns.saturating_sub(1),
+                '\t' => {
+                    let stop = (self.cursor.columns / TAB_SIZE + 1) * TAB_SIZE;
+                    for _ in self.cursor.columns..stop.min(self.columns) {
+                        self.put(' ', &mut pending);
+                    }
+                }
+                c if c < ' ' => {}
+                c => {
+                    if self.cursor.columns >= self.columns {
+                        status = self.render(self.cursor.rows, pending.start, pending.end);
+                        if status == Status::SUCCESS {
+                            self.cursor.columns = 0;
+                            status = self.line_feed();
+                        }
+                        pending = 0..0;
+                    }
+                    self.put(c, &mut pending);
+                }
+            }
+            if pending.is_empty() {
+                pending = self.cursor.columns..self.cursor.columns;
+            }
+        }
+        if status == Status::SUCCESS {
+            status = self.render(self.cursor.rows, pending.start, pending.end);
+        }
+        self.cursor_visible = visible;
+        if status == Status::SUCCESS && visible {
+            status = self.render_cursor();
+        }
+        status
+    }
+
+    ///Stores a character at the cursor and moves it right, staying on the line: the wrap happens
+    ///before the next character.
+    fn put(&mut self, c: char, pending: &mut core::ops::Range<usize>) {
+        let (row, column) = (self.cursor.rows as isize, self.cursor.columns);
+        if column >= self.columns {
+            return;
+        }
+        let (foreground, background) = (self.foreground, self.background);
+        self.line_cells(row)[column] = Cell {
+            c,
+            foreground,
+            background,
+        };
+        pending.start = pending.start.min(column);
+        pending.end = pending.end.max(column + 1);
+        self.cursor.columns += 1;
+    }
+
+    ///Displays a null-terminated UCS-2 string at the cursor. See TextOutput::output_string().
+    pub fn output_string(&mut self, str: *mut u16) -> Status {
+        if str.is_null() {
+            return Status::INVALID_PARAMETER;
+        }
+        let mut len = 0;
+        while unsafe { *str.add(len) } != 0 {
+            len += 1;
+        }
+        let units = unsafe { core::slice::from_raw_parts(str, len) };
+        self.write(char::decode_utf16(units.iter().copied()).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)))
+    }
+
+    ///Displays a Rust string at the cursor.
+    pub fn output_str(&mut self, s: &str) -> Status {
+        self.write(s.chars())
+    }
+
+    ///Sets the colors of the following output from an EFI text attribute.
+    pub fn set_attribute(&mut self, foreground: ForegroudColor, background: BackgroundColor) -> Status {
+        let foreground = match foreground {
+            ForegroudColor::None => DEFAULT_FOREGROUND,
+            color => PALETTE[color as usize],
+        };
+        self.set_colors(foreground, PALETTE[background as usize >> 4])
+    }
+
+    ///Sets any 24-bit colors for the following output.
+    pub fn set_colors(&mut self, foreground: Color, background: Color) -> Status {
+        self.foreground = foreground.to_blt();
+        self.background = background.to_blt();
+        Status::SUCCESS
+    }
+
+    ///Clears the screen to the current background color and moves the cursor to (0, 0). The
+    ///screen is kept in the scrollback.
+    pub fn clear_screen(&mut self) -> Status {
+        for _ in 0..self.rows {
+            self.top = (self.top + 1) % self.capacity();
+            self.history = (self.history + 1).min(self.scrollback);
+            self.clear_line(self.rows as isize - 1);
+        }
+        self.view = 0;
+        self.cursor = ScreenDimension { columns: 0, rows: 0 };
+        let status = self.gop.fill(self.gop.screen(), self.background);
+        if status != Status::SUCCESS || !self.cursor_visible {
+            return status;
+        }
+        self.render_cursor()
+    }
+
+    ///Moves the cursor. Fails if the position is outside the screen.
+    pub fn set_cursor_position(&mut self, dimension: ScreenDimension) -> Status {
+        if dimension.columns >= self.columns || dimension.rows >= self.rows {
+            return Status::UNSUPPORTED;
+        }
+        let status = self.follow();
+        if status != Status::SUCCESS {
+            return status;
+        }
+        let visible = self.cursor_visible;
+        self.cursor_visible = false;
+        let status = self.render_cursor();
+        self.cursor = dimension;
+        self.cursor_visible = visible;
+        if status != Status::SUCCESS || !visible {
+            return status;
+        }
+        self.render_cursor()
+    }
+
+    ///Shows or hides the cursor, drawn as an inverted cell.
+    pub fn enable_cursor(&mut self, visible: bool) -> Status {
+        if visible == self.cursor_visible {
+            return Status::SUCCESS;
+        }
+        self.cursor_visible = visible;
+        self.render_cursor()
+    }
+
+    ///Scrolls the view back into the scrollback by lines, or forward when negative. Output brings
+    ///it back to the current screen.
+    pub fn scroll_view(&mut self, lines: isize) -> Status {
+        let view = (self.view as isize + lines).clamp(0, self.history as isize) as usize;
+        if view == self.view {
+            return Status::SUCCESS;
+        }
+        // The cursor must not move with the content.
+        let visible = self.cursor_visible;
+        self.cursor_visible = false;
+        let status = self.render_cursor();
+        self.cursor_visible = visible;
+        if status != Status::SUCCESS {
+            return status;
+        }
+        let distance = view.abs_diff(self.view);
+        let back = view > self.view;
+        self.view = view;
+        if distance >= self.rows {
+            return self.redraw();
+        }
+        // Content moves down when going back in the scrollback.
+        let dy = (distance * self.cell.y) as isize;
+        let status = self.gop.scroll(self.area, if back { dy } else { -dy });
+        if status != Status::SUCCESS {
+            return status;
+        }
+        let uncovered = if back {
+            0..distance
+        } else {
+            self.rows - distance..self.rows
+        };
+        for row in uncovered {
+            let status = self.render(row, 0, self.columns);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        if view == 0 && visible {
+            return self.render_cursor();
+        }
+        Status::SUCCESS
+    }
+
+    ///Scrolls the view by a screen minus a line on PageUp and PageDown. Returns true if the key was
+    ///used.
+    pub fn handle_key(&mut self, key: &InputKey) -> Result<bool, Status> {
+        let page = self.rows.saturating_sub(1).max(1) as isize;
+        let status = if key.scan_code == ScanCode::PageUp as u16 {
+            self.scroll_view(page)
+        } else if key.scan_code == ScanCode::PageDown as u16 {
+            self.scroll_view(-page)
+        } else {
+            return Ok(false);
+        };
+        match status {
+            Status::SUCCESS => Ok(true),
+            status => Err(status),
+        }
+    }
+}
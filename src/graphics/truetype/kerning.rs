@@ -0,0 +1,219 @@
+use super::{i16_at, u16_at, u32_at};
+
+use lib_efi::efi::Status;
+
+const KERN_FEATURE: u32 = u32::from_be_bytes(*b"kern");
+const PAIR_ADJUSTMENT: u16 = 2;
+const EXTENSION: u16 = 9;
+///ValueFormat bits before and including XAdvance.
+const X_PLACEMENT: u16 = 0x0001;
+const Y_PLACEMENT: u16 = 0x0002;
+const X_ADVANCE: u16 = 0x0004;
+
+///Kerning from format 0 subtables of the kern table, in font units.
+pub fn kern(table: &[u8], left: u16, right: u16) -> Option<i16> {
+    // Version 0 only: the Apple version 1 table has a different header.
+    if u16_at(table, 0).ok()? != 0 {
+        return None;
+    }
+    let mut subtable = 4;
+    for _ in 0..u16_at(table, 2).ok()? {
+        let length = u16_at(table, subtable + 2).ok()? as usize;
+        let coverage = u16_at(table, subtable + 4).ok()?;
+        // Horizontal format 0 subtables, which are not cross-stream nor minimum values.
+        if coverage >> 8 == 0 && coverage & 0x07 == 0x01 {
+            if let Ok(Some(value)) = kern_pair(table, subtable, left, right) {
+                return Some(value);
+            }
+        }
+        subtable += length;
+    }
+    None
+}
+
+fn kern_pair(table: &[u8], subtable: usize, left: u16, right: u16) -> Result<Option<i16>, Status> {
+    let pairs = u16_at(table, subtable + 6)? as usize;
+    let key = (left as u32) << 16 | right as u32;
+    let (mut low, mut high) = (0, pairs);
+    while low < high {
+        let middle = (low + high) / 2;
+        let pair = subtable + 14 + middle * 6;
+        let found = u32_at(table, pair)?;
+        if found < key {
+            low = middle + 1;
+        } else if found > key {
+            high = middle;
+        } else {
+            return Ok(Some(i16_at(table, pair + 4)?));
+        }
+    }
+    Ok(None)
+}
+
+///Kerning from the pair adjustment lookups of the GPOS kern feature, in font units. Every
+///script and language system is considered, as text is not tagged with one.
+pub fn gpos(table: &[u8], left: u16, right: u16) -> Option<i16> {
+    let features = u16_at(table, 6).ok()? as usize;
+    let lookups = u16_at(table, 8).ok()? as usize;
+    for feature in 0..u16_at(table, features).ok()? as usize {
+        let record = features + 2 + feature * 6;
+        if u32_at(table, record).ok()? != KERN_FEATURE {
+            continue;
+        }
+        let feature = features + u16_at(table, record + 4).ok()? as usize;
+        for index in 0..u16_at(table, feature + 2).ok()? as usize {
+            let lookup = u16_at(table, feature + 4 + index * 2).ok()? as usize;
+            let lookup = lookups + u16_at(table, lookups + 2 + lookup * 2).ok()? as usize;
+            if let Ok(Some(value)) = lookup_pair(table, lookup, left, right) {
+                return Some(value);
+            }
+        }
+    }
+    None
+}
+
+fn lookup_pair(table: &[u8], lookup: usize, left: u16, right: u16) -> Result<Option<i16>, Status> {
+    let kind = u16_at(table, lookup)?;
+    for subtable in 0..u16_at(table, lookup + 4)? as usize {
+        let mut offset = lookup + u16_at(table, lookup + 6 + subtable * 2)? as usize;
+        if kind == EXTENSION {
+            if u16_at(table, offset + 2)? != PAIR_ADJUSTMENT {
+                return Ok(None);
+            }
+            offset += u32_at(table, offset + 4)? as usize;
+        } else if kind != PAIR_ADJUSTMENT {
+            return Ok(None);
+        }
+        if let Some(value) = pair_adjustment(table, offset, left, right)? {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+fn value_size(format: u16) -> usize {
+    format.count_ones() as usize * 2
+}
+
+///XAdvance of the first value record, where kerning is set for left to right text.
+fn x_advance(table: &[u8], record: usize, format: u16) -> Result<Option<i16>, Status> {
+    if format & X_ADVANCE == 0 {
+        return Ok(None);
+    }
+    let skipped = value_size(format & (X_PLACEMENT | Y_PLACEMENT));
+    Ok(Some(i16_at(table, record + skipped)?))
+}
+
+fn pair_adjustment(table: &[u8], subtable: usize, left: u16, right: u16) -> Result<Option<i16>, Status> {
+    let coverage_index = match coverage(table, subtable + u16_at(table, subtable + 2)? as usize, left)? {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+    let (format1, format2) = (u16_at(table, subtable + 4)?, u16_at(table, subtable + 6)?);
+    let record_size = 2 + value_size(format1) + value_size(format2);
+    match u16_at(table, subtable)? {
+        1 => {
+            if coverage_index >= u16_at(table, subtable + 8)? as usize {
+                return Ok(None);
+            }
+            let set = subtable + u16_at(table, subtable + 10 + coverage_index * 2)? as usize;
+            let (mut low, mut high) = (0, u16_at(table, set)? as usize);
+            while low < high {
+                let middle = (low + high) / 2;
+                let record = set + 2 + middle * record_size;
+                let second = u16_at(table, record)?;
+                if second < right {
+                    low = middle + 1;
+                } else if second > right {
+                    high = middle;
+                } else {
+                    return x_advance(table, record + 2, format1);
+                }
+            }
+            Ok(None)
+        }
+        2 => {
+            let first_class = class(table, subtable + u16_at(table, subtable + 8)? as usize, left)? as usize;
+            let second_class = class(table, subtable + u16_at(table, subtable + 10)? as usize, right)? as usize;
+            let (classes1, classes2) = (
+                u16_at(table, subtable + 12)? as usize,
+                u16_at(table, subtable + 14)? as usize,
+            );
+            if first_class >= classes1 || second_class >= classes2 {
+                return Ok(None);
+            }
+            let pair_size = value_size(format1) + value_size(format2);
+            let record = subtable + 16 + (first_class * classes2 + second_class) * pair_size;
+            x_advance(table, record, format1)
+        }
+        _ => Ok(None),
+    }
+}
+
+///Index of glyph in a coverage table, if it is covered.
+fn coverage(table: &[u8], offset: usize, glyph: u16) -> Result<Option<usize>, Status> {
+    let count = u16_at(table, offset + 2)? as usize;
+    let (mut low, mut high) = (0, count);
+    match u16_at(table, offset)? {
+        1 => {
+            while low < high {
+                let middle = (low + high) / 2;
+                let found = u16_at(table, offset + 4 + middle * 2)?;
+                if found < glyph {
+                    low = middle + 1;
+                } else if found > glyph {
+                    high = middle;
+                } else {
+                    return Ok(Some(middle));
+                }
+            }
+            Ok(None)
+        }
+        2 => {
+            while low < high {
+                let middle = (low + high) / 2;
+                let range = offset + 4 + middle * 6;
+                if u16_at(table, range + 2)? < glyph {
+                    low = middle + 1;
+                } else if u16_at(table, range)? > glyph {
+                    high = middle;
+                } else {
+                    let start = u16_at(table, range)?;
+                    return Ok(Some(u16_at(table, range + 4)? as usize + (glyph - start) as usize));
+                }
+            }
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+///Class of glyph in a class definition table, 0 when not listed.
+fn class(table: &[u8], offset: usize, glyph: u16) -> Result<u16, Status> {
+    match u16_at(table, offset)? {
+        1 => {
+            let start = u16_at(table, offset + 2)?;
+            let count = u16_at(table, offset + 4)?;
+            if glyph < start || glyph - start >= count {
+                return Ok(0);
+            }
+            u16_at(table, offset + 6 + (glyph - start) as usize * 2)
+        }
+        2 => {
+            let (mut low, mut high) = (0, u16_at(table, offset + 2)? as usize);
+            while low < high {
+                let middle = (low + high) / 2;
+                let range = offset + 4 + middle * 6;
+                if u16_at(table, range + 2)? < glyph {
+                    low = middle + 1;
+                } else if u16_at(table, range)? > glyph {
+                    high = middle;
+                } else {
+                    return u16_at(table, range + 4);
+                }
+            }
+            Ok(0)
+        }
+        _ => Ok(0),
+    }
+}
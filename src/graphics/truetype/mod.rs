@@ -0,0 +1,696 @@
+use core::convert::TryFrom;
+
+use lib_efi::efi::{Status, SystemTable};
+
+use crate::{protocols::media::file::File, services::pool::PoolBuffer};
+
+pub mod kerning;
+pub mod raster;
+pub mod render;
+
+const TRUETYPE: u32 = 0x0001_0000;
+const APPLE_TRUETYPE: u32 = u32::from_be_bytes(*b"true");
+const COLLECTION: u32 = u32::from_be_bytes(*b"ttcf");
+
+///Glyph flags of simple glyphs.
+const ON_CURVE: u8 = 0x01;
+const X_SHORT: u8 = 0x02;
+const Y_SHORT: u8 = 0x04;
+const REPEAT: u8 = 0x08;
+const X_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+///Component flags of composite glyphs.
+const ARGS_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const HAS_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const HAS_X_AND_Y_SCALE: u16 = 0x0040;
+const HAS_TWO_BY_TWO: u16 = 0x0080;
+
+///Nesting allowed for composite glyphs, which could otherwise refer to themselves.
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, Status> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or(Status::COMPROMISED_DATA)
+}
+
+fn i16_at(data: &[u8], offset: usize) -> Result<i16, Status> {
+    u16_at(data, offset).map(|value| value as i16)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, Status> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or(Status::COMPROMISED_DATA)
+}
+
+///A point of an outline, in font units with y going up.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+}
+impl Vector {
+    fn midpoint(self, other: Vector) -> Vector {
+        Vector {
+            x: (self.x + other.x) / 2.0,
+            y: (self.y + other.y) / 2.0,
+        }
+    }
+}
+
+///A piece of a glyph outline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+    Line(Vector, Vector),
+    ///A quadratic Bézier curve: start, control point and end.
+    Quad(Vector, Vector, Vector),
+}
+
+///Affine transform of a composite glyph component.
+#[derive(Clone, Copy)]
+struct Transform {
+    xx: f32,
+    xy: f32,
+    yx: f32,
+    yy: f32,
+    dx: f32,
+    dy: f32,
+}
+impl Transform {
+    const IDENTITY: Transform = Transform {
+        xx: 1.0,
+        xy: 0.0,
+        yx: 0.0,
+        yy: 1.0,
+        dx: 0.0,
+        dy: 0.0,
+    };
+
+    fn apply(&self, x: f32, y: f32) -> Vector {
+        Vector {
+            x: self.xx * x + self.yx * y + self.dx,
+            y: self.xy * x + self.yy * y + self.dy,
+        }
+    }
+
+    ///The transform applying inner, then self.
+    fn then(&self, inner: &Transform) -> Transform {
+        let origin = self.apply(inner.dx, inner.dy);
+        Transform {
+            xx: self.xx * inner.xx + self.yx * inner.xy,
+            xy: self.xy * inner.xx + self.yy * inner.xy,
+            yx: self.xx * inner.yx + self.yx * inner.yy,
+            yy: self.xy * inner.yx + self.yy * inner.yy,
+            dx: origin.x,
+            dy: origin.y,
+        }
+    }
+}
+
+///Turns the on and off-curve points of a contour into segments, adding the implied on-curve
+///points between two off-curve ones.
+struct Contour<'s, S: FnMut(Segment)> {
+    sink: &'s mut S,
+    start: Option<Vector>,
+    ///An off-curve point the contour began with, to be used when closing it.
+    first_control: Option<Vector>,
+    last: Vector,
+    control: Option<Vector>,
+}
+impl<'s, S: FnMut(Segment)> Contour<'s, S> {
+    fn new(sink: &'s mut S) -> Contour<'s, S> {
+        Contour {
+            sink,
+            start: None,
+            first_control: None,
+            last: Vector::default(),
+            control: None,
+        }
+    }
+
+    fn push(&mut self, point: Vector, on_curve: bool) {
+        if self.start.is_none() {
+            match (on_curve, self.first_control) {
+                (true, _) => {
+                    self.start = Some(point);
+                    self.last = point;
+                }
+                (false, Some(first)) => {
+                    let start = first.midpoint(point);
+                    self.start = Some(start);
+                    self.last = start;
+                    self.control = Some(point);
+                }
+                (false, None) => self.first_control = Some(point),
+            }
+            return;
+        }
+        match (on_curve, self.control) {
+            (true, Some(control)) => (self.sink)(Segment::Quad(self.last, control, point)),
+            (true, None) => (self.sink)(Segment::Line(self.last, point)),
+            (false, Some(control)) => {
+                let middle = control.midpoint(point);
+                (self.sink)(Segment::Quad(self.last, control, middle));
+                self.last = middle;
+            }
+            (false, None) => {}
+        }
+        if on_curve {
+            self.last = point;
+            self.control = None;
+        } else {
+            self.control = Some(point);
+        }
+    }
+
+    fn close(mut self) {
+        let start = match self.start {
+            Some(start) => start,
+            None => return,
+        };
+        if let Some(first) = self.first_control.take() {
+            self.push(first, false);
+        }
+        match self.control {
+            Some(control) => (self.sink)(Segment::Quad(self.last, control, start)),
+            None if self.last != start => (self.sink)(Segment::Line(self.last, start)),
+            None => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Table {
+    offset: usize,
+    length: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CmapFormat {
+    ///Segments of 16-bit code points.
+    Segments,
+    ///Groups of 32-bit code points.
+    Groups,
+}
+
+///Vertical metrics, in font units.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerticalMetrics {
+    ///Height above the baseline.
+    pub ascender: i16,
+    ///Depth below the baseline, negative.
+    pub descender: i16,
+    pub line_gap: i16,
+}
+
+///A TrueType font, or the first font of a collection. Only glyf outlines are supported: OpenType
+///fonts with CFF outlines are rejected.
+pub struct TrueTypeFont<D: AsRef<[u8]>> {
+    data: D,
+    units_per_em: u16,
+    glyph_count: u16,
+    long_offsets: bool,
+    metrics: VerticalMetrics,
+    long_metrics: u16,
+    cmap: usize,
+    cmap_format: CmapFormat,
+    loca: Table,
+    glyf: Table,
+    hmtx: Table,
+    kern: Option<Table>,
+    gpos: Option<Table>,
+}
+impl<D: AsRef<[u8]>> TrueTypeFont<D> {
+    ///Parses a font from its file content.
+    pub fn parse(data: D) -> Result<TrueTypeFont<D>, Status> {
+        TrueTypeFont::parse_collection(data, 0)
+    }
+
+    ///Parses font number index of a TrueType collection (.ttc), or a single font if index is 0.
+    pub fn parse_collection(data: D, index: usize) -> Result<TrueTypeFont<D>, Status> {
+        let bytes = data.as_ref();
+        let start = match u32_at(bytes, 0)? {
+            COLLECTION => {
+                if index >= u32_at(bytes, 8)? as usize {
+                    return Err(Status::NOT_FOUND);
+                }
+                u32_at(bytes, 12 + index * 4)? as usize
+            }
+            _ if index > 0 => return Err(Status::NOT_FOUND),
+            _ => 0,
+        };
+        match u32_at(bytes, start)? {
+            TRUETYPE | APPLE_TRUETYPE => {}
+            _ => return Err(Status::UNSUPPORTED),
+        }
+        let find = |name: &[u8; 4]| -> Result<Option<Table>, Status> {
+            let tag = u32::from_be_bytes(*name);
+            for record in 0..u16_at(bytes, start + 4)? as usize {
+                let record = start + 12 + record * 16;
+                if u32_at(bytes, record)? != tag {
+                    continue;
+                }
+                let table = Table {
+                    offset: u32_at(bytes, record + 8)? as usize,
+                    length: u32_at(bytes, record + 12)? as usize,
+                };
+                return match table.offset.checked_add(table.length) {
+                    Some(end) if end <= bytes.len() => Ok(Some(table)),
+                    _ => Err(Status::COMPROMISED_DATA),
+                };
+            }
+            Ok(None)
+        };
+        let require = |name: &[u8; 4]| find(name)?.ok_or(Status::UNSUPPORTED);
+        let (head, maxp, hhea) = (require(b"head")?, require(b"maxp")?, require(b"hhea")?);
+        let (cmap_table, loca, glyf, hmtx) = (
+            require(b"cmap")?,
+            require(b"loca")?,
+            require(b"glyf")?,
+            require(b"hmtx")?,
+        );
+        let units_per_em = u16_at(bytes, head.offset + 18)?;
+        if units_per_em == 0 {
+            return Err(Status::COMPROMISED_DATA);
+        }
+        let (cmap, cmap_format) = TrueTypeFont::<D>::find_cmap(bytes, cmap_table.offset)?;
+        Ok(TrueTypeFont {
+            units_per_em,
+            glyph_count: u16_at(bytes, maxp.offset + 4)?,
+            long_offsets: i16_at(bytes, head.offset + 50)? != 0,
+            metrics: VerticalMetrics {
+                ascender: i16_at(bytes, hhea.offset + 4)?,
+                descender: i16_at(bytes, hhea.offset + 6)?,
+                line_gap: i16_at(bytes, hhea.offset + 8)?,
+            },
+            long_metrics: u16_at(bytes, hhea.offset + 34)?.max(1),
+            cmap,
+            cmap_format,
+            loca,
+            glyf,
+            hmtx,
+            kern: find(b"kern")?,
+            gpos: find(b"GPOS")?,
+            data,
+        })
+    }
+
+    ///Picks the Unicode subtable of cmap: a full repertoire one if there is one, else a BMP one.
+    fn find_cmap(bytes: &[u8], cmap: usize) -> Result<(usize, CmapFormat), Status> {
+        let mut best = None;
+        for record in 0..u16_at(bytes, cmap + 2)? as usize {
+            let record = cmap + 4 + record * 8;
+            let (platform, encoding) = (u16_at(bytes, record)?, u16_at(bytes, record + 2)?);
+            let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+            if !unicode {
+                continue;
+            }
+            let subtable = cmap + u32_at(bytes, record + 4)? as usize;
+            match u16_at(bytes, subtable)? {
+                12 => return Ok((subtable, CmapFormat::Groups)),
+                4 => best = Some((subtable, CmapFormat::Segments)),
+                _ => {}
+            }
+        }
+        best.ok_or(Status::UNSUPPORTED)
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    pub fn glyph_count(&self) -> u16 {
+        self.glyph_count
+    }
+
+    pub fn vertical_metrics(&self) -> VerticalMetrics {
+        self.metrics
+    }
+
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    ///Pixels per font unit at size pixels per em.
+    pub fn scale(&self, size: usize) -> f32 {
+        size as f32 / self.units_per_em as f32
+    }
+
+    ///Glyph of a character, 0 being the missing glyph.
+    pub fn glyph_index(&self, c: char) -> u16 {
+        let code = c as u32;
+        let bytes = self.bytes();
+        let found = match self.cmap_format {
+            CmapFormat::Segments => self.segment_glyph(bytes, code),
+            CmapFormat::Groups => self.group_glyph(bytes, code),
+        };
+        found
+            .ok()
+            .flatten()
+            .filter(|&glyph| glyph < self.glyph_count)
+            .unwrap_or(0)
+    }
+
+    fn segment_glyph(&self, bytes: &[u8], code: u32) -> Result<Option<u16>, Status> {
+        if code > 0xFFFF {
+            return Ok(None);
+        }
+        let code = code as u16;
+        let segments = u16_at(bytes, self.cmap + 6)? as usize / 2;
+        let ends = self.cmap + 14;
+        let starts = ends + segments * 2 + 2;
+        let deltas = starts + segments * 2;
+        let ranges = deltas + segments * 2;
+        // The first segment ending at or after code.
+        let (mut low, mut high) = (0, segments);
+        while low < high {
+            let middle = (low + high) / 2;
+            if u16_at(bytes, ends + middle * 2)? < code {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        if low == segments || u16_at(bytes, starts + low * 2)? > code {
+            return Ok(None);
+        }
+        let start = u16_at(bytes, starts + low * 2)?;
+        let delta = u16_at(bytes, deltas + low * 2)?;
+        let range = u16_at(bytes, ranges + low * 2)? as usize;
+        if range == 0 {
+            return Ok(Some(code.wrapping_add(delta)));
+        }
+        // The offset is relative to its own position in the idRangeOffset array.
+        let glyph = u16_at(bytes, ranges + low * 2 + range + (code - start) as usize * 2)?;
+        Ok(if glyph == 0 {
+            None
+        } else {
+            Some(glyph.wrapping_add(delta))
+        })
+    }
+
+    fn group_glyph(&self, bytes: &[u8], code: u32) -> Result<Option<u16>, Status> {
+        let groups = u32_at(bytes, self.cmap + 12)? as usize;
+        let (mut low, mut high) = (0, groups);
+        while low < high {
+            let middle = (low + high) / 2;
+            let group = self.cmap + 16 + middle * 12;
+            if u32_at(bytes, group + 4)? < code {
+                low = middle + 1;
+            } else if u32_at(bytes, group)? > code {
+                high = middle;
+            } else {
+                // A start glyph near u32::MAX in a corrupt font maps to no glyph.
+                let glyph = u32_at(bytes, group + 8)?.checked_add(code - u32_at(bytes, group)?);
+                return Ok(glyph.and_then(|glyph| u16::try_from(glyph).ok()));
+            }
+        }
+        Ok(None)
+    }
+
+    ///Advance width and left side bearing of a glyph, in font units.
+    pub fn horizontal_metrics(&self, glyph: u16) -> (u16, i16) {
+        let bytes = self.bytes();
+        let long = self.long_metrics as usize;
+        let (glyph, hmtx) = (glyph as usize, self.hmtx.offset);
+        let metrics = if glyph < long {
+            u16_at(bytes, hmtx + glyph * 4).and_then(|advance| Ok((advance, i16_at(bytes, hmtx + glyph * 4 + 2)?)))
+        } else {
+            // Monospaced runs at the end share the last advance.
+            u16_at(bytes, hmtx + (long - 1) * 4)
+                .and_then(|advance| Ok((advance, i16_at(bytes, hmtx + long * 4 + (glyph - long) * 2)?)))
+        };
+        metrics.unwrap_or((0, 0))
+    }
+
+    ///Kerning between two glyphs, in font units: from the GPOS kern feature, else the kern table.
+    pub fn kerning(&self, left: u16, right: u16) -> i16 {
+        let bytes = self.bytes();
+        if let Some(gpos) = self.gpos {
+            if let Some(value) = kerning::gpos(&bytes[gpos.offset..gpos.offset + gpos.length], left, right) {
+                return value;
+            }
+        }
+        self.kern
+            .and_then(|kern| kerning::kern(&bytes[kern.offset..kern.offset + kern.length], left, right))
+            .unwrap_or(0)
+    }
+
+    ///Outline data of a glyph, empty for glyphs without one such as the space.
+    fn glyph_data(&self, glyph: u16) -> Result<&[u8], Status> {
+        if glyph >= self.glyph_count {
+            return Err(Status::NOT_FOUND);
+        }
+        let (bytes, glyph) = (self.bytes(), glyph as usize);
+        let (start, end) = if self.long_offsets {
+            let at = |index: usize| u32_at(bytes, self.loca.offset + index * 4).map(|offset| offset as usize);
+            (at(glyph)?, at(glyph + 1)?)
+        } else {
+            let at = |index: usize| u16_at(bytes, self.loca.offset + index * 2).map(|offset| offset as usize * 2);
+            (at(glyph)?, at(glyph + 1)?)
+        };
+        if start > end || end > self.glyf.length {
+            return Err(Status::COMPROMISED_DATA);
+        }
+        Ok(&bytes[self.glyf.offset + start..self.glyf.offset + end])
+    }
+
+    ///Bounding box of a glyph in font units: x min, y min, x max and y max. None if it is empty.
+    pub fn bounding_box(&self, glyph: u16) -> Option<(i16, i16, i16, i16)> {
+        let data = self.glyph_data(glyph).ok().filter(|data| data.len() >= 10)?;
+        let at = |offset: usize| i16_at(data, offset).unwrap_or(0);
+        Some((at(2), at(4), at(6), at(8)))
+    }
+
+    ///Calls sink with each segment of the outline of a glyph, in font units.
+    pub fn outline(&self, glyph: u16, sink: &mut impl FnMut(Segment)) -> Status {
+        match self.outline_with(glyph, &Transform::IDENTITY, sink, 0) {
+            Ok(()) => Status::SUCCESS,
+            Err(status) => status,
+        }
+    }
+
+    fn outline_with(
+        &self,
+        glyph: u16,
+        transform: &Transform,
+        sink: &mut impl FnMut(Segment),
+        depth: usize,
+    ) -> Result<(), Status> {
+        let data = self.glyph_data(glyph)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        match i16_at(data, 0)? {
+            contours if contours >= 0 => simple_outline(data, contours as usize, transform, sink),
+            _ if depth >= MAX_COMPONENT_DEPTH => Err(Status::COMPROMISED_DATA),
+            _ => self.composite_outline(data, transform, sink, depth),
+        }
+    }
+
+    fn composite_outline(
+        &self,
+        data: &[u8],
+        transform: &Transform,
+        sink: &mut impl FnMut(Segment),
+        depth: usize,
+    ) -> Result<(), Status> {
+        let f2dot14 = |offset: usize| i16_at(data, offset).map(|value| value as f32 / 16384.0);
+        let mut position = 10;
+        loop {
+            let flags = u16_at(data, position)?;
+            let glyph = u16_at(data, position + 2)?;
+            position += 4;
+            let (dx, dy) = if flags & ARGS_ARE_WORDS != 0 {
+                position += 4;
+                (i16_at(data, position - 4)? as f32, i16_at(data, position - 2)? as f32)
+            } else {
+                position += 2;
+                let byte = |offset: usize| {
+                    data.get(offset)
+                        .map(|&b| b as i8 as f32)
+                        .ok_or(Status::COMPROMISED_DATA)
+                };
+                (byte(position - 2)?, byte(position - 1)?)
+            };
+            // Components aligned by point numbers are rare: they are drawn unmoved.
+            let (dx, dy) = if flags & ARGS_ARE_XY_VALUES != 0 {
+                (dx, dy)
+            } else {
+                (0.0, 0.0)
+            };
+            let mut component = Transform {
+                dx,
+                dy,
+                ..Transform::IDENTITY
+            };
+            if flags & HAS_SCALE != 0 {
+                component.xx = f2dot14(position)?;
+                component.yy = component.xx;
+                position += 2;
+            } else if flags & HAS_X_AND_Y_SCALE != 0 {
+                component.xx = f2dot14(position)?;
+                component.yy = f2dot14(position + 2)?;
+                position += 4;
+            } else if flags & HAS_TWO_BY_TWO != 0 {
+                component.xx = f2dot14(position)?;
+                component.xy = f2dot14(position + 2)?;
+                component.yx = f2dot14(position + 4)?;
+                component.yy = f2dot14(position + 6)?;
+                position += 8;
+            }
+            self.outline_with(glyph, &transform.then(&component), sink, depth + 1)?;
+            if flags & MORE_COMPONENTS == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    ///Positions the glyphs of a line of text at size pixels per em, with kerning. Scripts are
+    ///shaped left to right, one glyph per character: there are no ligatures nor contextual forms.
+    pub fn layout<'t>(&'t self, text: &'t str, size: usize) -> Layout<'t, D> {
+        Layout {
+            font: self,
+            chars: text.char_indices(),
+            scale: self.scale(size),
+            pen: 0.0,
+            previous: None,
+        }
+    }
+}
+
+impl TrueTypeFont<PoolBuffer<u8>> {
+    ///Reads a font file.
+    pub fn load(st: *mut SystemTable, file: &File) -> Result<TrueTypeFont<PoolBuffer<u8>>, Status> {
+        TrueTypeFont::parse(PoolBuffer::read_file(st, file)?)
+    }
+}
+
+///Reads the points of a simple glyph, which are stored as three arrays of flags, x and y deltas,
+///walking the three at once.
+fn simple_outline(
+    data: &[u8],
+    contours: usize,
+    transform: &Transform,
+    sink: &mut impl FnMut(Segment),
+) -> Result<(), Status> {
+    let ends = 10;
+    if contours == 0 {
+        return Ok(());
+    }
+    let points = u16_at(data, ends + (contours - 1) * 2)? as usize + 1;
+    let instructions = u16_at(data, ends + contours * 2)? as usize;
+    let flags_start = ends + contours * 2 + 2 + instructions;
+    // First pass over the flags to find where the coordinates start.
+    let (mut position, mut count, mut x_size) = (flags_start, 0, 0);
+    while count < points {
+        let flag = *data.get(position).ok_or(Status::COMPROMISED_DATA)?;
+        let mut repeat = 1;
+        position += 1;
+        if flag & REPEAT != 0 {
+            repeat += *data.get(position).ok_or(Status::COMPROMISED_DATA)? as usize;
+            position += 1;
+        }
+        if flag & X_SHORT != 0 {
+            x_size += repeat;
+        } else if flag & X_SAME_OR_POSITIVE == 0 {
+            x_size += repeat * 2;
+        }
+        count += repeat;
+    }
+    let (mut flag_at, mut x_at, mut y_at) = (flags_start, position, position + x_size);
+    let (mut flag, mut repeat) = (0, 0);
+    let (mut x, mut y) = (0i32, 0i32);
+    let mut point = 0;
+    for contour in 0..contours {
+        let end = u16_at(data, ends + contour * 2)? as usize;
+        if end >= points || end + 1 < point {
+            return Err(Status::COMPROMISED_DATA);
+        }
+        let mut builder = Contour::new(sink);
+        while point <= end {
+            if repeat == 0 {
+                flag = *data.get(flag_at).ok_or(Status::COMPROMISED_DATA)?;
+                flag_at += 1;
+                repeat = 1;
+                if flag & REPEAT != 0 {
+                    repeat += *data.get(flag_at).ok_or(Status::COMPROMISED_DATA)? as usize;
+                    flag_at += 1;
+                }
+            }
+            repeat -= 1;
+            x += delta(data, &mut x_at, flag, X_SHORT, X_SAME_OR_POSITIVE)?;
+            y += delta(data, &mut y_at, flag, Y_SHORT, Y_SAME_OR_POSITIVE)?;
+            builder.push(transform.apply(x as f32, y as f32), flag & ON_CURVE != 0);
+            point += 1;
+        }
+        builder.close();
+    }
+    Ok(())
+}
+
+///Reads a coordinate delta: a byte with a sign flag, a repeat of the previous value or a word.
+fn delta(data: &[u8], position: &mut usize, flag: u8, short: u8, same_or_positive: u8) -> Result<i32, Status> {
+    if flag & short != 0 {
+        let value = *data.get(*position).ok_or(Status::COMPROMISED_DATA)? as i32;
+        *position += 1;
+        Ok(if flag & same_or_positive != 0 { value } else { -value })
+    } else if flag & same_or_positive != 0 {
+        Ok(0)
+    } else {
+        *position += 2;
+        Ok(i16_at(data, *position - 2)? as i32)
+    }
+}
+
+///A glyph placed by TrueTypeFont::layout().
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PositionedGlyph {
+    pub c: char,
+    ///Byte offset of the character in the text.
+    pub index: usize,
+    pub glyph: u16,
+    ///Pen position before and after the glyph, in pixels from the start of the line.
+    pub x: isize,
+    pub end: isize,
+}
+
+///Iterator returned by TrueTypeFont::layout(). Control characters are skipped.
+pub struct Layout<'t, D: AsRef<[u8]>> {
+    font: &'t TrueTypeFont<D>,
+    chars: core::str::CharIndices<'t>,
+    scale: f32,
+    ///Pen position in font units.
+    pen: f32,
+    previous: Option<u16>,
+}
+
+impl<D: AsRef<[u8]>> Iterator for Layout<'_, D> {
+    type Item = PositionedGlyph;
+
+    fn next(&mut self) -> Option<PositionedGlyph> {
+        let (index, c) = self.chars.find(|&(_, c)| !c.is_control())?;
+        let glyph = self.font.glyph_index(c);
+        if let Some(previous) = self.previous {
+            self.pen += self.font.kerning(previous, glyph) as f32;
+        }
+        self.previous = Some(glyph);
+        let x = raster::round(self.pen * self.scale);
+        self.pen += self.font.horizontal_metrics(glyph).0 as f32;
+        Some(PositionedGlyph {
+            c,
+            index,
+            glyph,
+            x,
+            end: raster::round(self.pen * self.scale),
+        })
+    }
+}
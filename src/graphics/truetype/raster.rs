@@ -0,0 +1,162 @@
+use core::convert::TryFrom;
+
+use lib_efi::efi::{Status, SystemTable};
+
+use crate::services::pool::PoolBuffer;
+
+use super::Vector;
+
+///Accumulation cells past the end, written by lines ending on the last column.
+const SLACK: usize = 4;
+///Flatness of curves: higher values split them in more lines.
+const CURVE_TOLERANCE: f32 = 3.0;
+const MAX_CURVE_LINES: usize = 16;
+
+pub fn floor(x: f32) -> f32 {
+    let truncated = x as i32 as f32;
+    if truncated > x {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+pub fn ceil(x: f32) -> f32 {
+    -floor(-x)
+}
+
+pub fn round(x: f32) -> isize {
+    floor(x + 0.5) as isize
+}
+
+fn abs(x: f32) -> f32 {
+    if x < 0.0 {
+        -x
+    } else {
+        x
+    }
+}
+
+///Computes the antialiased coverage of outlines by accumulating the signed area each line covers
+///in each pixel, then summing along the lines of pixels. Points are in pixels, y going down.
+pub struct Rasterizer {
+    width: usize,
+    height: usize,
+    accumulation: PoolBuffer<f32>,
+}
+impl Rasterizer {
+    pub fn new(st: *mut SystemTable, width: usize, height: usize) -> Result<Rasterizer, Status> {
+        let len = width.checked_mul(height).ok_or(Status::OUT_OF_RESOURCES)?;
+        Ok(Rasterizer {
+            width,
+            height,
+            accumulation: PoolBuffer::new(st, len + SLACK, 0.0)?,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn add(&mut self, index: isize, value: f32) {
+        if let Some(cell) = usize::try_from(index)
+            .ok()
+            .and_then(|index| self.accumulation.get_mut(index))
+        {
+            *cell += value;
+        }
+    }
+
+    pub fn line(&mut self, p0: Vector, p1: Vector) {
+        if p0.y == p1.y {
+            return;
+        }
+        let (direction, p0, p1) = if p0.y < p1.y { (1.0, p0, p1) } else { (-1.0, p1, p0) };
+        let dxdy = (p1.x - p0.x) / (p1.y - p0.y);
+        let mut x = p0.x;
+        let top = if p0.y < 0.0 {
+            x -= p0.y * dxdy;
+            0
+        } else {
+            p0.y as usize
+        };
+        let bottom = (ceil(p1.y) as usize).min(self.height);
+        for y in top..bottom {
+            let line_start = (y * self.width) as isize;
+            let dy = (y as f32 + 1.0).min(p1.y) - (y as f32).max(p0.y);
+            let x_next = x + dxdy * dy;
+            let d = dy * direction;
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let x0_floor = floor(x0);
+            let x0i = x0_floor as isize;
+            let x1_ceil = ceil(x1);
+            let x1i = x1_ceil as isize;
+            if x1i <= x0i + 1 {
+                // The line stays within one pixel of this line.
+                let middle = 0.5 * (x + x_next) - x0_floor;
+                self.add(line_start + x0i, d - d * middle);
+                self.add(line_start + x0i + 1, d * middle);
+            } else {
+                let s = 1.0 / (x1 - x0);
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+                self.add(line_start + x0i, d * a0);
+                if x1i == x0i + 2 {
+                    self.add(line_start + x0i + 1, d * (1.0 - a0 - am));
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.add(line_start + x0i + 1, d * (a1 - a0));
+                    for xi in x0i + 2..x1i - 1 {
+                        self.add(line_start + xi, d * s);
+                    }
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    self.add(line_start + x1i - 1, d * (1.0 - a2 - am));
+                }
+                self.add(line_start + x1i, d * am);
+            }
+            x = x_next;
+        }
+    }
+
+    ///Draws a quadratic curve as lines, more of them the more it bends.
+    pub fn quad(&mut self, p0: Vector, p1: Vector, p2: Vector) {
+        let (dx, dy) = (p0.x - 2.0 * p1.x + p2.x, p0.y - 2.0 * p1.y + p2.y);
+        let deviation = CURVE_TOLERANCE * (dx * dx + dy * dy);
+        // One more line each time the fourth power of their number reaches the deviation.
+        let mut lines = 1;
+        while lines < MAX_CURVE_LINES && ((lines * lines * lines * lines) as f32) < deviation {
+            lines += 1;
+        }
+        let mut previous = p0;
+        for step in 1..=lines {
+            let t = step as f32 / lines as f32;
+            let u = 1.0 - t;
+            let point = Vector {
+                x: u * u * p0.x + 2.0 * u * t * p1.x + t * t * p2.x,
+                y: u * u * p0.y + 2.0 * u * t * p1.y + t * t * p2.y,
+            };
+            self.line(previous, point);
+            previous = point;
+        }
+    }
+
+    ///Writes the coverage of each pixel, 255 being fully covered, to coverage, which must hold
+    ///width * height values.
+    pub fn coverage(&self, coverage: &mut [u8]) {
+        let mut sum = 0.0;
+        for (value, &area) in coverage
+            .iter_mut()
+            .zip(self.accumulation.iter())
+            .take(self.width * self.height)
+        {
+            sum += area;
+            *value = (abs(sum).min(1.0) * 255.0 + 0.5) as u8;
+        }
+    }
+}
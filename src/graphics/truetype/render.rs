@@ -0,0 +1,287 @@
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::graphics_output::BltPixel,
+};
+
+use crate::{
+    graphics::canvas::{BltBuffer, Canvas},
+    protocols::console::graphic_output::{Color, Coordinate, Point, Rect},
+    services::pool::PoolBuffer,
+};
+
+use super::{
+    raster::{ceil, floor, round, Rasterizer},
+    Segment, TrueTypeFont, Vector,
+};
+
+///Glyphs kept by a GlyphCache.
+const CACHE_SIZE: usize = 128;
+
+///The antialiased coverage of a glyph at some size.
+pub struct GlyphBitmap {
+    ///Position of the top left pixel relative to the pen on the baseline.
+    pub left: isize,
+    pub top: isize,
+    pub width: usize,
+    pub height: usize,
+    coverage: PoolBuffer<u8>,
+}
+impl GlyphBitmap {
+    ///Rasterizes a glyph at size pixels per em. Returns None for glyphs without an outline.
+    pub fn rasterize<D: AsRef<[u8]>>(
+        st: *mut SystemTable,
+        font: &TrueTypeFont<D>,
+        glyph: u16,
+        size: usize,
+    ) -> Result<Option<GlyphBitmap>, Status> {
+        let (x_min, y_min, x_max, y_max) = match font.bounding_box(glyph) {
+            Some(bounds) => bounds,
+            None => return Ok(None),
+        };
+        let scale = font.scale(size);
+        // A pixel of margin on each side, for antialiasing and rounding.
+        let left = floor(x_min as f32 * scale) as isize - 1;
+        let top = floor(-y_max as f32 * scale) as isize - 1;
+        let width = (ceil(x_max as f32 * scale) as isize + 1 - left).max(0) as usize;
+        let height = (ceil(-y_min as f32 * scale) as isize + 1 - top).max(0) as usize;
+        if width == 0 || height == 0 {
+            return Ok(None);
+        }
+        let mut rasterizer = Rasterizer::new(st, width, height)?;
+        let to_pixels = |point: Vector| Vector {
+            x: point.x * scale - left as f32,
+            y: -point.y * scale - top as f32,
+        };
+        let status = font.outline(glyph, &mut |segment| match segment {
+            Segment::Line(p0, p1) => rasterizer.line(to_pixels(p0), to_pixels(p1)),
+            Segment::Quad(p0, p1, p2) => rasterizer.quad(to_pixels(p0), to_pixels(p1), to_pixels(p2)),
+        });
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        let mut coverage = PoolBuffer::new(st, width * height, 0u8)?;
+        rasterizer.coverage(&mut coverage);
+        Ok(Some(GlyphBitmap {
+            left,
+            top,
+            width,
+            height,
+            coverage,
+        }))
+    }
+
+    ///Coverage of each pixel, line by line, 255 being fully covered.
+    pub fn coverage(&self) -> &[u8] {
+        &self.coverage
+    }
+}
+
+struct CacheEntry {
+    glyph: u16,
+    size: usize,
+    bitmap: Option<GlyphBitmap>,
+}
+
+///Glyphs of one font rasterized recently, so text is not rasterized again each time it is drawn.
+///Each glyph and size has one slot, used by the last one rasterized.
+pub struct GlyphCache {
+    st: *mut SystemTable,
+    entries: [Option<CacheEntry>; CACHE_SIZE],
+}
+impl GlyphCache {
+    pub fn new(st: *mut SystemTable) -> GlyphCache {
+        GlyphCache {
+            st,
+            entries: core::array::from_fn(|_| None),
+        }
+    }
+
+    ///Returns the bitmap of a glyph of font, rasterizing it if needed.
+    pub fn get<D: AsRef<[u8]>>(
+        &mut self,
+        font: &TrueTypeFont<D>,
+        glyph: u16,
+        size: usize,
+    ) -> Result<Option<&GlyphBitmap>, Status> {
+        let slot = (glyph as usize).wrapping_mul(31).wrapping_add(size) % CACHE_SIZE;
+        let cached = matches!(&self.entries[slot], Some(entry) if entry.glyph == glyph && entry.size == size);
+        if !cached {
+            let bitmap = GlyphBitmap::rasterize(self.st, font, glyph, size)?;
+            self.entries[slot] = Some(CacheEntry { glyph, size, bitmap });
+        }
+        Ok(self.entries[slot].as_ref().and_then(|entry| entry.bitmap.as_ref()))
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+    }
+}
+
+///Blends color over pixel with the given coverage. The reserved byte grows as an alpha channel,
+///so text drawn on a transparent layer of a Compositor shows.
+fn blend(pixel: &mut BltPixel, color: Color, coverage: u8) {
+    let alpha = coverage as u32;
+    let over = |under: u8, color: u8| ((color as u32 * alpha + under as u32 * (255 - alpha) + 127) / 255) as u8;
+    *pixel = BltPixel {
+        blue: over(pixel.blue, color.blue),
+        green: over(pixel.green, color.green),
+        red: over(pixel.red, color.red),
+        reserved: pixel.reserved.max(coverage),
+    };
+}
+
+///CJK ideographs and kana: lines may break between any two of them.
+fn breaks_anywhere(c: char) -> bool {
+    matches!(c, '\u{2E80}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FFEF}' | '\u{20000}'..='\u{3FFFF}')
+}
+
+///Splits the first line of text fitting in max_width pixels: returns where it ends and where
+///the next one starts. Lines break after spaces and around CJK characters, or anywhere when
+///a word is too long. At least one character is kept.
+fn fit<D: AsRef<[u8]>>(font: &TrueTypeFont<D>, text: &str, size: usize, max_width: usize) -> (usize, usize) {
+    if let Some(newline) = text.find('\n') {
+        let (end, next) = fit(font, &text[..newline], size, max_width);
+        return if end == newline {
+            (end, newline + 1)
+        } else {
+            (end, next)
+        };
+    }
+    // measure() passes usize::MAX for unwrapped text.
+    let max_width = max_width.min(isize::MAX as usize) as isize;
+    let mut last_break = None;
+    for glyph in font.layout(text, size) {
+        if glyph.c == ' ' {
+            last_break = Some((glyph.index, glyph.index + 1));
+            continue;
+        }
+        if breaks_anywhere(glyph.c) && glyph.index > 0 {
+            last_break = Some((glyph.index, glyph.index));
+        }
+        if glyph.end > max_width && glyph.index > 0 {
+            return last_break.unwrap_or((glyph.index, glyph.index));
+        }
+    }
+    (text.len(), text.len())
+}
+
+///Calls f with each line of text wrapped to max_width, until it returns false.
+fn for_each_line<D: AsRef<[u8]>>(
+    font: &TrueTypeFont<D>,
+    text: &str,
+    size: usize,
+    max_width: usize,
+    mut f: impl FnMut(&str) -> bool,
+) {
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (end, next) = fit(font, rest, size, max_width);
+        if !f(rest[..end].trim_end_matches('\r')) {
+            return;
+        }
+        rest = &rest[next..];
+    }
+}
+
+///A TrueType font with its glyph cache, drawing antialiased text into off-screen buffers.
+pub struct TextRenderer<D: AsRef<[u8]>> {
+    font: TrueTypeFont<D>,
+    cache: GlyphCache,
+}
+impl<D: AsRef<[u8]>> TextRenderer<D> {
+    pub fn new(st: *mut SystemTable, font: TrueTypeFont<D>) -> TextRenderer<D> {
+        TextRenderer {
+            font,
+            cache: GlyphCache::new(st),
+        }
+    }
+
+    pub fn font(&self) -> &TrueTypeFont<D> {
+        &self.font
+    }
+
+    ///Distance between two baselines at size pixels per em.
+    pub fn line_height(&self, size: usize) -> usize {
+        let metrics = self.font.vertical_metrics();
+        let units = metrics.ascender as i32 - metrics.descender as i32 + metrics.line_gap as i32;
+        round(units.max(0) as f32 * self.font.scale(size)).max(1) as usize
+    }
+
+    ///Height of the baseline below the top of a line.
+    fn baseline(&self, size: usize) -> isize {
+        round(self.font.vertical_metrics().ascender as f32 * self.font.scale(size))
+    }
+
+    ///Size of text drawn with draw_text() in an area max_width pixels wide, or unwrapped.
+    pub fn measure(&self, text: &str, size: usize, max_width: Option<usize>) -> Coordinate {
+        let line_height = self.line_height(size);
+        let (mut width, mut height) = (0, 0);
+        for_each_line(&self.font, text, size, max_width.unwrap_or(usize::MAX), |line| {
+            let end = self.font.layout(line, size).last().map_or(0, |glyph| glyph.end);
+            width = width.max(end.max(0) as usize);
+            height += line_height;
+            true
+        });
+        Coordinate { x: width, y: height }
+    }
+
+    ///Draws text at size pixels per em, wrapped to the width of area, blending it into target.
+    ///Lines which do not fit in the height of area are dropped. Returns the position following the
+    ///last character drawn, at the top of its line.
+    pub fn draw_text<S: AsRef<[BltPixel]> + AsMut<[BltPixel]>>(
+        &mut self,
+        target: &mut BltBuffer<S>,
+        text: &str,
+        area: Rect,
+        size: usize,
+        color: Color,
+    ) -> Result<Point, Status> {
+        let area = match target.bounds().intersection(&area) {
+            Some(area) => area,
+            None => return Ok(area.origin()),
+        };
+        let (line_height, baseline) = (self.line_height(size), self.baseline(size));
+        let (font, cache) = (&self.font, &mut self.cache);
+        let width = target.width();
+        let pixels = target.pixels_mut();
+        let mut end = area.origin();
+        let mut y = area.y;
+        let mut result = Ok(());
+        for_each_line(font, text, size, area.width, |line| {
+            if y + line_height > area.bottom() {
+                return false;
+            }
+            let mut x = area.x;
+            for glyph in font.layout(line, size) {
+                x = area.x + glyph.end.max(0) as usize;
+                let bitmap = match cache.get(font, glyph.glyph, size) {
+                    Ok(Some(bitmap)) => bitmap,
+                    Ok(None) => continue,
+                    Err(status) => {
+                        result = Err(status);
+                        return false;
+                    }
+                };
+                let origin_x = area.x as isize + glyph.x + bitmap.left;
+                let origin_y = y as isize + baseline + bitmap.top;
+                for (row, coverage) in bitmap.coverage().chunks_exact(bitmap.width).enumerate() {
+                    let py = origin_y + row as isize;
+                    if py < area.y as isize || py >= area.bottom() as isize {
+                        continue;
+                    }
+                    for (column, &coverage) in coverage.iter().enumerate() {
+                        let px = origin_x + column as isize;
+                        if coverage == 0 || px < area.x as isize || px >= area.right() as isize {
+                            continue;
+                        }
+                        blend(&mut pixels[py as usize * width + px as usize], color, coverage);
+                    }
+                }
+            }
+            end = Point { x, y };
+            y += line_height;
+            true
+        });
+        result.map(|()| end)
+    }
+}
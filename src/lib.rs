@@ -1,8 +1,18 @@
 #![no_std]
 
+extern crate bitflags;
+#[cfg(feature = "embedded-graphics")]
+extern crate embedded_graphics_core;
 extern crate lib_efi;
+extern crate log;
+#[cfg(test)]
+extern crate std;
+pub mod graphics;
 pub mod protocols;
+pub mod services;
 
 pub mod uefi_helper {
+    pub use crate::graphics;
     pub use crate::protocols;
+    pub use crate::services;
 }
\ No newline at end of file
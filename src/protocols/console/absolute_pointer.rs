@@ -3,6 +3,19 @@ use lib_efi::{
     protocols::absolute_pointer::{Mode, Protocol, State, PROTOCOL_GUID}
 };
 
+///Bits of EFI_ABSOLUTE_POINTER_MODE.Attributes.
+pub enum Attribute {
+    ///The device has an alternate button in addition to the touch-active status.
+    AltActive         = 0x01,
+    ///The Z axis reports pressure instead of a position.
+    PressureSupported = 0x02,
+}
+
+///Bits of EFI_ABSOLUTE_POINTER_STATE.ActiveButtons.
+pub enum ActiveButton {
+    TouchActive = 0x01,
+    AltActive   = 0x02,
+}
 
 pub struct AbsolutePointer {
     protocol: *mut Protocol,
@@ -44,12 +57,24 @@ impl AbsolutePointer {
         }
     }
 
-    pub fn mode(&self) -> *mut Mode {
-        unsafe { (*self.protocol).mode }
+    ///Copy of the EFI_ABSOLUTE_POINTER_MODE data: the minimum and maximum value of each axis and the device attributes.
+    pub fn mode(&self) -> Mode {
+        unsafe { *(*self.protocol).mode }
     }
-
+    ///Returns true if the device reports the given attribute in its mode.
+    pub fn supports(&self, attribute: Attribute) -> bool {
+        self.mode().attributes & attribute as u32 != 0
+    }
+    ///Event to use with EFI_BOOT_SERVICES.WaitForEvent() to wait for input from the pointer device.
     pub fn wait_for_input(&self) -> Event {
         unsafe { (*self.protocol).wait_for_input }
     }
     
 }
+
+impl ActiveButton {
+    ///Returns true if this button is pressed in the given state.
+    pub fn is_pressed(self, state: &State) -> bool {
+        state.active_buttons & self as u32 != 0
+    }
+}
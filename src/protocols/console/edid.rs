@@ -0,0 +1,176 @@
+use lib_efi::efi::{Guid, Handle, Status, SystemTable};
+
+use crate::services::handle::handle_protocol;
+
+///EDID of the display currently driven, installed on the GOP handle.
+pub const ACTIVE_PROTOCOL_GUID: Guid = Guid::from_fields(
+    0xbd8c1056,
+    0x9f36,
+    0x44ec,
+    0x92,
+    0xa8,
+    &[0xa6, 0x33, 0x7f, 0x81, 0x79, 0x86],
+);
+
+///EDID read from the display, before any override by the platform.
+pub const DISCOVERED_PROTOCOL_GUID: Guid = Guid::from_fields(
+    0x1c0c34f6,
+    0xd380,
+    0x41fa,
+    0xa0,
+    0x49,
+    &[0x8a, 0xd0, 0x6c, 0x1a, 0x66, 0xaa],
+);
+
+///EFI_EDID_ACTIVE_PROTOCOL and EFI_EDID_DISCOVERED_PROTOCOL share this layout.
+#[repr(C)]
+pub struct Protocol {
+    pub size_of_edid: u32,
+    pub edid: *mut u8,
+}
+
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const BLOCK_SIZE: usize = 128;
+const DESCRIPTORS: usize = 54;
+
+///A detailed timing descriptor: one video mode of the display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DetailedTiming {
+    pub pixel_clock_khz: u32,
+    pub width: u32,
+    pub height: u32,
+    pub horizontal_blanking: u32,
+    pub vertical_blanking: u32,
+    ///Image size in millimeters.
+    pub width_mm: u32,
+    pub height_mm: u32,
+    pub interlaced: bool,
+}
+impl DetailedTiming {
+    fn parse(bytes: &[u8]) -> Option<DetailedTiming> {
+        let clock = u16::from_le_bytes([bytes[0], bytes[1]]) as u32;
+        // A zero pixel clock marks a display descriptor (name, serial number, ranges...).
+        if clock == 0 {
+            return None;
+        }
+        let high = |byte: u8, shift: u32| ((byte >> shift) as u32 & 0x0F) << 8;
+        Some(DetailedTiming {
+            pixel_clock_khz: clock * 10,
+            width: bytes[2] as u32 | high(bytes[4], 4),
+            horizontal_blanking: bytes[3] as u32 | high(bytes[4], 0),
+            height: bytes[5] as u32 | high(bytes[7], 4),
+            vertical_blanking: bytes[6] as u32 | high(bytes[7], 0),
+            width_mm: bytes[12] as u32 | high(bytes[14], 4),
+            height_mm: bytes[13] as u32 | high(bytes[14], 0),
+            interlaced: bytes[17] & 0x80 != 0,
+        })
+    }
+
+    ///Refresh rate in millihertz.
+    pub fn refresh_mhz(&self) -> u32 {
+        let total = (self.width + self.horizontal_blanking) as u64 * (self.height + self.vertical_blanking) as u64;
+        if total == 0 {
+            return 0;
+        }
+        (self.pixel_clock_khz as u64 * 1_000_000 / total) as u32
+    }
+}
+
+///Extended Display Identification Data: the base block, then any extension blocks.
+#[derive(Clone, Copy, Debug)]
+pub struct Edid<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> Edid<'a> {
+    ///Checks the header and the checksum of the base block.
+    pub fn parse(bytes: &'a [u8]) -> Result<Edid<'a>, Status> {
+        if bytes.len() < BLOCK_SIZE || bytes[..8] != HEADER {
+            return Err(Status::UNSUPPORTED);
+        }
+        let sum = bytes[..BLOCK_SIZE].iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+        if sum != 0 {
+            return Err(Status::CRC_ERROR);
+        }
+        Ok(Edid { bytes })
+    }
+
+    ///EDID of the display driven by the first GOP found.
+    pub fn active(st: *mut SystemTable) -> Result<Edid<'static>, Status> {
+        let mut protocol: *mut Protocol = core::ptr::null_mut();
+        let mut guid = ACTIVE_PROTOCOL_GUID;
+        let boot_services = unsafe { (*st).boot_services };
+        let status = unsafe {
+            ((*boot_services).locate_protocol)(
+                &mut guid,
+                core::ptr::null_mut(),
+                &mut protocol as *mut *mut Protocol as *mut _,
+            )
+        };
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        unsafe { Edid::from_protocol(protocol) }
+    }
+
+    ///EDID of the display driven by a GOP handle: the active one, else the discovered one.
+    pub fn for_handle(st: *mut SystemTable, handle: Handle) -> Result<Edid<'static>, Status> {
+        let protocol = handle_protocol::<Protocol>(st, handle, ACTIVE_PROTOCOL_GUID)
+            .or_else(|_| handle_protocol::<Protocol>(st, handle, DISCOVERED_PROTOCOL_GUID))?;
+        unsafe { Edid::from_protocol(protocol) }
+    }
+
+    ///# Safety
+    ///protocol must point to an EDID protocol instance, which stays installed while in use.
+    pub unsafe fn from_protocol(protocol: *const Protocol) -> Result<Edid<'static>, Status> {
+        let (size, edid) = ((*protocol).size_of_edid as usize, (*protocol).edid);
+        if size == 0 || edid.is_null() {
+            return Err(Status::NOT_FOUND);
+        }
+        Edid::parse(core::slice::from_raw_parts(edid, size))
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    ///EDID version and revision, such as (1, 4).
+    pub fn version(&self) -> (u8, u8) {
+        (self.bytes[18], self.bytes[19])
+    }
+
+    ///Three letter PNP ID of the manufacturer.
+    pub fn manufacturer(&self) -> [u8; 3] {
+        let id = u16::from_be_bytes([self.bytes[8], self.bytes[9]]);
+        let letter = |shift: u16| b'@' + ((id >> shift) & 0x1F) as u8;
+        [letter(10), letter(5), letter(0)]
+    }
+
+    pub fn product_code(&self) -> u16 {
+        u16::from_le_bytes([self.bytes[10], self.bytes[11]])
+    }
+
+    ///Size of the screen in centimeters, if known.
+    pub fn screen_size_cm(&self) -> Option<(u8, u8)> {
+        match (self.bytes[21], self.bytes[22]) {
+            (0, _) | (_, 0) => None,
+            size => Some(size),
+        }
+    }
+
+    ///The detailed timings of the base block, the preferred one first.
+    pub fn detailed_timings(&self) -> impl Iterator<Item = DetailedTiming> + 'a {
+        self.bytes[DESCRIPTORS..DESCRIPTORS + 4 * 18]
+            .chunks_exact(18)
+            .filter_map(DetailedTiming::parse)
+    }
+
+    ///The timing the display works best with, normally its native resolution.
+    pub fn preferred_timing(&self) -> Option<DetailedTiming> {
+        self.detailed_timings().next()
+    }
+
+    ///Width and height of the preferred timing.
+    pub fn native_resolution(&self) -> Option<(u32, u32)> {
+        self.preferred_timing().map(|timing| (timing.width, timing.height))
+    }
+}
@@ -0,0 +1,238 @@
+use lib_efi::{
+    efi::Status,
+    protocols::graphics_output::{
+        BltPixel, ModeInformation, PIXEL_BIT_MASK, PIXEL_BLT_ONLY,
+        PIXEL_BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR, PIXEL_RED_GREEN_BLUE_RESERVED_8_BIT_PER_COLOR,
+    },
+};
+
+use super::graphic_output::{Color, GraphicOutput, Point, Rect};
+
+///How a pixel is laid out in a 32-bit framebuffer word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    ///Byte 0 is red, 1 green, 2 blue.
+    Rgb,
+    ///Byte 0 is blue, 1 green, 2 red.
+    Bgr,
+    ///Channels defined by bit masks.
+    Bitmask { red: u32, green: u32, blue: u32 },
+    ///No linear framebuffer: only Blt() can draw.
+    BltOnly,
+}
+impl PixelFormat {
+    pub fn from_info(info: &ModeInformation) -> PixelFormat {
+        match info.pixel_format {
+            PIXEL_RED_GREEN_BLUE_RESERVED_8_BIT_PER_COLOR => PixelFormat::Rgb,
+            PIXEL_BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR => PixelFormat::Bgr,
+            PIXEL_BIT_MASK => PixelFormat::Bitmask {
+                red: info.pixel_information.red_mask,
+                green: info.pixel_information.green_mask,
+                blue: info.pixel_information.blue_mask,
+            },
+            _ => PixelFormat::BltOnly,
+        }
+    }
+
+    ///Encodes a color as a framebuffer word.
+    pub fn encode(&self, color: Color) -> u32 {
+        match self {
+            PixelFormat::Rgb => u32::from_le_bytes([color.red, color.green, color.blue, 0]),
+            PixelFormat::Bgr | PixelFormat::BltOnly => u32::from_le_bytes([color.blue, color.green, color.red, 0]),
+            PixelFormat::Bitmask { red, green, blue } => {
+                pack(color.red, *red) | pack(color.green, *green) | pack(color.blue, *blue)
+            }
+        }
+    }
+
+    ///Decodes a framebuffer word.
+    pub fn decode(&self, pixel: u32) -> Color {
+        match self {
+            PixelFormat::Rgb => {
+                let [red, green, blue, _] = pixel.to_le_bytes();
+                Color::rgb(red, green, blue)
+            }
+            PixelFormat::Bgr | PixelFormat::BltOnly => {
+                let [blue, green, red, _] = pixel.to_le_bytes();
+                Color::rgb(red, green, blue)
+            }
+            PixelFormat::Bitmask { red, green, blue } => {
+                Color::rgb(unpack(pixel, *red), unpack(pixel, *green), unpack(pixel, *blue))
+            }
+        }
+    }
+}
+
+///Scales an 8-bit channel to the width of mask and moves it into place.
+fn pack(value: u8, mask: u32) -> u32 {
+    if mask == 0 {
+        return 0;
+    }
+    let max = mask >> mask.trailing_zeros();
+    (((value as u64 * max as u64 + 127) / 255) as u32) << mask.trailing_zeros()
+}
+
+fn unpack(pixel: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let max = mask >> mask.trailing_zeros();
+    (((pixel & mask) >> mask.trailing_zeros()) as u64 * 255 / max as u64) as u8
+}
+
+///Framebuffer description to hand over to a kernel. It stays valid after ExitBootServices(),
+///as long as the mode is not changed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FramebufferInfo {
+    pub base: u64,
+    pub size: u64,
+    pub width: u32,
+    pub height: u32,
+    ///Pixels per scan line, which can be more than width.
+    pub stride: u32,
+    ///EFI_GRAPHICS_PIXEL_FORMAT value.
+    pub pixel_format: u32,
+    pub red_mask: u32,
+    pub green_mask: u32,
+    pub blue_mask: u32,
+    pub reserved_mask: u32,
+}
+
+///Direct access to the linear framebuffer of the current mode, falling back to Blt() when there
+///is none. Create it again after GraphicOutput::set_mode().
+pub struct Framebuffer<'a> {
+    gop: &'a GraphicOutput,
+    info: FramebufferInfo,
+    format: PixelFormat,
+}
+impl<'a> Framebuffer<'a> {
+    pub fn new(gop: &'a GraphicOutput) -> Framebuffer<'a> {
+        let mode = gop.mode();
+        let info = gop.info();
+        let masks = match info.pixel_format {
+            PIXEL_RED_GREEN_BLUE_RESERVED_8_BIT_PER_COLOR => [0x0000_00FF, 0x0000_FF00, 0x00FF_0000, 0xFF00_0000],
+            PIXEL_BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000],
+            PIXEL_BIT_MASK => [
+                info.pixel_information.red_mask,
+                info.pixel_information.green_mask,
+                info.pixel_information.blue_mask,
+                info.pixel_information.reserved_mask,
+            ],
+            _ => [0; 4],
+        };
+        let blt_only = info.pixel_format == PIXEL_BLT_ONLY;
+        Framebuffer {
+            gop,
+            info: FramebufferInfo {
+                base: if blt_only { 0 } else { mode.frame_buffer_base },
+                size: if blt_only { 0 } else { mode.frame_buffer_size as u64 },
+                width: info.horizontal_resolution,
+                height: info.vertical_resolution,
+                stride: info.pixels_per_scan_line,
+                pixel_format: info.pixel_format,
+                red_mask: masks[0],
+                green_mask: masks[1],
+                blue_mask: masks[2],
+                reserved_mask: masks[3],
+            },
+            format: PixelFormat::from_info(&info),
+        }
+    }
+
+    pub fn info(&self) -> FramebufferInfo {
+        self.info
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    pub fn width(&self) -> usize {
+        self.info.width as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.info.height as usize
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width(), self.height())
+    }
+
+    ///Returns true if pixels are written to memory directly rather than with Blt().
+    pub fn is_direct(&self) -> bool {
+        self.format != PixelFormat::BltOnly && self.info.base != 0
+    }
+
+    fn pixel_pointer(&self, x: usize, y: usize) -> *mut u32 {
+        (self.info.base as *mut u32).wrapping_add(y * self.info.stride as usize + x)
+    }
+
+    pub fn set_pixel(&self, x: usize, y: usize, color: Color) -> Status {
+        self.fill(Rect::new(x, y, 1, 1), color)
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Result<Color, Status> {
+        if !self.bounds().contains(Point { x, y }) {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        if !self.is_direct() {
+            let mut pixel = [Color::BLACK.to_blt()];
+            return match self.gop.copy_from_screen(&mut pixel, Rect::new(x, y, 1, 1)) {
+                Status::SUCCESS => Ok(Color::from_blt(pixel[0])),
+                status => Err(status),
+            };
+        }
+        Ok(self.format.decode(unsafe { self.pixel_pointer(x, y).read_volatile() }))
+    }
+
+    pub fn fill(&self, rect: Rect, color: Color) -> Status {
+        if !self.bounds().contains_rect(&rect) {
+            return Status::INVALID_PARAMETER;
+        }
+        if !self.is_direct() {
+            return self.gop.fill(rect, color.to_blt());
+        }
+        let pixel = self.format.encode(color);
+        for y in rect.y..rect.bottom() {
+            for x in rect.x..rect.right() {
+                unsafe { self.pixel_pointer(x, y).write_volatile(pixel) };
+            }
+        }
+        Status::SUCCESS
+    }
+
+    ///Writes rect.width * rect.height colors, line by line, into rect.
+    pub fn write_pixels(&self, rect: Rect, colors: &[Color]) -> Status {
+        const CHUNK: usize = 256;
+        if !self.bounds().contains_rect(&rect) || colors.len() < rect.width * rect.height {
+            return Status::INVALID_PARAMETER;
+        }
+        for (row, line) in colors.chunks(rect.width.max(1)).take(rect.height).enumerate() {
+            let y = rect.y + row;
+            if self.is_direct() {
+                for (column, &color) in line.iter().enumerate() {
+                    unsafe { self.pixel_pointer(rect.x + column, y).write_volatile(self.format.encode(color)) };
+                }
+                continue;
+            }
+            let mut buffer = [BltPixel { blue: 0, green: 0, red: 0, reserved: 0 }; CHUNK];
+            for (chunk_index, chunk) in line.chunks(CHUNK).enumerate() {
+                for (pixel, &color) in buffer.iter_mut().zip(chunk) {
+                    *pixel = color.to_blt();
+                }
+                let destination = Point {
+                    x: rect.x + chunk_index * CHUNK,
+                    y,
+                };
+                let status =
+                    self.gop.copy_to_screen(&buffer, Rect::new(0, 0, chunk.len(), 1), destination, CHUNK);
+                if status != Status::SUCCESS {
+                    return status;
+                }
+            }
+        }
+        Status::SUCCESS
+    }
+}
@@ -1,16 +1,244 @@
+use core::mem::size_of;
+
 use lib_efi::{
-    efi::{Status, SystemTable},
-    protocols::graphics_output::{BltOperation, BltPixel, Mode, ModeInformation, Protocol, PROTOCOL_GUID},
+    efi::{Handle, Status, SystemTable},
+    protocols::graphics_output::{
+        BltOperation, BltPixel, Mode, ModeInformation, Protocol, BLT_BUFFER_TO_VIDEO, BLT_VIDEO_FILL,
+        BLT_VIDEO_TO_BLT_BUFFER, BLT_VIDEO_TO_VIDEO, PROTOCOL_GUID,
+    },
+};
+
+use crate::{
+    graphics::image::bmp,
+    protocols::{
+        device_path::DevicePath,
+        media::file::{Attribute, File, OpenMode},
+    },
+    services::{
+        handle::{handle_protocol, HandleBuffer},
+        pool::PoolBuffer,
+        variable::{Variable, GLOBAL_VARIABLE_GUID, NON_VOLATILE_BOOT_ACCESS, VENDOR_GUID},
+    },
 };
 
+use super::edid::Edid;
+
+///Name of the NVRAM variable holding the resolution chosen by set_best_mode().
+pub const MODE_VARIABLE: &str = "GraphicsMode";
+
+///Largest ConOut variable read by GraphicOutput::displays().
+const CONSOLE_OUT_SIZE: usize = 4096;
+
+///Pixels read from the screen at once by screenshot().
+const SCREENSHOT_CHUNK: usize = 512;
+
 #[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct Coordinate {
     pub x: usize,
     pub y: usize,
 }
 
+pub type Point = Coordinate;
+
+///A 24-bit color, independent of the pixel format of the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    pub const fn to_blt(self) -> BltPixel {
+        BltPixel {
+            blue: self.blue,
+            green: self.green,
+            red: self.red,
+            reserved: 0,
+        }
+    }
+
+    pub const fn from_blt(pixel: BltPixel) -> Color {
+        Color::rgb(pixel.red, pixel.green, pixel.blue)
+    }
+}
+
+impl From<BltPixel> for Color {
+    fn from(pixel: BltPixel) -> Color {
+        Color::from_blt(pixel)
+    }
+}
+
+impl From<Color> for BltPixel {
+    fn from(color: Color) -> BltPixel {
+        color.to_blt()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+impl Rect {
+    pub const fn new(x: usize, y: usize, width: usize, height: usize) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    pub fn from_points(origin: Point, size: Coordinate) -> Rect {
+        Rect::new(origin.x, origin.y, size.x, size.y)
+    }
+
+    pub fn origin(&self) -> Point {
+        Point { x: self.x, y: self.y }
+    }
+
+    pub fn size(&self) -> Coordinate {
+        Coordinate {
+            x: self.width,
+            y: self.height,
+        }
+    }
+
+    ///First column right of the rectangle.
+    pub fn right(&self) -> usize {
+        self.x.saturating_add(self.width)
+    }
+
+    ///First line below the rectangle.
+    pub fn bottom(&self) -> usize {
+        self.y.saturating_add(self.height)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.y >= self.y && point.x < self.right() && point.y < self.bottom()
+    }
+
+    ///Returns true if other lies entirely inside this rectangle.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+
+    ///The overlapping part of two rectangles, if any.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right <= x || bottom <= y {
+            return None;
+        }
+        Some(Rect::new(x, y, right - x, bottom - y))
+    }
+
+    ///The smallest rectangle holding both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+    }
+}
+
+///Returns true if rect lies inside a buffer of len pixels with stride pixels per line.
+fn fits_buffer(len: usize, rect: &Rect, stride: usize) -> bool {
+    if rect.is_empty() {
+        return true;
+    }
+    rect.right() <= stride && (rect.bottom() - 1).saturating_mul(stride).saturating_add(rect.right()) <= len
+}
+
+///Iterator over the modes of a GraphicOutput, as (mode number, information).
+pub struct Modes<'a> {
+    gop: &'a GraphicOutput,
+    next: u32,
+    max: u32,
+}
+
+impl Iterator for Modes<'_> {
+    type Item = (u32, ModeInformation);
+
+    fn next(&mut self) -> Option<(u32, ModeInformation)> {
+        while self.next < self.max {
+            let mode = self.next;
+            self.next += 1;
+            // Skip modes which cannot be queried, such as ones the display does not support.
+            if let Ok(info) = self.gop.query_mode(mode) {
+                return Some((mode, info));
+            }
+        }
+        None
+    }
+}
+
+///How to choose a graphics mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModePolicy {
+    ///The preferred timing of the display from its EDID, else the closest aspect ratio to it, else
+    ///MaxArea.
+    Native,
+    ///The mode with the most pixels.
+    MaxArea,
+    Exact { width: u32, height: u32 },
+    ///The largest mode among those whose aspect ratio is the closest to width:height.
+    ClosestAspect { width: u32, height: u32 },
+}
+
+fn resolution(info: &ModeInformation) -> (u32, u32) {
+    (info.horizontal_resolution, info.vertical_resolution)
+}
+
+fn area(info: &ModeInformation) -> u64 {
+    info.horizontal_resolution as u64 * info.vertical_resolution as u64
+}
+
+///Distance between the aspect ratio of a mode and width:height, as a fraction to compare.
+fn aspect_distance(info: &ModeInformation, width: u32, height: u32) -> (u64, u64) {
+    let (w, h) = (info.horizontal_resolution as u64, info.vertical_resolution as u64);
+    ((w * height as u64).abs_diff(h * width as u64), h * height as u64)
+}
+
+fn load_resolution(variable: &Variable) -> Result<(u32, u32), Status> {
+    let mut bytes = [0u8; 8];
+    match variable.get(MODE_VARIABLE, VENDOR_GUID, &mut bytes)? {
+        (8, _) => Ok((
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        )),
+        _ => Err(Status::VOLUME_CORRUPTED),
+    }
+}
+
+///Resolutions are saved rather than mode numbers, which change with the display.
+fn save_resolution(variable: &Variable, (width, height): (u32, u32)) -> Status {
+    let mut bytes = [0u8; 8];
+    bytes[..4].copy_from_slice(&width.to_le_bytes());
+    bytes[4..].copy_from_slice(&height.to_le_bytes());
+    variable.set(MODE_VARIABLE, VENDOR_GUID, NON_VOLATILE_BOOT_ACCESS, &bytes)
+}
+
 pub struct GraphicOutput {
     protocol: *mut Protocol,
+    ///The handle the protocol was opened on, if known: its EDID is then the one of this display.
+    handle: Option<Handle>,
 }
 impl GraphicOutput {
     pub fn new(st: *mut SystemTable) -> Result<GraphicOutput, Status> {
@@ -28,11 +256,38 @@ impl GraphicOutput {
         if status == Status::SUCCESS {
             Ok(GraphicOutput {
                 protocol,
+                handle: None,
             })
         } else {
             Err(status)
         }
     }
+
+    ///Opens the GOP installed on a specific handle.
+    pub fn for_handle(st: *mut SystemTable, handle: Handle) -> Result<GraphicOutput, Status> {
+        let protocol = handle_protocol::<Protocol>(st, handle, PROTOCOL_GUID)?;
+        Ok(GraphicOutput {
+            protocol,
+            handle: Some(handle),
+        })
+    }
+
+    ///Lists every GOP instance, in handle database order, noting which ones ConOut is sent to.
+    pub fn displays(st: *mut SystemTable) -> Result<Displays, Status> {
+        let handles = HandleBuffer::by_protocol(st, PROTOCOL_GUID)?;
+        let mut console_out = PoolBuffer::new(st, CONSOLE_OUT_SIZE, 0u8)?;
+        // Without ConOut, only the console handle itself is known to be the console.
+        let console_out_len = Variable::new(st)
+            .get("ConOut", GLOBAL_VARIABLE_GUID, &mut console_out)
+            .map_or(0, |(len, _)| len);
+        Ok(Displays {
+            st,
+            handles,
+            console_out,
+            console_out_len,
+        })
+    }
+
     ///Returns information for an available graphics mode that the graphics device and the set of active video output devices supports.
     pub fn query_mode(&self, mode_number: u32) -> Result<ModeInformation, Status> {
         let mut size_of_info: usize = 0;
@@ -55,41 +310,349 @@ impl GraphicOutput {
             Err(r)
         }
     }
+    ///Iterates over every mode supported, from 0 to max_mode.
+    pub fn modes(&self) -> Modes<'_> {
+        Modes {
+            gop: self,
+            next: 0,
+            max: self.mode().max_mode,
+        }
+    }
+
+    ///Chooses a mode according to policy. Returns None if no mode matches.
+    pub fn find_mode(&self, st: *mut SystemTable, policy: ModePolicy) -> Option<(u32, ModeInformation)> {
+        match policy {
+            ModePolicy::Native => {
+                let edid = match self.handle {
+                    Some(handle) => Edid::for_handle(st, handle),
+                    None => Edid::active(st),
+                };
+                let native = edid.ok().and_then(|edid| edid.native_resolution());
+                match native {
+                    Some((width, height)) => self
+                        .find_mode(st, ModePolicy::Exact { width, height })
+                        .or_else(|| self.find_mode(st, ModePolicy::ClosestAspect { width, height })),
+                    None => self.find_mode(st, ModePolicy::MaxArea),
+                }
+            }
+            // The first of equal modes wins.
+            ModePolicy::MaxArea => self
+                .modes()
+                .fold(None, |best: Option<(u32, ModeInformation)>, (mode, info)| match best {
+                    Some((_, best_info)) if area(&best_info) >= area(&info) => best,
+                    _ => Some((mode, info)),
+                }),
+            ModePolicy::Exact { width, height } => self.modes().find(|(_, info)| resolution(info) == (width, height)),
+            ModePolicy::ClosestAspect { width, height } => {
+                if width == 0 || height == 0 {
+                    return None;
+                }
+                self.modes().fold(None, |best: Option<(u32, ModeInformation)>, (mode, info)| {
+                    let best_info = match best {
+                        Some((_, best_info)) => best_info,
+                        None => return Some((mode, info)),
+                    };
+                    let (distance, scale) = aspect_distance(&info, width, height);
+                    let (best_distance, best_scale) = aspect_distance(&best_info, width, height);
+                    let (this, other) = (distance as u128 * best_scale as u128, best_distance as u128 * scale as u128);
+                    if this < other || (this == other && area(&info) > area(&best_info)) {
+                        Some((mode, info))
+                    } else {
+                        best
+                    }
+                })
+            }
+        }
+    }
+
+    ///Switches to the resolution saved in NVRAM if the display still supports it, else to the mode
+    ///chosen by policy, which is then saved. Returns the mode number set.
+    pub fn set_best_mode(&self, st: *mut SystemTable, policy: ModePolicy) -> Result<u32, Status> {
+        let variable = Variable::new(st);
+        let saved = load_resolution(&variable)
+            .ok()
+            .and_then(|(width, height)| self.find_mode(st, ModePolicy::Exact { width, height }));
+        let (mode, info) = match saved {
+            Some(found) => found,
+            None => self.find_mode(st, policy).ok_or(Status::NOT_FOUND)?,
+        };
+        if mode != self.mode().mode {
+            let status = self.set_mode(mode);
+            if status != Status::SUCCESS {
+                return Err(status);
+            }
+        }
+        if saved.is_none() {
+            let status = save_resolution(&variable, resolution(&info));
+            if status != Status::SUCCESS {
+                return Err(status);
+            }
+        }
+        Ok(mode)
+    }
+
+    ///Stores the resolution of the current mode, to be restored by set_best_mode() on next boot.
+    pub fn save_mode(&self, variable: &Variable) -> Status {
+        save_resolution(variable, resolution(&self.info()))
+    }
+
     /// Set the video device into the specified mode and clears the visible portions of the output display to black.
     pub fn set_mode(&self, mode_number: u32) -> Status {
         unsafe { ((*self.protocol).set_mode)(self.protocol, mode_number) }
     }
     ///Blt a rectangle of pixels on the graphics screen. Blt stands for BLock Transfer.
-    pub fn blt(&self,
+    ///# Safety
+    ///blt_buffer must hold the rectangle the operation reads or writes, with delta bytes per line
+    ///(or a single pixel for BLT_VIDEO_FILL). The safe methods below check this for you.
+    pub unsafe fn blt(
+        &self,
         blt_buffer: *mut BltPixel,
         blt_operation: BltOperation,
-        // source_x: usize,
-        // source_y: usize,
-        source: *const Coordinate,
-        // destination_x: usize,
-        // destination_y: usize,
-        destination: *const Coordinate,
-        // width: usize,
-        // height: usize,
-        dimension: *const Coordinate,
+        source: Point,
+        destination: Point,
+        dimension: Coordinate,
         delta: usize,
     ) -> Status {
-        unsafe { ((*self.protocol).blt)(
+        ((*self.protocol).blt)(
             self.protocol,
             blt_buffer,
             blt_operation,
-            (*source).x,
-            (*source).y,
-            (*destination).x,
-            (*destination).y,
-            (*dimension).x,
-            (*dimension).y,
+            source.x,
+            source.y,
+            destination.x,
+            destination.y,
+            dimension.x,
+            dimension.y,
             delta,
-            ) }
+        )
+    }
+
+    ///The visible area of the current mode.
+    pub fn screen(&self) -> Rect {
+        let info = self.info();
+        Rect::new(0, 0, info.horizontal_resolution as usize, info.vertical_resolution as usize)
+    }
+
+    ///Fills a rectangle of the screen with one color.
+    pub fn fill(&self, rect: Rect, color: BltPixel) -> Status {
+        if !self.screen().contains_rect(&rect) {
+            return Status::INVALID_PARAMETER;
+        }
+        if rect.is_empty() {
+            return Status::SUCCESS;
+        }
+        let mut pixel = color;
+        unsafe { self.blt(&mut pixel, BLT_VIDEO_FILL, Point::default(), rect.origin(), rect.size(), 0) }
+    }
+
+    ///Copies the source rectangle of a buffer of stride pixels per line to the screen at destination.
+    pub fn copy_to_screen(&self, buffer: &[BltPixel], source: Rect, destination: Point, stride: usize) -> Status {
+        let target = Rect::new(destination.x, destination.y, source.width, source.height);
+        if !fits_buffer(buffer.len(), &source, stride) || !self.screen().contains_rect(&target) {
+            return Status::INVALID_PARAMETER;
+        }
+        if source.is_empty() {
+            return Status::SUCCESS;
+        }
+        unsafe {
+            self.blt(
+                buffer.as_ptr() as *mut BltPixel,
+                BLT_BUFFER_TO_VIDEO,
+                source.origin(),
+                destination,
+                source.size(),
+                stride * size_of::<BltPixel>(),
+            )
+        }
+    }
+
+    ///Copies a rectangle of the screen into buffer, packed at rect.width pixels per line.
+    pub fn copy_from_screen(&self, buffer: &mut [BltPixel], rect: Rect) -> Status {
+        let packed = Rect::new(0, 0, rect.width, rect.height);
+        if !fits_buffer(buffer.len(), &packed, rect.width) || !self.screen().contains_rect(&rect) {
+            return Status::INVALID_PARAMETER;
+        }
+        if rect.is_empty() {
+            return Status::SUCCESS;
+        }
+        unsafe {
+            self.blt(
+                buffer.as_mut_ptr(),
+                BLT_VIDEO_TO_BLT_BUFFER,
+                rect.origin(),
+                Point::default(),
+                rect.size(),
+                rect.width * size_of::<BltPixel>(),
+            )
+        }
+    }
+
+    ///Moves the content of rect by dy lines, up when negative. Lines scrolled out of rect are lost,
+    ///the lines uncovered keep their previous content.
+    pub fn scroll(&self, rect: Rect, dy: isize) -> Status {
+        if !self.screen().contains_rect(&rect) {
+            return Status::INVALID_PARAMETER;
+        }
+        let distance = dy.unsigned_abs();
+        if rect.is_empty() || distance == 0 || distance >= rect.height {
+            return Status::SUCCESS;
+        }
+        let (source, destination) = if dy < 0 {
+            (Point { x: rect.x, y: rect.y + distance }, rect.origin())
+        } else {
+            (rect.origin(), Point { x: rect.x, y: rect.y + distance })
+        };
+        let size = Coordinate {
+            x: rect.width,
+            y: rect.height - distance,
+        };
+        unsafe { self.blt(core::ptr::null_mut(), BLT_VIDEO_TO_VIDEO, source, destination, size, 0) }
+    }
+
+    ///Saves the visible screen as a 24-bit BMP file at path, relative to directory, replacing any
+    ///existing file.
+    pub fn screenshot(&self, directory: &File, path: &str) -> Status {
+        if let Ok(existing) = directory.open(path, OpenMode::ReadWrite, Attribute::None) {
+            existing.delete();
+        }
+        let file = match directory.open(path, OpenMode::ReadWriteCreate, Attribute::None) {
+            Ok(file) => file,
+            Err(status) => return status,
+        };
+        let status = self.write_bmp(&file).err().unwrap_or(Status::SUCCESS);
+        let closed = file.close();
+        if status != Status::SUCCESS {
+            // Do not leave a truncated image behind.
+            if let Ok(partial) = directory.open(path, OpenMode::ReadWrite, Attribute::None) {
+                partial.delete();
+            }
+            return status;
+        }
+        closed
+    }
+
+    fn write_bmp(&self, file: &File) -> Result<(), Status> {
+        let screen = self.screen();
+        file.write_all(&bmp::header(screen.width, screen.height))?;
+        let padding = bmp::line_size(screen.width) - screen.width * 3;
+        let mut pixels = [Color::BLACK.to_blt(); SCREENSHOT_CHUNK];
+        let mut bytes = [0u8; SCREENSHOT_CHUNK * 3];
+        for y in (0..screen.height).rev() {
+            let mut x = 0;
+            while x < screen.width {
+                let count = (screen.width - x).min(SCREENSHOT_CHUNK);
+                let status = self.copy_from_screen(&mut pixels[..count], Rect::new(x, y, count, 1));
+                if status != Status::SUCCESS {
+                    return Err(status);
+                }
+                for (bgr, pixel) in bytes.chunks_exact_mut(3).zip(&pixels[..count]) {
+                    bgr.copy_from_slice(&[pixel.blue, pixel.green, pixel.red]);
+                }
+                file.write_all(&bytes[..count * 3])?;
+                x += count;
+            }
+            file.write_all(&[0; 3][..padding])?;
+        }
+        Ok(())
     }
 
     ///Pointer to EFI_SIMPLE_POINTER_MODE data. The type EFI_SIMPLE_POINTER_MODE is defined in “Related Definitions” below.
     pub fn mode(&self) -> Mode {
         unsafe { *(*self.protocol).mode }
     }
+
+    ///Information about the current mode.
+    pub fn info(&self) -> ModeInformation {
+        unsafe { *(*(*self.protocol).mode).info }
+    }
+}
+
+///A GOP instance and where it lives.
+pub struct Display {
+    pub handle: Handle,
+    pub gop: GraphicOutput,
+    pub device_path: Option<DevicePath<'static>>,
+    ///True if ConOut is sent to this display: firmware text and boot menus appear on it.
+    pub is_console: bool,
+}
+impl Display {
+    ///Returns false for GOPs without a device path, such as the one of the EDK2 console splitter,
+    ///which draws to every console display at once.
+    pub fn is_physical(&self) -> bool {
+        self.device_path.is_some()
+    }
+}
+
+///Displays found by GraphicOutput::displays().
+pub struct Displays {
+    st: *mut SystemTable,
+    handles: HandleBuffer,
+    console_out: PoolBuffer<u8>,
+    console_out_len: usize,
+}
+impl Displays {
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    ///Returns true if one of the ConOut device paths is path, its graphics controller or one of
+    ///its children.
+    fn in_console_out(&self, path: &DevicePath) -> bool {
+        DevicePath::from_bytes(&self.console_out[..self.console_out_len])
+            .instances()
+            .filter(|instance| !instance.as_bytes().is_empty())
+            .any(|instance| instance.starts_with(path) || path.starts_with(&instance))
+    }
+
+    pub fn get(&self, index: usize) -> Result<Display, Status> {
+        let handle = *self.handles.as_slice().get(index).ok_or(Status::NOT_FOUND)?;
+        let device_path = DevicePath::for_handle(self.st, handle).ok();
+        let is_console = match device_path {
+            Some(ref path) => self.in_console_out(path),
+            None => handle == unsafe { (*self.st).console_out_handle },
+        };
+        Ok(Display {
+            handle,
+            gop: GraphicOutput::for_handle(self.st, handle)?,
+            device_path,
+            is_console,
+        })
+    }
+
+    ///Iterates over the displays, skipping handles whose protocol could not be opened.
+    pub fn iter(&self) -> impl Iterator<Item = Display> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index).ok())
+    }
+
+    ///The physical displays, each to be drawn to separately. Drawing to the console splitter instead
+    ///shows the same content on all, but only in the modes they have in common.
+    pub fn physical(&self) -> impl Iterator<Item = Display> + '_ {
+        self.iter().filter(Display::is_physical)
+    }
+
+    ///The display the boot menu should be drawn on: the first physical display ConOut is sent
+    ///to, else the console splitter, else the first display.
+    pub fn console(&self) -> Result<Display, Status> {
+        self.physical()
+            .find(|display| display.is_console)
+            .or_else(|| self.iter().find(|display| display.is_console))
+            .or_else(|| self.iter().next())
+            .ok_or(Status::NOT_FOUND)
+    }
+
+    ///Calls draw with every physical display, to show the same content on each. Stops at the
+    ///first error.
+    pub fn for_each(&self, mut draw: impl FnMut(&Display) -> Status) -> Status {
+        for display in self.physical() {
+            let status = draw(&display);
+            if status != Status::SUCCESS {
+                return status;
+            }
+        }
+        Status::SUCCESS
+    }
 }
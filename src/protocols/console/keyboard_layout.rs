@@ -0,0 +1,298 @@
+use lib_efi::{
+    efi::Status,
+    protocols::simple_text_input_ex::{
+        KeyData, CAPS_LOCK_ACTIVE, LEFT_ALT_PRESSED, LEFT_CONTROL_PRESSED, LEFT_SHIFT_PRESSED, RIGHT_ALT_PRESSED,
+        RIGHT_CONTROL_PRESSED, RIGHT_SHIFT_PRESSED, SHIFT_STATE_VALID, TOGGLE_STATE_VALID,
+    },
+};
+
+use crate::services::variable::{Variable, NON_VOLATILE_BOOT_ACCESS, VENDOR_GUID};
+
+use super::text_input_ex::TextInputEx;
+
+///Name of the NVRAM variable holding the selected layout name.
+pub const LAYOUT_VARIABLE: &str = "KeyboardLayout";
+
+// Firmware keymaps report the characters of a US keyboard. Every layout below lists, in the
+// order of the US keys, the character printed on the same physical key: unshifted, shifted,
+// and the AltGr characters as (US key, character) pairs. Combining accents mark dead keys.
+// Both ISO keys (left of Z and left of Enter) are reported as '\' by US keymaps: it is mapped
+// to the key left of Z.
+const US_UNSHIFTED: &str = "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./";
+const US_SHIFTED: &str = "~!@#$%^&*()_+QWERTYUIOP{}|ASDFGHJKL:\"ZXCVBNM<>?";
+
+const FR_UNSHIFTED: &str = "²&é\"'(-è_çà)=azertyuiop\u{0302}$<qsdfghjklmùwxcvbn,;:!";
+const FR_SHIFTED: &str = "\u{0}1234567890°+AZERTYUIOP\u{0308}£>QSDFGHJKLM%WXCVBN?./§";
+const FR_ALTGR: &[(char, char)] = &[
+    ('2', '\u{0303}'),
+    ('3', '#'),
+    ('4', '{'),
+    ('5', '['),
+    ('6', '|'),
+    ('7', '\u{0300}'),
+    ('8', '\\'),
+    ('9', '^'),
+    ('0', '@'),
+    ('-', ']'),
+    ('=', '}'),
+    ('e', '€'),
+    (']', '¤'),
+];
+
+const DE_UNSHIFTED: &str = "\u{0302}1234567890ß\u{0301}qwertzuiopü+<asdfghjklöäyxcvbnm,.-";
+const DE_SHIFTED: &str = "°!\"§$%&/()=?\u{0300}QWERTZUIOPÜ*>ASDFGHJKLÖÄYXCVBNM;:_";
+const DE_ALTGR: &[(char, char)] = &[
+    ('2', '²'),
+    ('3', '³'),
+    ('7', '{'),
+    ('8', '['),
+    ('9', ']'),
+    ('0', '}'),
+    ('-', '\\'),
+    ('q', '@'),
+    ('e', '€'),
+    (']', '~'),
+    ('\\', '|'),
+    ('m', 'µ'),
+];
+
+const UK_SHIFTED: &str = "¬!\"£$%^&*()_+QWERTYUIOP{}|ASDFGHJKL:@ZXCVBNM<>?";
+const UK_ALTGR: &[(char, char)] = &[('`', '¦'), ('4', '€')];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    Us,
+    FrAzerty,
+    DeQwertz,
+    Uk,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadKey {
+    Grave,
+    Acute,
+    Circumflex,
+    Tilde,
+    Diaeresis,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Symbol {
+    Char(char),
+    Dead(DeadKey),
+}
+
+impl Layout {
+    pub const ALL: [Layout; 4] = [Layout::Us, Layout::FrAzerty, Layout::DeQwertz, Layout::Uk];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Us => "us",
+            Layout::FrAzerty => "fr-azerty",
+            Layout::DeQwertz => "de-qwertz",
+            Layout::Uk => "uk",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Layout> {
+        Layout::ALL.iter().copied().find(|layout| layout.name() == name)
+    }
+
+    ///Reads the layout stored in NVRAM by save().
+    pub fn load(variable: &Variable) -> Result<Layout, Status> {
+        let mut buffer = [0u8; 16];
+        let (size, _) = variable.get(LAYOUT_VARIABLE, VENDOR_GUID, &mut buffer)?;
+        core::str::from_utf8(&buffer[..size])
+            .ok()
+            .and_then(Layout::from_name)
+            .ok_or(Status::NOT_FOUND)
+    }
+
+    ///Stores the layout name in a non-volatile variable so it survives reboots.
+    pub fn save(self, variable: &Variable) -> Status {
+        variable.set(LAYOUT_VARIABLE, VENDOR_GUID, NON_VOLATILE_BOOT_ACCESS, self.name().as_bytes())
+    }
+
+    fn tables(self) -> (&'static str, &'static str, &'static [(char, char)]) {
+        match self {
+            Layout::Us => (US_UNSHIFTED, US_SHIFTED, &[]),
+            Layout::FrAzerty => (FR_UNSHIFTED, FR_SHIFTED, FR_ALTGR),
+            Layout::DeQwertz => (DE_UNSHIFTED, DE_SHIFTED, DE_ALTGR),
+            Layout::Uk => (US_UNSHIFTED, UK_SHIFTED, UK_ALTGR),
+        }
+    }
+
+    ///Maps the character a US keymap reported to the symbol of the same key in this layout.
+    ///Returns None for characters that do not come from the main block of keys.
+    fn map(self, us: char, altgr: bool) -> Option<Symbol> {
+        let (unshifted, shifted, altgr_table) = self.tables();
+        let (index, table) = match US_UNSHIFTED.chars().position(|c| c == us) {
+            Some(index) => (index, unshifted),
+            None => (US_SHIFTED.chars().position(|c| c == us)?, shifted),
+        };
+        let c = if altgr {
+            let key = US_UNSHIFTED.chars().nth(index)?;
+            altgr_table.iter().find(|&&(k, _)| k == key)?.1
+        } else {
+            table.chars().nth(index)?
+        };
+        match c {
+            '\0' => None,
+            '\u{0300}' => Some(Symbol::Dead(DeadKey::Grave)),
+            '\u{0301}' => Some(Symbol::Dead(DeadKey::Acute)),
+            '\u{0302}' => Some(Symbol::Dead(DeadKey::Circumflex)),
+            '\u{0303}' => Some(Symbol::Dead(DeadKey::Tilde)),
+            '\u{0308}' => Some(Symbol::Dead(DeadKey::Diaeresis)),
+            c => Some(Symbol::Char(c)),
+        }
+    }
+}
+
+impl DeadKey {
+    ///The character typed when the accent cannot be combined, or is followed by a space.
+    pub fn spacing(self) -> char {
+        match self {
+            DeadKey::Grave => '`',
+            DeadKey::Acute => '´',
+            DeadKey::Circumflex => '^',
+            DeadKey::Tilde => '~',
+            DeadKey::Diaeresis => '¨',
+        }
+    }
+
+    ///Combines the accent with a base letter, if Latin-1 has the result.
+    pub fn compose(self, base: char) -> Option<char> {
+        let (bases, composed) = match self {
+            DeadKey::Grave => ("aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+            DeadKey::Acute => ("aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ"),
+            DeadKey::Circumflex => ("aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+            DeadKey::Tilde => ("anoANO", "ãñõÃÑÕ"),
+            DeadKey::Diaeresis => ("aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+        };
+        let index = bases.chars().position(|c| c == base)?;
+        composed.chars().nth(index)
+    }
+}
+
+///Remaps the keys read from TextInputEx to a keyboard layout, composing dead keys.
+pub struct LayoutRemapper<'a> {
+    input: &'a TextInputEx,
+    layout: Layout,
+    dead_key: Option<DeadKey>,
+    pending: Option<KeyData>,
+}
+impl<'a> LayoutRemapper<'a> {
+    pub fn new(input: &'a TextInputEx, layout: Layout) -> LayoutRemapper<'a> {
+        LayoutRemapper {
+            input,
+            layout,
+            dead_key: None,
+            pending: None,
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.dead_key = None;
+    }
+
+    ///Reads the next keystroke, translated to the layout.
+    ///Like TextInputEx::read_key_stroke_ex(), EFI_NOT_READY is returned when no key is available,
+    ///which includes the time between a dead key and the key it combines with.
+    pub fn read_key_stroke_ex(&mut self) -> Result<KeyData, Status> {
+        if let Some(key_data) = self.pending.take() {
+            return Ok(key_data);
+        }
+        loop {
+            let key_data = self.input.read_key_stroke_ex()?;
+            if let Some(key_data) = self.translate(key_data) {
+                return Ok(key_data);
+            }
+        }
+    }
+
+    ///Translates one keystroke. Returns None when the key was consumed, e.g. a dead key.
+    pub fn translate(&mut self, key_data: KeyData) -> Option<KeyData> {
+        let shift_state = key_data.key_state.key_shift_state;
+        let toggle_state = key_data.key_state.key_toggle_state;
+        let shift_valid = shift_state & SHIFT_STATE_VALID != 0;
+        let control = shift_valid
+            && shift_state & (LEFT_CONTROL_PRESSED | RIGHT_CONTROL_PRESSED | LEFT_ALT_PRESSED) != 0;
+        let altgr = shift_valid && shift_state & RIGHT_ALT_PRESSED != 0;
+        let shift = shift_valid && shift_state & (LEFT_SHIFT_PRESSED | RIGHT_SHIFT_PRESSED) != 0;
+        let caps_lock = toggle_state & TOGGLE_STATE_VALID != 0 && toggle_state & CAPS_LOCK_ACTIVE != 0;
+
+        let us = match core::char::from_u32(key_data.key.unicode_char as u32) {
+            // Firmware keymaps apply Caps Lock to the US letter keys: undo it, so that the key
+            // is looked up by its Shift state alone. It is applied again to the result.
+            Some(c) if caps_lock && c.is_ascii_alphabetic() && !control => {
+                if shift {
+                    c.to_ascii_uppercase()
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            }
+            Some(c) if c != '\0' && !control => c,
+            _ => return self.unmapped(key_data),
+        };
+        let symbol = match self.layout.map(us, altgr) {
+            Some(Symbol::Char(c)) if caps_lock && c.is_alphabetic() => {
+                // Caps Lock affects the letters of the layout, and Shift reverts it.
+                let toggled = if shift { c.to_lowercase().next() } else { c.to_uppercase().next() };
+                Symbol::Char(toggled.unwrap_or(c))
+            }
+            Some(symbol) => symbol,
+            None => return self.unmapped(key_data),
+        };
+
+        match (self.dead_key.take(), symbol) {
+            (None, Symbol::Char(c)) => Some(with_char(key_data, c)),
+            (None, Symbol::Dead(dead_key)) => {
+                self.dead_key = Some(dead_key);
+                None
+            }
+            (Some(dead_key), Symbol::Char(' ')) => Some(with_char(key_data, dead_key.spacing())),
+            (Some(dead_key), Symbol::Char(c)) => match dead_key.compose(c) {
+                Some(composed) => Some(with_char(key_data, composed)),
+                None => {
+                    self.pending = Some(with_char(key_data, c));
+                    Some(with_char(key_data, dead_key.spacing()))
+                }
+            },
+            (Some(dead_key), Symbol::Dead(next)) => {
+                if next != dead_key {
+                    self.dead_key = Some(next);
+                }
+                Some(with_char(key_data, dead_key.spacing()))
+            }
+        }
+    }
+
+    ///Passes a key the layout does not change, such as Enter or an arrow key, typing the accent
+    ///of a pending dead key first.
+    fn unmapped(&mut self, key_data: KeyData) -> Option<KeyData> {
+        // Modifier keys alone, reported with partial keystrokes, leave the dead key pending.
+        if key_data.key.scan_code == 0 && key_data.key.unicode_char == 0 {
+            return Some(key_data);
+        }
+        match self.dead_key.take() {
+            Some(dead_key) => {
+                self.pending = Some(key_data);
+                let mut spacing = with_char(key_data, dead_key.spacing());
+                spacing.key.scan_code = 0;
+                Some(spacing)
+            }
+            None => Some(key_data),
+        }
+    }
+}
+
+fn with_char(key_data: KeyData, c: char) -> KeyData {
+    let mut key_data = key_data;
+    key_data.key.unicode_char = c as u32 as u16;
+    key_data
+}
@@ -4,4 +4,11 @@ pub mod text_input_ex;
 pub mod pointer;
 pub mod absolute_pointer;
 pub mod serial_io;
-pub mod graphic_output;
\ No newline at end of file
+pub mod graphic_output;
+pub mod edid;
+pub mod touch_screen;
+pub mod keyboard_layout;
+pub mod xmodem;
+pub mod multiplexer;
+pub mod screenshot;
+pub mod framebuffer;
\ No newline at end of file
@@ -0,0 +1,401 @@
+use core::fmt::{self, Write};
+
+use lib_efi::{
+    efi::{BootServices, Status, SystemTable},
+    protocols::simple_text_input::InputKey,
+};
+
+use super::{
+    serial_io::{ControlBits, SerialIO},
+    text_input::{ScanCode, TextInput, CHAR_BACKSPACE, CHAR_NULL},
+    text_output::{BackgroundColor, ForegroudColor, ScreenDimension, TextOutput},
+};
+
+const ESC: u8 = 0x1B;
+const DEL: u8 = 0x7F;
+///How long a lone ESC waits for the rest of a sequence before it counts as the Esc key.
+const ESCAPE_TIMEOUT_US: usize = 20_000;
+
+// EFI colors are ordered blue, green, red; ANSI colors red, green, blue.
+const ANSI_COLORS: [u8; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DecoderState {
+    Ground,
+    Escape,
+    ///ESC [ followed by parameters.
+    Csi,
+    ///ESC O, used by the keypad and F1-F4 in application mode.
+    Ss3,
+    ///Continuation bytes of a UTF-8 character.
+    Utf8 { remaining: u8 },
+}
+
+///Turns the bytes a VT100/xterm terminal sends for keys into EFI_INPUT_KEY values.
+pub struct EscapeDecoder {
+    state: DecoderState,
+    ///First parameter of a CSI sequence: the key. The following ones are modifiers.
+    parameter: u16,
+    ///Set once a ';' ended the first parameter.
+    after_separator: bool,
+    code_point: u32,
+}
+
+fn key(scan_code: ScanCode, unicode_char: u16) -> InputKey {
+    InputKey {
+        scan_code: scan_code as u16,
+        unicode_char,
+    }
+}
+
+impl EscapeDecoder {
+    pub const fn new() -> EscapeDecoder {
+        EscapeDecoder {
+            state: DecoderState::Ground,
+            parameter: 0,
+            after_separator: false,
+            code_point: 0,
+        }
+    }
+
+    ///True while in the middle of a sequence.
+    pub fn is_pending(&self) -> bool {
+        self.state != DecoderState::Ground
+    }
+
+    ///Ends the pending sequence because no more bytes came: a lone ESC is the Esc key.
+    pub fn timeout(&mut self) -> Option<InputKey> {
+        let state = self.state;
+        self.state = DecoderState::Ground;
+        match state {
+            DecoderState::Escape => Some(key(ScanCode::Esc, CHAR_NULL)),
+            _ => None,
+        }
+    }
+
+    ///Feeds one byte. Returns a key when it completes one.
+    pub fn feed(&mut self, byte: u8) -> Option<InputKey> {
+        match self.state {
+            DecoderState::Ground => match byte {
+                ESC => {
+                    self.state = DecoderState::Escape;
+                    None
+                }
+                // Terminals send DEL for the backspace key.
+                DEL => Some(key(ScanCode::Null, CHAR_BACKSPACE)),
+                0x00..=0x7F => Some(key(ScanCode::Null, byte as u16)),
+                0xC0..=0xDF => self.start_utf8(byte & 0x1F, 1),
+                0xE0..=0xEF => self.start_utf8(byte & 0x0F, 2),
+                // 4-byte characters do not fit in UCS-2 and are dropped.
+                _ => None,
+            },
+            DecoderState::Escape => match byte {
+                b'[' => {
+                    self.parameter = 0;
+                    self.after_separator = false;
+                    self.state = DecoderState::Csi;
+                    None
+                }
+                b'O' => {
+                    self.state = DecoderState::Ss3;
+                    None
+                }
+                ESC => Some(key(ScanCode::Esc, CHAR_NULL)),
+                _ => {
+                    // Alt+key: report the key itself.
+                    self.state = DecoderState::Ground;
+                    self.feed(byte)
+                }
+            },
+            DecoderState::Csi => match byte {
+                b'0'..=b'9' => {
+                    if !self.after_separator {
+                        self.parameter = self.parameter.saturating_mul(10).saturating_add((byte - b'0') as u16);
+                    }
+                    None
+                }
+                // Modifier parameters (e.g. ESC [ 1 ; 5 A) are ignored.
+                b';' => {
+                    self.after_separator = true;
+                    None
+                }
+                b'~' => {
+                    self.state = DecoderState::Ground;
+                    tilde_key(self.parameter).map(|scan_code| key(scan_code, CHAR_NULL))
+                }
+                _ => {
+                    self.state = DecoderState::Ground;
+                    final_key(byte).map(|scan_code| key(scan_code, CHAR_NULL))
+                }
+            },
+            DecoderState::Ss3 => {
+                self.state = DecoderState::Ground;
+                final_key(byte).map(|scan_code| key(scan_code, CHAR_NULL))
+            }
+            DecoderState::Utf8 { remaining } => {
+                if byte & 0xC0 != 0x80 {
+                    self.state = DecoderState::Ground;
+                    return self.feed(byte);
+                }
+                self.code_point = self.code_point << 6 | (byte & 0x3F) as u32;
+                if remaining > 1 {
+                    self.state = DecoderState::Utf8 { remaining: remaining - 1 };
+                    return None;
+                }
+                self.state = DecoderState::Ground;
+                Some(key(ScanCode::Null, self.code_point as u16))
+            }
+        }
+    }
+
+    fn start_utf8(&mut self, bits: u8, remaining: u8) -> Option<InputKey> {
+        self.code_point = bits as u32;
+        self.state = DecoderState::Utf8 { remaining };
+        None
+    }
+}
+
+impl Default for EscapeDecoder {
+    fn default() -> Self {
+        EscapeDecoder::new()
+    }
+}
+
+///Final byte of ESC [ x and ESC O x sequences.
+fn final_key(byte: u8) -> Option<ScanCode> {
+    match byte {
+        b'A' => Some(ScanCode::Up),
+        b'B' => Some(ScanCode::Down),
+        b'C' => Some(ScanCode::Right),
+        b'D' => Some(ScanCode::Left),
+        b'H' => Some(ScanCode::Home),
+        b'F' => Some(ScanCode::End),
+        b'P' => Some(ScanCode::F1),
+        b'Q' => Some(ScanCode::F2),
+        b'R' => Some(ScanCode::F3),
+        b'S' => Some(ScanCode::F4),
+        _ => None,
+    }
+}
+
+///Parameter of ESC [ n ~ sequences.
+fn tilde_key(parameter: u16) -> Option<ScanCode> {
+    match parameter {
+        1 | 7 => Some(ScanCode::Home),
+        2 => Some(ScanCode::Insert),
+        3 => Some(ScanCode::Delete),
+        4 | 8 => Some(ScanCode::End),
+        5 => Some(ScanCode::PageUp),
+        6 => Some(ScanCode::PageDown),
+        11 => Some(ScanCode::F1),
+        12 => Some(ScanCode::F2),
+        13 => Some(ScanCode::F3),
+        14 => Some(ScanCode::F4),
+        15 => Some(ScanCode::F5),
+        17 => Some(ScanCode::F6),
+        18 => Some(ScanCode::F7),
+        19 => Some(ScanCode::F8),
+        20 => Some(ScanCode::F9),
+        21 => Some(ScanCode::F10),
+        23 => Some(ScanCode::F11),
+        24 => Some(ScanCode::F12),
+        _ => None,
+    }
+}
+
+///Buffers UTF-8 output to the serial port, flushing in chunks.
+struct SerialWriter<'a> {
+    serial: &'a SerialIO,
+    buffer: [u8; 128],
+    len: usize,
+    status: Status,
+}
+impl SerialWriter<'_> {
+    fn flush(&mut self) {
+        if self.len > 0 && self.status == Status::SUCCESS {
+            if let Err(status) = self.serial.write_all(&self.buffer[..self.len]) {
+                self.status = status;
+            }
+        }
+        self.len = 0;
+    }
+}
+
+impl Write for SerialWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            if self.len == self.buffer.len() {
+                self.flush();
+            }
+            self.buffer[self.len] = byte;
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+///Console mirrored on a serial terminal: output goes to ConOut and, as VT100, to the serial port;
+///keys are read from ConIn and from the serial port.
+pub struct Console {
+    output: TextOutput,
+    input: TextInput,
+    serial: Option<SerialIO>,
+    decoder: EscapeDecoder,
+    boot_services: *mut BootServices,
+}
+impl Console {
+    pub fn new(st: *mut SystemTable, serial: Option<SerialIO>) -> Console {
+        Console {
+            output: TextOutput::new(st),
+            input: TextInput::new(st),
+            serial,
+            decoder: EscapeDecoder::new(),
+            boot_services: unsafe { (*st).boot_services },
+        }
+    }
+
+    pub fn output(&self) -> &TextOutput {
+        &self.output
+    }
+
+    pub fn serial(&self) -> Option<&SerialIO> {
+        self.serial.as_ref()
+    }
+
+    ///Runs f on a buffered writer to the serial port, if there is one.
+    fn serial_write(&self, f: impl FnOnce(&mut SerialWriter)) -> Status {
+        let serial = match self.serial.as_ref() {
+            Some(serial) => serial,
+            None => return Status::SUCCESS,
+        };
+        let mut writer = SerialWriter {
+            serial,
+            buffer: [0; 128],
+            len: 0,
+            status: Status::SUCCESS,
+        };
+        f(&mut writer);
+        writer.flush();
+        writer.status
+    }
+
+    ///Returns the first error of the two devices.
+    fn both(console: Status, serial: Status) -> Status {
+        if console.is_error() {
+            console
+        } else {
+            serial
+        }
+    }
+
+    ///Displays the string on both devices.
+    pub fn output_str(&self, s: &str) -> Status {
+        let console = self.output.output_str(s);
+        Console::both(console, self.serial_write(|w| {
+            let _ = w.write_str(s);
+        }))
+    }
+
+    ///Displays a null-terminated UCS-2 string on both devices. See OutputString()
+    pub fn output_string(&self, str: *mut u16) -> Status {
+        let console = self.output.output_string(str);
+        let mut len = 0;
+        while unsafe { *str.add(len) } != 0 {
+            len += 1;
+        }
+        let ucs2 = unsafe { core::slice::from_raw_parts(str, len) };
+        Console::both(console, self.serial_write(|w| {
+            for c in char::decode_utf16(ucs2.iter().copied()) {
+                let _ = w.write_char(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+        }))
+    }
+
+    ///Sets the colors of both devices. The serial terminal gets the nearest ANSI colors.
+    pub fn set_attribute(&self, foreground: ForegroudColor, background: BackgroundColor) -> Status {
+        let console = self.output.set_attribute(foreground, background);
+        Console::both(console, self.serial_write(|w| {
+            let background = ANSI_COLORS[(background as usize >> 4) & 0x7];
+            let _ = match foreground {
+                ForegroudColor::None => write!(w, "\x1b[0;39;4{}m", background),
+                foreground => {
+                    let foreground = foreground as usize;
+                    let bold = if foreground & 0x8 != 0 { "1;" } else { "" };
+                    write!(w, "\x1b[0;{}3{};4{}m", bold, ANSI_COLORS[foreground & 0x7], background)
+                }
+            };
+        }))
+    }
+
+    ///Clears both displays and moves the cursor to (0, 0).
+    pub fn clear_screen(&self) -> Status {
+        let console = self.output.clear_screen();
+        Console::both(console, self.serial_write(|w| {
+            let _ = w.write_str("\x1b[2J\x1b[H");
+        }))
+    }
+
+    ///Moves the cursor of both displays. The upper left corner is (0, 0).
+    pub fn set_cursor_position(&self, dimension: ScreenDimension) -> Status {
+        let (column, row) = (dimension.columns, dimension.rows);
+        let console = self.output.set_cursor_position(dimension);
+        Console::both(console, self.serial_write(|w| {
+            let _ = write!(w, "\x1b[{};{}H", row + 1, column + 1);
+        }))
+    }
+
+    ///Turns the visibility of the cursor on/off on both displays.
+    pub fn enable_cursor(&self, visible: bool) -> Status {
+        let console = self.output.enable_cursor(visible);
+        Console::both(console, self.serial_write(|w| {
+            let _ = w.write_str(if visible { "\x1b[?25h" } else { "\x1b[?25l" });
+        }))
+    }
+
+    ///Resets the terminal state and the keyboard.
+    pub fn reset(&mut self, extended: bool) -> Status {
+        self.decoder = EscapeDecoder::new();
+        let console = self.input.reset(extended);
+        Console::both(console, self.serial_write(|w| {
+            let _ = w.write_str("\x1b[0m");
+        }))
+    }
+
+    fn serial_pending(serial: &SerialIO) -> bool {
+        serial
+            .get_control()
+            .map(|control| !control.contains(ControlBits::INPUT_BUFFER_EMPTY))
+            .unwrap_or(false)
+    }
+
+    ///Decodes the bytes waiting on the serial port. A lone ESC is reported after a short delay.
+    fn read_serial_key(&mut self) -> Option<InputKey> {
+        let serial = self.serial.as_ref()?;
+        let mut byte = [0u8; 1];
+        loop {
+            if !Console::serial_pending(serial) {
+                if !self.decoder.is_pending() {
+                    return None;
+                }
+                unsafe { ((*self.boot_services).stall)(ESCAPE_TIMEOUT_US) };
+                if !Console::serial_pending(serial) {
+                    return self.decoder.timeout();
+                }
+            }
+            if serial.read(&mut byte) == Ok(1) {
+                if let Some(key) = self.decoder.feed(byte[0]) {
+                    return Some(key);
+                }
+            }
+        }
+    }
+
+    ///Reads the next keystroke from ConIn or from the serial terminal.
+    ///Returns EFI_NOT_READY when neither has one.
+    pub fn read_key_stroke(&mut self) -> Result<InputKey, Status> {
+        match self.input.read_key_stroke() {
+            Err(Status::NOT_READY) => self.read_serial_key().ok_or(Status::NOT_READY),
+            result => result,
+        }
+    }
+}
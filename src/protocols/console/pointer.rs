@@ -1,5 +1,23 @@
 use lib_efi::{efi::{Event, Status, SystemTable}, protocols::simple_pointer::{Mode, Protocol, State, PROTOCOL_GUID}};
 
+use super::graphic_output::Coordinate;
+
+///Position of a pointing device on screen, in pixels, with its button state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PointerSample {
+    pub position: Coordinate,
+    ///Left button, or touch contact.
+    pub primary: bool,
+    ///Right button, or alternate touch button.
+    pub secondary: bool,
+}
+
+///A pointing device reporting positions on screen, whatever the underlying protocol.
+pub trait PointerSource {
+    ///Returns the current sample, or None if the device state did not change since the last call.
+    fn poll(&mut self) -> Result<Option<PointerSample>, Status>;
+}
+
 pub struct Pointer {
     protocol: *mut Protocol,
 }
@@ -49,3 +67,90 @@ impl Pointer {
     }
     
 }
+
+///Turns the relative movements of a Pointer into a position on a screen of the given size.
+pub struct Cursor {
+    pointer: Pointer,
+    mode: Mode,
+    width: usize,
+    height: usize,
+    position: Coordinate,
+    pixels_per_mm: i64,
+    remainder_x: i64,
+    remainder_y: i64,
+}
+impl Cursor {
+    ///Creates a cursor centered on the screen.
+    pub fn new(pointer: Pointer, width: usize, height: usize) -> Cursor {
+        Cursor {
+            mode: pointer.mode(),
+            pointer,
+            width,
+            height,
+            position: Coordinate {
+                x: width / 2,
+                y: height / 2,
+            },
+            pixels_per_mm: 4,
+            remainder_x: 0,
+            remainder_y: 0,
+        }
+    }
+
+    pub fn pointer(&self) -> &Pointer {
+        &self.pointer
+    }
+
+    pub fn position(&self) -> Coordinate {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Coordinate) {
+        self.position = Coordinate {
+            x: core::cmp::min(position.x, self.width.saturating_sub(1)),
+            y: core::cmp::min(position.y, self.height.saturating_sub(1)),
+        };
+    }
+
+    ///Sets how many pixels the cursor moves per millimeter of device movement.
+    pub fn set_speed(&mut self, pixels_per_mm: u32) {
+        self.pixels_per_mm = pixels_per_mm as i64;
+    }
+
+    ///Moves the cursor by the relative movement reported in a pointer state.
+    pub fn apply(&mut self, state: &State) -> PointerSample {
+        let dx = scale_movement(state.relative_movement_x, self.mode.resolution_x, self.pixels_per_mm, &mut self.remainder_x);
+        let dy = scale_movement(state.relative_movement_y, self.mode.resolution_y, self.pixels_per_mm, &mut self.remainder_y);
+        let x = (self.position.x as i64 + dx).clamp(0, self.width.saturating_sub(1) as i64);
+        let y = (self.position.y as i64 + dy).clamp(0, self.height.saturating_sub(1) as i64);
+        self.position = Coordinate {
+            x: x as usize,
+            y: y as usize,
+        };
+        PointerSample {
+            position: self.position,
+            primary: bool::from(state.left_button),
+            secondary: bool::from(state.right_button),
+        }
+    }
+}
+
+impl PointerSource for Cursor {
+    fn poll(&mut self) -> Result<Option<PointerSample>, Status> {
+        match self.pointer.get_state() {
+            Ok(state) => Ok(Some(self.apply(&state))),
+            Err(Status::NOT_READY) => Ok(None),
+            Err(status) => Err(status),
+        }
+    }
+}
+
+fn scale_movement(movement: i32, resolution: u64, pixels_per_mm: i64, remainder: &mut i64) -> i64 {
+    // A resolution of 0 means the device does not report one: use counts as pixels.
+    if resolution == 0 {
+        return movement as i64;
+    }
+    let total = movement as i64 * pixels_per_mm + *remainder;
+    *remainder = total % resolution as i64;
+    total / resolution as i64
+}
@@ -0,0 +1,82 @@
+use core::{
+    ffi::c_void,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use lib_efi::{efi::Status, protocols::simple_text_input_ex::KeyData};
+
+use super::{graphic_output::GraphicOutput, text_input_ex::TextInputEx};
+use crate::protocols::media::file::{Attribute, File, OpenMode};
+
+///Set by the key notification, cleared once the screenshot is saved.
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+///Registered hotkeys: the notification is shared by all of them.
+static REGISTERED: AtomicU32 = AtomicU32::new(0);
+
+///Longest file name prefix accepted by ScreenshotHotkey::poll().
+const MAX_PREFIX: usize = 100;
+
+extern "efiapi" fn on_hotkey(_key: *mut KeyData) -> Status {
+    // Files cannot be written from a notification: poll() does it.
+    REQUESTED.store(true, Ordering::Release);
+    Status::SUCCESS
+}
+
+///A key which requests a screenshot, saved by the next call to poll(). For example F12 is
+///KeyData { key: InputKey { scan_code: 0x16, unicode_char: 0 }, ..Default::default() }.
+pub struct ScreenshotHotkey<'a> {
+    input: &'a TextInputEx,
+    handle: *mut c_void,
+    next: u32,
+}
+impl<'a> ScreenshotHotkey<'a> {
+    pub fn register(input: &'a TextInputEx, key: KeyData) -> Result<ScreenshotHotkey<'a>, Status> {
+        let handle = input.register_key_notify(key, on_hotkey)?;
+        REGISTERED.fetch_add(1, Ordering::AcqRel);
+        Ok(ScreenshotHotkey { input, handle, next: 0 })
+    }
+
+    ///Returns true if the hotkey was pressed since the last screenshot.
+    pub fn is_pending(&self) -> bool {
+        REQUESTED.load(Ordering::Acquire)
+    }
+
+    ///Saves a screenshot if the hotkey was pressed, to the first free name from prefix000.bmp to
+    ///prefix999.bmp in directory. Returns the number used.
+    pub fn poll(&mut self, gop: &GraphicOutput, directory: &File, prefix: &str) -> Result<Option<u32>, Status> {
+        if !REQUESTED.swap(false, Ordering::AcqRel) {
+            return Ok(None);
+        }
+        if prefix.len() > MAX_PREFIX {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        let mut name = [0u8; MAX_PREFIX + 7];
+        name[..prefix.len()].copy_from_slice(prefix.as_bytes());
+        name[prefix.len() + 3..prefix.len() + 7].copy_from_slice(b".bmp");
+        while self.next < 1000 {
+            let number = self.next;
+            self.next += 1;
+            let digits = [b'0' + (number / 100) as u8, b'0' + (number / 10 % 10) as u8, b'0' + (number % 10) as u8];
+            name[prefix.len()..prefix.len() + 3].copy_from_slice(&digits);
+            let path = core::str::from_utf8(&name[..prefix.len() + 7]).map_err(|_| Status::INVALID_PARAMETER)?;
+            if let Ok(existing) = directory.open(path, OpenMode::Read, Attribute::None) {
+                existing.close();
+                continue;
+            }
+            return match gop.screenshot(directory, path) {
+                Status::SUCCESS => Ok(Some(number)),
+                status => Err(status),
+            };
+        }
+        Err(Status::VOLUME_FULL)
+    }
+}
+
+impl Drop for ScreenshotHotkey<'_> {
+    fn drop(&mut self) {
+        self.input.unregister_key_notify(self.handle);
+        if REGISTERED.fetch_sub(1, Ordering::AcqRel) == 1 {
+            REQUESTED.store(false, Ordering::Release);
+        }
+    }
+}
@@ -1,7 +1,53 @@
-use core::{ffi::c_void, ptr::null_mut};
+use core::ffi::c_void;
 
-use lib_efi::{efi::{Event, Status, SystemTable}, protocols::serial_io::{Mode, Protocol, PROTOCOL_GUID}};
+use lib_efi::{efi::{Guid, Handle, Status, SystemTable}, protocols::serial_io::{Mode, Protocol, PROTOCOL_GUID}};
 
+use crate::{
+    protocols::device_path::{DevicePath, Node, NodeKind, PNP_SERIAL_PORT},
+    services::handle::{handle_protocol, HandleBuffer},
+};
+
+///Revision of the protocol that added DeviceTypeGuid.
+const REVISION1P1: u32 = 0x00010001;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parity {
+    Default = 0,
+    NoParity = 1,
+    Even = 2,
+    Odd = 3,
+    Mark = 4,
+    Space = 5,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopBits {
+    Default = 0,
+    One = 1,
+    OneFive = 2,
+    Two = 3,
+}
+
+bitflags::bitflags! {
+    ///Control bits of a serial device. The output bits (DTR, RTS and the enable bits) can be set
+    ///with SetControl(), the others are read-only.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ControlBits: u32 {
+        const DATA_TERMINAL_READY = 0x0001;
+        const REQUEST_TO_SEND = 0x0002;
+        const CLEAR_TO_SEND = 0x0010;
+        const DATA_SET_READY = 0x0020;
+        const RING_INDICATE = 0x0040;
+        const CARRIER_DETECT = 0x0080;
+        const INPUT_BUFFER_EMPTY = 0x0100;
+        const OUTPUT_BUFFER_EMPTY = 0x0200;
+        const HARDWARE_LOOPBACK_ENABLE = 0x1000;
+        const SOFTWARE_LOOPBACK_ENABLE = 0x2000;
+        const HARDWARE_FLOW_CONTROL_ENABLE = 0x4000;
+    }
+}
 
 pub struct SerialIO {
     protocol: *mut Protocol,
@@ -18,7 +64,7 @@ impl SerialIO {
                 &mut protocol as *mut *mut Protocol as *mut _,
             )
         };
-    
+
         if status == Status::SUCCESS {
             Ok(SerialIO {
                 protocol,
@@ -28,61 +74,215 @@ impl SerialIO {
         }
     }
 
+    ///Opens the SerialIO protocol installed on a specific handle.
+    pub fn for_handle(st: *mut SystemTable, handle: Handle) -> Result<SerialIO, Status> {
+        let protocol = handle_protocol::<Protocol>(st, handle, PROTOCOL_GUID)?;
+        Ok(SerialIO { protocol })
+    }
+
+    ///Lists every serial device, in handle database order.
+    pub fn ports(st: *mut SystemTable) -> Result<SerialPorts, Status> {
+        Ok(SerialPorts {
+            st,
+            handles: HandleBuffer::by_protocol(st, PROTOCOL_GUID)?,
+        })
+    }
+
+    ///Opens the first serial device matching the selector.
+    pub fn select(st: *mut SystemTable, selector: PortSelector) -> Result<SerialIO, Status> {
+        let ports = SerialIO::ports(st)?;
+        match selector {
+            PortSelector::Index(index) => ports.get(index),
+            _ => ports.iter().find(|port| port.matches(&selector)).ok_or(Status::NOT_FOUND),
+        }
+        .map(|port| port.io)
+    }
+
     ///Resets the serial device.
     pub fn reset(&self) -> Status {
         unsafe { ((*self.protocol).reset)(self.protocol) }
     }
     ///Sets the baud rate, receive FIFO depth, transmit/receive time out, parity, data bits, and stop bits on a serial device.
+    ///A value of 0 for baud_rate, receive_fifo_depth, time_out or data_bits selects the device default. time_out is in microseconds.
     pub fn set_attribute(
         &self,
         baud_rate: u64,
         receive_fifo_depth: u32,
         time_out: u32,
-        parity: u32,
+        parity: Parity,
         data_bits: u32,
-        stop_bits: u32
+        stop_bits: StopBits,
     ) -> Status {
-        unsafe { ((*self.protocol).set_attribute)(self.protocol, baud_rate, receive_fifo_depth, time_out, parity, data_bits, stop_bits) }
+        unsafe {
+            ((*self.protocol).set_attribute)(
+                self.protocol,
+                baud_rate,
+                receive_fifo_depth,
+                time_out,
+                parity as u32,
+                data_bits,
+                stop_bits as u32,
+            )
+        }
     }
 
     ///Retrieves the status of the control bits on a serial device.
-    pub fn get_control(&self) -> Result<u32, Status> {
+    pub fn get_control(&self) -> Result<ControlBits, Status> {
         let mut control: u32 = Default::default();
         let status = unsafe { ((*self.protocol).get_control)(self.protocol, &mut control) };
         if status == Status::SUCCESS {
-            Ok(control)
+            Ok(ControlBits::from_bits_retain(control))
         } else {
             Err(status)
         }
     }
 
     ///Sets the control bits on a serial device.
-    pub fn set_control(&self, control: u32) -> Status {
-        unsafe { ((*self.protocol).set_control)(self.protocol, control) }
+    pub fn set_control(&self, control: ControlBits) -> Status {
+        unsafe { ((*self.protocol).set_control)(self.protocol, control.bits()) }
     }
 
-    ///Writes data to a serial device.
-    pub fn write(&self, buffer_size: *mut usize, buffer: *mut c_void) -> Status {
-        unsafe { ((*self.protocol).write)(self.protocol, buffer_size, buffer) }
+    ///Writes data to a serial device. Returns the number of bytes actually written,
+    ///which is less than buffer.len() if the transmit timeout expired first.
+    pub fn write(&self, buffer: &[u8]) -> Result<usize, Status> {
+        let mut buffer_size = buffer.len();
+        let status = unsafe {
+            ((*self.protocol).write)(self.protocol, &mut buffer_size, buffer.as_ptr() as *mut c_void)
+        };
+        match status {
+            Status::SUCCESS => Ok(buffer_size),
+            Status::TIMEOUT if buffer_size > 0 => Ok(buffer_size),
+            _ => Err(status),
+        }
     }
 
-    ///Read data from a serial device.
-    pub fn read(&self, buffer_size: *mut usize) -> Result<*mut c_void, Status> {
-        let buffer: *mut c_void = null_mut();
-        let status = unsafe { ((*self.protocol).read)(self.protocol, buffer_size, buffer) };
-        if status == Status::SUCCESS {
-            Ok(buffer)
-        } else {
-            Err(status)
+    ///Writes the whole buffer, retrying after partial writes. Fails with EFI_TIMEOUT if the
+    ///device stops accepting data.
+    pub fn write_all(&self, buffer: &[u8]) -> Result<(), Status> {
+        let mut written = 0;
+        while written < buffer.len() {
+            match self.write(&buffer[written..])? {
+                0 => return Err(Status::TIMEOUT),
+                count => written += count,
+            }
+        }
+        Ok(())
+    }
+
+    ///Reads data from a serial device into buffer and returns the number of bytes read.
+    ///The read stops when the buffer is full or the receive timeout expires: a timeout after
+    ///some data was received is a success, EFI_TIMEOUT is only returned when nothing arrived.
+    pub fn read(&self, buffer: &mut [u8]) -> Result<usize, Status> {
+        let mut buffer_size = buffer.len();
+        let status = unsafe {
+            ((*self.protocol).read)(self.protocol, &mut buffer_size, buffer.as_mut_ptr() as *mut c_void)
+        };
+        match status {
+            Status::SUCCESS => Ok(buffer_size),
+            Status::TIMEOUT if buffer_size > 0 => Ok(buffer_size),
+            _ => Err(status),
         }
     }
 
-    pub fn mode(&self) -> *mut Mode {
-        unsafe { (*self.protocol).mode }
+    ///Pointer to SERIAL_IO_MODE data: the current baud rate, timeout, FIFO depth and line settings.
+    pub fn mode(&self) -> Mode {
+        unsafe { *(*self.protocol).mode }
     }
 
     pub fn revision(&self) -> u32 {
         unsafe { (*self.protocol).revision }
     }
-    
+
+    ///Type of the device (e.g. terminal or debug port), only reported by revision 1.1 implementations.
+    pub fn device_type_guid(&self) -> Option<Guid> {
+        if self.revision() < REVISION1P1 {
+            return None;
+        }
+        let guid = unsafe { (*self.protocol).device_type_guid };
+        if guid.is_null() {
+            None
+        } else {
+            Some(unsafe { *guid })
+        }
+    }
+}
+
+///How SerialIO::select() picks a serial device.
+#[derive(Clone, Copy)]
+pub enum PortSelector<'a> {
+    ///Position in the handle database.
+    Index(usize),
+    ///ACPI UID of a PNP0501 serial port: COM1 is 0, COM2 is 1.
+    AcpiUid(u32),
+    ///A device path node the port's device path must contain, e.g. a Uart() or PciRoot() node.
+    Node(Node<'a>),
+    ///The whole device path of the port.
+    DevicePath(DevicePath<'a>),
+}
+
+///A serial device and where it lives.
+pub struct SerialPort {
+    pub handle: Handle,
+    pub io: SerialIO,
+    pub device_path: Option<DevicePath<'static>>,
+}
+impl SerialPort {
+    ///UID of the ACPI PNP0501 node, the COM port number counted from 0.
+    pub fn acpi_uid(&self) -> Option<u32> {
+        self.device_path?.nodes().find_map(|node| match node.kind() {
+            NodeKind::Acpi { hid, uid } if hid == PNP_SERIAL_PORT => Some(uid),
+            _ => None,
+        })
+    }
+
+    ///Device and function of the PCI node closest to the UART, for PCI serial cards.
+    pub fn pci_location(&self) -> Option<(u8, u8)> {
+        self.device_path?.nodes().filter_map(|node| match node.kind() {
+            NodeKind::Pci { function, device } => Some((device, function)),
+            _ => None,
+        }).last()
+    }
+
+    pub fn device_type_guid(&self) -> Option<Guid> {
+        self.io.device_type_guid()
+    }
+
+    pub fn matches(&self, selector: &PortSelector) -> bool {
+        match (selector, self.device_path) {
+            (PortSelector::Index(_), _) => false,
+            (PortSelector::AcpiUid(uid), _) => self.acpi_uid() == Some(*uid),
+            (PortSelector::Node(node), Some(path)) => path.contains(node),
+            (PortSelector::DevicePath(other), Some(path)) => path == *other,
+            _ => false,
+        }
+    }
+}
+
+///Serial devices found by SerialIO::ports().
+pub struct SerialPorts {
+    st: *mut SystemTable,
+    handles: HandleBuffer,
+}
+impl SerialPorts {
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Result<SerialPort, Status> {
+        let handle = *self.handles.as_slice().get(index).ok_or(Status::NOT_FOUND)?;
+        Ok(SerialPort {
+            handle,
+            io: SerialIO::for_handle(self.st, handle)?,
+            device_path: DevicePath::for_handle(self.st, handle).ok(),
+        })
+    }
+
+    ///Iterates over the ports, skipping handles whose protocol could not be opened.
+    pub fn iter(&self) -> impl Iterator<Item = SerialPort> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index).ok())
+    }
 }
@@ -2,6 +2,43 @@ use core::ffi::c_void;
 
 use lib_efi::{efi::{Event, Status, SystemTable}, protocols::simple_text_input::{InputKey, Protocol}};
 
+///Scan codes reported in EFI_INPUT_KEY.ScanCode for keys without a Unicode character.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanCode {
+    Null     = 0x00,
+    Up       = 0x01,
+    Down     = 0x02,
+    Right    = 0x03,
+    Left     = 0x04,
+    Home     = 0x05,
+    End      = 0x06,
+    Insert   = 0x07,
+    Delete   = 0x08,
+    PageUp   = 0x09,
+    PageDown = 0x0A,
+    F1       = 0x0B,
+    F2       = 0x0C,
+    F3       = 0x0D,
+    F4       = 0x0E,
+    F5       = 0x0F,
+    F6       = 0x10,
+    F7       = 0x11,
+    F8       = 0x12,
+    F9       = 0x13,
+    F10      = 0x14,
+    F11      = 0x15,
+    F12      = 0x16,
+    Esc      = 0x17,
+}
+
+///Control characters reported in EFI_INPUT_KEY.UnicodeChar.
+pub const CHAR_NULL: u16 = 0x0000;
+pub const CHAR_BACKSPACE: u16 = 0x0008;
+pub const CHAR_TAB: u16 = 0x0009;
+pub const CHAR_LINEFEED: u16 = 0x000A;
+pub const CHAR_CARRIAGE_RETURN: u16 = 0x000D;
+
 // The Simple Text Input protocol defines the minimum input required to support the ConsoleIn device.
 /// The Simple Text Input protocol defines the minimum input required to support the ConsoleIn device.
 
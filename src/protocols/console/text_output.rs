@@ -3,7 +3,7 @@ use lib_efi::{
     protocols::simple_text_output::Protocol,
 };
 
-
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BackgroundColor {
     Black       = 0x00,
     Blue        = 0x10,
@@ -15,6 +15,7 @@ pub enum BackgroundColor {
     LightGray   = 0x70,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ForegroudColor {
     None         = -1,
     BLACK        = 0x00,
@@ -55,6 +56,24 @@ impl TextOutput {
     pub fn output_string(&self, str: *mut u16) -> Status {
         unsafe { ((*self.protocol).output_string)(self.protocol, str) }
     }
+    ///Displays a Rust string, converted to UCS-2 in small chunks so it can be of any length.
+    pub fn output_str(&self, s: &str) -> Status {
+        let mut buffer = [0u16; 128];
+        let mut len = 0;
+        for c in s.chars() {
+            if len + 3 > buffer.len() {
+                buffer[len] = 0;
+                let status = self.output_string(buffer.as_mut_ptr());
+                if status.is_error() {
+                    return status;
+                }
+                len = 0;
+            }
+            len += c.encode_utf16(&mut buffer[len..]).len();
+        }
+        buffer[len] = 0;
+        self.output_string(buffer.as_mut_ptr())
+    }
     ///Tests to see if the ConsoleOut device supports this string.
     pub fn test_string(&self, str: *mut u16) -> Status {
         unsafe { ((*self.protocol).test_string)(self.protocol, str) }
@@ -91,6 +110,15 @@ impl TextOutput {
         let attribute: usize = foreground as usize | ((background as usize) << 4);
         unsafe { ((*self.protocol).set_attribute)(self.protocol, attribute) }
     }
+    ///The current attribute, from the mode of the protocol: the foreground color in bits 0-3 and the
+    ///background color in bits 4-6.
+    pub fn attribute(&self) -> usize {
+        unsafe { (*(*self.protocol).mode).attribute as usize }
+    }
+    ///Sets an attribute read with attribute(), to restore it after changing the colors.
+    pub fn restore_attribute(&self, attribute: usize) -> Status {
+        unsafe { ((*self.protocol).set_attribute)(self.protocol, attribute) }
+    }
     ///Clears the output device(s) display to the currently selected background color.
     /// The ClearScreen() function clears the output device(s) display to the currently selected background color. The cursor position is set to (0, 0).
     pub fn clear_screen(&self) -> Status {
@@ -0,0 +1,378 @@
+use core::convert::TryFrom;
+
+use lib_efi::{
+    efi::Status,
+    protocols::absolute_pointer::{Mode, State},
+};
+
+use crate::services::variable::{Variable, NON_VOLATILE_BOOT_ACCESS, VENDOR_GUID};
+
+use super::{
+    absolute_pointer::{AbsolutePointer, ActiveButton, Attribute},
+    graphic_output::Coordinate,
+    pointer::{PointerSample, PointerSource},
+};
+
+///Name of the NVRAM variable holding the calibration matrix.
+pub const CALIBRATION_VARIABLE: &str = "TouchCalibration";
+
+const FRACTION_BITS: u32 = 16;
+const ONE: i32 = 1 << FRACTION_BITS;
+
+///Affine transform applied to scaled touch coordinates, in 16.16 fixed point.
+///x' = a * x + b * y + c
+///y' = d * x + e * y + f
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Calibration {
+    pub a: i32,
+    pub b: i32,
+    pub c: i32,
+    pub d: i32,
+    pub e: i32,
+    pub f: i32,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration::IDENTITY
+    }
+}
+
+impl Calibration {
+    pub const IDENTITY: Calibration = Calibration {
+        a: ONE,
+        b: 0,
+        c: 0,
+        d: 0,
+        e: ONE,
+        f: 0,
+    };
+    const SIZE: usize = 24;
+
+    ///Computes the transform moving three touched points onto the three targets that were displayed.
+    ///The touched points must come from TouchScreen::scale(), i.e. without any calibration applied.
+    ///Returns None if the touched points are colinear.
+    pub fn from_samples(touched: [Coordinate; 3], targets: [Coordinate; 3]) -> Option<Calibration> {
+        let (x0, y0) = (touched[0].x as i64, touched[0].y as i64);
+        let (x1, y1) = (touched[1].x as i64, touched[1].y as i64);
+        let (x2, y2) = (touched[2].x as i64, touched[2].y as i64);
+        let det = (x0 - x2) * (y1 - y2) - (x1 - x2) * (y0 - y2);
+        if det == 0 {
+            return None;
+        }
+        let solve = |t0: i64, t1: i64, t2: i64| -> Option<(i32, i32, i32)> {
+            let p = (((t0 - t2) * (y1 - y2) - (t1 - t2) * (y0 - y2)) << FRACTION_BITS) / det;
+            let q = (((x0 - x2) * (t1 - t2) - (x1 - x2) * (t0 - t2)) << FRACTION_BITS) / det;
+            let r = (t0 << FRACTION_BITS) - p * x0 - q * y0;
+            Some((i32::try_from(p).ok()?, i32::try_from(q).ok()?, i32::try_from(r).ok()?))
+        };
+        let (a, b, c) = solve(targets[0].x as i64, targets[1].x as i64, targets[2].x as i64)?;
+        let (d, e, f) = solve(targets[0].y as i64, targets[1].y as i64, targets[2].y as i64)?;
+        Some(Calibration { a, b, c, d, e, f })
+    }
+
+    ///Applies the transform to a point. The result may lie outside of the screen.
+    pub fn apply(&self, point: Coordinate) -> (i64, i64) {
+        let (x, y) = (point.x as i64, point.y as i64);
+        (
+            (self.a as i64 * x + self.b as i64 * y + self.c as i64) >> FRACTION_BITS,
+            (self.d as i64 * x + self.e as i64 * y + self.f as i64) >> FRACTION_BITS,
+        )
+    }
+
+    pub fn to_bytes(&self) -> [u8; Calibration::SIZE] {
+        let mut bytes = [0u8; Calibration::SIZE];
+        let values = [self.a, self.b, self.c, self.d, self.e, self.f];
+        for (chunk, value) in bytes.chunks_mut(4).zip(values.iter()) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Calibration> {
+        if bytes.len() != Calibration::SIZE {
+            return None;
+        }
+        let mut values = [0i32; 6];
+        for (value, chunk) in values.iter_mut().zip(bytes.chunks(4)) {
+            *value = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Some(Calibration {
+            a: values[0],
+            b: values[1],
+            c: values[2],
+            d: values[3],
+            e: values[4],
+            f: values[5],
+        })
+    }
+
+    ///Reads the calibration stored in NVRAM by save().
+    pub fn load(variable: &Variable) -> Result<Calibration, Status> {
+        let mut buffer = [0u8; Calibration::SIZE];
+        let (size, _) = variable.get(CALIBRATION_VARIABLE, VENDOR_GUID, &mut buffer)?;
+        Calibration::from_bytes(&buffer[..size]).ok_or(Status::VOLUME_CORRUPTED)
+    }
+
+    ///Stores the calibration in a non-volatile variable so it survives reboots.
+    pub fn save(&self, variable: &Variable) -> Status {
+        variable.set(CALIBRATION_VARIABLE, VENDOR_GUID, NON_VOLATILE_BOOT_ACCESS, &self.to_bytes())
+    }
+}
+
+///A pointer state mapped to screen pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TouchSample {
+    pub position: Coordinate,
+    pub touching: bool,
+    pub alt_active: bool,
+    ///Pressure relative to the minimum Z value, if the Z axis reports pressure.
+    pub pressure: Option<u64>,
+}
+
+///Maps an absolute pointer device onto a screen of the given size.
+pub struct TouchScreen {
+    pointer: AbsolutePointer,
+    mode: Mode,
+    width: usize,
+    height: usize,
+    calibration: Calibration,
+}
+impl TouchScreen {
+    pub fn new(pointer: AbsolutePointer, width: usize, height: usize) -> TouchScreen {
+        TouchScreen {
+            mode: pointer.mode(),
+            pointer,
+            width,
+            height,
+            calibration: Calibration::IDENTITY,
+        }
+    }
+
+    pub fn pointer(&self) -> &AbsolutePointer {
+        &self.pointer
+    }
+
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = calibration;
+    }
+
+    ///Changes the screen size, e.g. after GraphicOutput::set_mode().
+    pub fn set_screen_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+    }
+
+    ///Scales device coordinates to screen pixels without applying the calibration.
+    pub fn scale(&self, state: &State) -> Coordinate {
+        Coordinate {
+            x: scale_axis(state.current_x, self.mode.absolute_min_x, self.mode.absolute_max_x, self.width),
+            y: scale_axis(state.current_y, self.mode.absolute_min_y, self.mode.absolute_max_y, self.height),
+        }
+    }
+
+    ///Maps device coordinates to calibrated screen pixels, clamped to the screen.
+    pub fn map(&self, state: &State) -> Coordinate {
+        let (x, y) = self.calibration.apply(self.scale(state));
+        Coordinate {
+            x: x.clamp(0, self.width.saturating_sub(1) as i64) as usize,
+            y: y.clamp(0, self.height.saturating_sub(1) as i64) as usize,
+        }
+    }
+
+    pub fn sample(&self, state: &State) -> TouchSample {
+        let pressure = if self.mode.attributes & Attribute::PressureSupported as u32 != 0 {
+            Some(state.current_z.saturating_sub(self.mode.absolute_min_z))
+        } else {
+            None
+        };
+        TouchSample {
+            position: self.map(state),
+            touching: ActiveButton::TouchActive.is_pressed(state),
+            alt_active: ActiveButton::AltActive.is_pressed(state),
+            pressure,
+        }
+    }
+
+    ///Retrieves the current state of the device, mapped to the screen.
+    ///EFI_NOT_READY is returned when the state did not change since the last call.
+    pub fn read(&self) -> Result<TouchSample, Status> {
+        self.pointer.get_state().map(|state| self.sample(&state))
+    }
+}
+
+impl PointerSource for TouchScreen {
+    fn poll(&mut self) -> Result<Option<PointerSample>, Status> {
+        match self.read() {
+            Ok(sample) => Ok(Some(PointerSample {
+                position: sample.position,
+                primary: sample.touching,
+                secondary: sample.alt_active,
+            })),
+            Err(Status::NOT_READY) => Ok(None),
+            Err(status) => Err(status),
+        }
+    }
+}
+
+fn scale_axis(value: u64, min: u64, max: u64, size: usize) -> usize {
+    if size == 0 {
+        return 0;
+    }
+    if max <= min {
+        // Some devices leave the range empty and report screen coordinates directly.
+        return core::cmp::min(value, size as u64 - 1) as usize;
+    }
+    let offset = value.clamp(min, max) - min;
+    (offset as u128 * (size as u128 - 1) / (max - min) as u128) as usize
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gesture {
+    Tap(Coordinate),
+    LongPress(Coordinate),
+    Swipe {
+        from: Coordinate,
+        to: Coordinate,
+        direction: SwipeDirection,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GestureConfig {
+    ///Longest contact, in milliseconds, still reported as a tap.
+    pub tap_max_ms: u64,
+    ///Contact duration, in milliseconds, after which a stationary touch is a long press.
+    pub long_press_ms: u64,
+    ///Distance in pixels a contact may drift and still count as stationary.
+    pub move_tolerance: usize,
+    ///Minimum distance in pixels between touch-down and release for a swipe.
+    pub swipe_min_distance: usize,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        GestureConfig {
+            tap_max_ms: 300,
+            long_press_ms: 700,
+            move_tolerance: 12,
+            swipe_min_distance: 60,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Contact {
+    start: Coordinate,
+    start_ms: u64,
+    last: Coordinate,
+    moved: bool,
+    long_press_reported: bool,
+}
+
+///Turns a stream of touch samples into taps, long presses and swipes.
+///The caller provides the time of each update, in milliseconds, from any monotonic source.
+pub struct GestureDetector {
+    config: GestureConfig,
+    contact: Option<Contact>,
+}
+impl GestureDetector {
+    pub fn new(config: GestureConfig) -> GestureDetector {
+        GestureDetector {
+            config,
+            contact: None,
+        }
+    }
+
+    ///Feeds the detector. Pass None when the device reported no new state (EFI_NOT_READY),
+    ///so a long press is still detected while the finger rests on the panel.
+    pub fn update(&mut self, sample: Option<&TouchSample>, now_ms: u64) -> Option<Gesture> {
+        let touching = match sample {
+            Some(sample) => sample.touching,
+            None => self.contact.is_some(),
+        };
+        match (self.contact.as_mut(), touching) {
+            (None, true) => {
+                let position = sample?.position;
+                self.contact = Some(Contact {
+                    start: position,
+                    start_ms: now_ms,
+                    last: position,
+                    moved: false,
+                    long_press_reported: false,
+                });
+                None
+            }
+            (Some(contact), true) => {
+                if let Some(sample) = sample {
+                    contact.last = sample.position;
+                }
+                if distance(contact.start, contact.last) > self.config.move_tolerance {
+                    contact.moved = true;
+                }
+                if !contact.moved
+                    && !contact.long_press_reported
+                    && now_ms.saturating_sub(contact.start_ms) >= self.config.long_press_ms
+                {
+                    contact.long_press_reported = true;
+                    return Some(Gesture::LongPress(contact.start));
+                }
+                None
+            }
+            (Some(contact), false) => {
+                let contact = *contact;
+                self.contact = None;
+                if contact.long_press_reported {
+                    return None;
+                }
+                if distance(contact.start, contact.last) >= self.config.swipe_min_distance {
+                    return Some(Gesture::Swipe {
+                        from: contact.start,
+                        to: contact.last,
+                        direction: direction(contact.start, contact.last),
+                    });
+                }
+                if !contact.moved && now_ms.saturating_sub(contact.start_ms) <= self.config.tap_max_ms {
+                    return Some(Gesture::Tap(contact.start));
+                }
+                None
+            }
+            (None, false) => None,
+        }
+    }
+
+    ///Forgets the current contact, e.g. when the screen content changed under the finger.
+    pub fn cancel(&mut self) {
+        self.contact = None;
+    }
+}
+
+fn distance(a: Coordinate, b: Coordinate) -> usize {
+    core::cmp::max(a.x.abs_diff(b.x), a.y.abs_diff(b.y))
+}
+
+fn direction(from: Coordinate, to: Coordinate) -> SwipeDirection {
+    if from.x.abs_diff(to.x) >= from.y.abs_diff(to.y) {
+        if to.x >= from.x {
+            SwipeDirection::Right
+        } else {
+            SwipeDirection::Left
+        }
+    } else if to.y >= from.y {
+        SwipeDirection::Down
+    } else {
+        SwipeDirection::Up
+    }
+}
@@ -0,0 +1,930 @@
+use core::fmt::{self, Write};
+
+use lib_efi::efi::Status;
+
+use crate::protocols::media::file::{Attribute, File, OpenMode};
+
+use super::serial_io::SerialIO;
+
+const SOH: u8 = 0x01;
+const STX: u8 = 0x02;
+const EOT: u8 = 0x04;
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1A;
+///Sent by the receiver to start a transfer in CRC mode.
+const CRC_REQUEST: u8 = b'C';
+
+const BLOCK_SIZE: usize = 128;
+const BLOCK_SIZE_1K: usize = 1024;
+///Header, block number and its complement before the data, CRC after it.
+const PACKET_OVERHEAD: usize = 5;
+
+///Byte stream the transfers run over. Implemented by SerialIO; a loopback mock can stand in
+///for it on the host.
+pub trait Channel {
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Status>;
+    ///Reads one byte, waiting at most timeout_ms. Returns Ok(None) when the timeout expired.
+    fn read_byte(&mut self, timeout_ms: u32) -> Result<Option<u8>, Status>;
+}
+
+impl Channel for SerialIO {
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Status> {
+        SerialIO::write_all(self, data)
+    }
+
+    fn read_byte(&mut self, timeout_ms: u32) -> Result<Option<u8>, Status> {
+        // Each Read() waits for the device timeout, in microseconds.
+        let step_ms = (self.mode().timeout / 1000).max(1);
+        let mut waited = 0;
+        let mut byte = [0u8; 1];
+        loop {
+            match self.read(&mut byte) {
+                Ok(1) => return Ok(Some(byte[0])),
+                Ok(_) | Err(Status::TIMEOUT) => {}
+                Err(status) => return Err(status),
+            }
+            waited += step_ms;
+            if waited >= timeout_ms {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+pub struct Config {
+    ///Number of times a packet or a start request is repeated before giving up.
+    pub retries: u32,
+    ///Delay between two start requests of the receiver, or the sender's wait for one.
+    pub start_timeout_ms: u32,
+    ///Time allowed for the next packet to begin.
+    pub packet_timeout_ms: u32,
+    ///Time allowed between two bytes of a packet.
+    pub byte_timeout_ms: u32,
+    ///Send 1024 byte blocks (XMODEM-1K). YMODEM always uses them.
+    pub block_1k: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            retries: 10,
+            start_timeout_ms: 3000,
+            packet_timeout_ms: 10_000,
+            byte_timeout_ms: 1000,
+            block_1k: true,
+        }
+    }
+}
+
+///Reported after every block.
+pub struct Progress<'a> {
+    ///Name of the file in a YMODEM batch, empty for XMODEM.
+    pub file_name: &'a str,
+    pub transferred: u64,
+    ///Size of the file, when known.
+    pub total: Option<u64>,
+    ///Packets repeated so far because of errors.
+    pub retries: u32,
+}
+
+///CRC-16/XMODEM (polynomial 0x1021, initial value 0).
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+enum Packet {
+    Block { number: u8, len: usize },
+    End,
+    Cancel,
+}
+
+fn cancel<C: Channel>(channel: &mut C) {
+    let _ = channel.write_all(&[CAN, CAN, CAN]);
+}
+
+struct Receiver<'a, C: Channel> {
+    channel: &'a mut C,
+    config: &'a Config,
+    buffer: [u8; BLOCK_SIZE_1K],
+    retries: u32,
+}
+impl<'a, C: Channel> Receiver<'a, C> {
+    fn new(channel: &'a mut C, config: &'a Config) -> Receiver<'a, C> {
+        Receiver {
+            channel,
+            config,
+            buffer: [0; BLOCK_SIZE_1K],
+            retries: 0,
+        }
+    }
+
+    fn read(&mut self) -> Result<u8, Status> {
+        self.channel.read_byte(self.config.byte_timeout_ms)?.ok_or(Status::TIMEOUT)
+    }
+
+    ///Discards input until the line is quiet, to resynchronize after a bad packet.
+    fn purge(&mut self) -> Result<(), Status> {
+        while self.channel.read_byte(self.config.byte_timeout_ms)?.is_some() {}
+        Ok(())
+    }
+
+    ///Receives one packet. Returns None when nothing valid arrived in time.
+    fn receive_packet(&mut self, timeout_ms: u32) -> Result<Option<Packet>, Status> {
+        let len = match self.channel.read_byte(timeout_ms)? {
+            None => return Ok(None),
+            Some(SOH) => BLOCK_SIZE,
+            Some(STX) => BLOCK_SIZE_1K,
+            Some(EOT) => return Ok(Some(Packet::End)),
+            Some(CAN) => {
+                return match self.channel.read_byte(self.config.byte_timeout_ms)? {
+                    Some(CAN) => Ok(Some(Packet::Cancel)),
+                    _ => Ok(None),
+                }
+            }
+            Some(_) => {
+                self.purge()?;
+                return Ok(None);
+            }
+        };
+        match self.receive_body(len) {
+            Ok(Some(number)) => Ok(Some(Packet::Block { number, len })),
+            Ok(None) | Err(Status::TIMEOUT) => {
+                self.purge()?;
+                Ok(None)
+            }
+            Err(status) => Err(status),
+        }
+    }
+
+    ///Reads the block number, data and CRC of a packet. Returns None if they do not check out.
+    fn receive_body(&mut self, len: usize) -> Result<Option<u8>, Status> {
+        let number = self.read()?;
+        let complement = self.read()?;
+        for i in 0..len {
+            self.buffer[i] = self.read()?;
+        }
+        let crc = (self.read()? as u16) << 8 | self.read()? as u16;
+        if complement != !number || crc != crc16(&self.buffer[..len]) {
+            return Ok(None);
+        }
+        Ok(Some(number))
+    }
+
+    ///Sends start requests until the sender answers with a packet.
+    fn start(&mut self) -> Result<Packet, Status> {
+        for _ in 0..self.config.retries {
+            self.channel.write_all(&[CRC_REQUEST])?;
+            if let Some(packet) = self.receive_packet(self.config.start_timeout_ms)? {
+                return Ok(packet);
+            }
+        }
+        cancel(self.channel);
+        Err(Status::TIMEOUT)
+    }
+
+    ///Answers the last packet and waits for the next one, asking for retransmission on errors.
+    fn next(&mut self, reply: u8) -> Result<Packet, Status> {
+        let mut reply = reply;
+        for _ in 0..=self.config.retries {
+            self.channel.write_all(&[reply])?;
+            if let Some(packet) = self.receive_packet(self.config.packet_timeout_ms)? {
+                return Ok(packet);
+            }
+            reply = NAK;
+            self.retries += 1;
+        }
+        cancel(self.channel);
+        Err(Status::TIMEOUT)
+    }
+
+    ///Receives data blocks, starting with first, until the sender ends the file.
+    ///With a known size the padding of the last block is dropped. YMODEM senders expect the
+    ///first EOT to be refused.
+    fn receive_data(
+        &mut self,
+        first: Packet,
+        file_name: &str,
+        size: Option<u64>,
+        ymodem: bool,
+        write: &mut dyn FnMut(&[u8]) -> Result<(), Status>,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<u64, Status> {
+        let mut expected: u8 = 1;
+        let mut received: u64 = 0;
+        let mut end_refused = !ymodem;
+        let mut packet = first;
+        loop {
+            let reply = match packet {
+                Packet::Block { number, len } if number == expected => {
+                    let len = match size {
+                        Some(size) => (len as u64).min(size.saturating_sub(received)) as usize,
+                        None => len,
+                    };
+                    if let Err(status) = write(&self.buffer[..len]) {
+                        cancel(self.channel);
+                        return Err(status);
+                    }
+                    received += len as u64;
+                    expected = expected.wrapping_add(1);
+                    progress(Progress {
+                        file_name,
+                        transferred: received,
+                        total: size,
+                        retries: self.retries,
+                    });
+                    ACK
+                }
+                // The ACK of the YMODEM header was lost with the data request: send both again.
+                Packet::Block { number: 0, .. } if ymodem && expected == 1 => {
+                    self.channel.write_all(&[ACK])?;
+                    CRC_REQUEST
+                }
+                // Our ACK was lost and the sender repeated the block.
+                Packet::Block { number, .. } if number == expected.wrapping_sub(1) => ACK,
+                Packet::Block { .. } => {
+                    cancel(self.channel);
+                    return Err(Status::PROTOCOL_ERROR);
+                }
+                Packet::End if end_refused => {
+                    self.channel.write_all(&[ACK])?;
+                    return Ok(received);
+                }
+                Packet::End => {
+                    end_refused = true;
+                    NAK
+                }
+                Packet::Cancel => return Err(Status::ABORTED),
+            };
+            packet = self.next(reply)?;
+        }
+    }
+}
+
+struct Sender<'a, C: Channel> {
+    channel: &'a mut C,
+    config: &'a Config,
+    packet: [u8; BLOCK_SIZE_1K + PACKET_OVERHEAD],
+    retries: u32,
+}
+impl<'a, C: Channel> Sender<'a, C> {
+    fn new(channel: &'a mut C, config: &'a Config) -> Sender<'a, C> {
+        Sender {
+            channel,
+            config,
+            packet: [0; BLOCK_SIZE_1K + PACKET_OVERHEAD],
+            retries: 0,
+        }
+    }
+
+    fn data(&mut self) -> &mut [u8] {
+        &mut self.packet[3..3 + BLOCK_SIZE_1K]
+    }
+
+    ///Waits for the receiver to request a file in CRC mode.
+    fn wait_request(&mut self) -> Result<(), Status> {
+        for _ in 0..self.config.retries {
+            match self.channel.read_byte(self.config.start_timeout_ms)? {
+                Some(CRC_REQUEST) => return Ok(()),
+                Some(CAN) if self.channel.read_byte(self.config.byte_timeout_ms)? == Some(CAN) => {
+                    return Err(Status::ABORTED)
+                }
+                _ => {}
+            }
+        }
+        cancel(self.channel);
+        Err(Status::TIMEOUT)
+    }
+
+    ///Sends the filled bytes of data() as one block, padded with padding, until it is acknowledged.
+    fn send_block(&mut self, number: u8, filled: usize, padding: u8) -> Result<(), Status> {
+        self.send_block_until(number, filled, padding, &[ACK]).map(|_| ())
+    }
+
+    ///Sends a block until the receiver answers with one of replies, and returns the answer.
+    fn send_block_until(&mut self, number: u8, filled: usize, padding: u8, replies: &[u8]) -> Result<u8, Status> {
+        let len = if filled > BLOCK_SIZE { BLOCK_SIZE_1K } else { BLOCK_SIZE };
+        self.packet[0] = if len == BLOCK_SIZE { SOH } else { STX };
+        self.packet[1] = number;
+        self.packet[2] = !number;
+        self.data()[filled..len].fill(padding);
+        let crc = crc16(&self.data()[..len]);
+        self.packet[3 + len] = (crc >> 8) as u8;
+        self.packet[4 + len] = crc as u8;
+        for _ in 0..=self.config.retries {
+            self.channel.write_all(&self.packet[..len + PACKET_OVERHEAD])?;
+            match self.channel.read_byte(self.config.packet_timeout_ms)? {
+                Some(reply) if replies.contains(&reply) => return Ok(reply),
+                Some(CAN) if self.channel.read_byte(self.config.byte_timeout_ms)? == Some(CAN) => {
+                    return Err(Status::ABORTED)
+                }
+                _ => self.retries += 1,
+            }
+        }
+        cancel(self.channel);
+        Err(Status::TIMEOUT)
+    }
+
+    ///Sends block 0 of a YMODEM file, holding filled bytes of data(), and waits for the receiver
+    ///to request the data. A request arriving instead of the ACK means the ACK was lost.
+    fn send_header(&mut self, filled: usize) -> Result<(), Status> {
+        // Start requests repeated while the receiver waited for us must not pass for that one.
+        while self.channel.read_byte(0)?.is_some() {}
+        match self.send_block_until(0, filled, 0, &[ACK, CRC_REQUEST])? {
+            CRC_REQUEST => Ok(()),
+            _ => self.wait_request(),
+        }
+    }
+
+    fn send_end(&mut self) -> Result<(), Status> {
+        for _ in 0..=self.config.retries {
+            self.channel.write_all(&[EOT])?;
+            if self.channel.read_byte(self.config.packet_timeout_ms)? == Some(ACK) {
+                return Ok(());
+            }
+        }
+        Err(Status::TIMEOUT)
+    }
+
+    ///Sends everything read returns, then ends the file.
+    fn send_data(
+        &mut self,
+        file_name: &str,
+        total: Option<u64>,
+        block_1k: bool,
+        read: &mut dyn FnMut(&mut [u8]) -> Result<usize, Status>,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<u64, Status> {
+        let block_size = if block_1k { BLOCK_SIZE_1K } else { BLOCK_SIZE };
+        let mut number: u8 = 1;
+        let mut sent: u64 = 0;
+        loop {
+            let mut filled = 0;
+            while filled < block_size {
+                match read(&mut self.data()[filled..block_size]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(status) => {
+                        cancel(self.channel);
+                        return Err(status);
+                    }
+                }
+            }
+            if filled == 0 {
+                break;
+            }
+            self.send_block(number, filled, SUB)?;
+            number = number.wrapping_add(1);
+            sent += filled as u64;
+            progress(Progress {
+                file_name,
+                transferred: sent,
+                total,
+                retries: self.retries,
+            });
+        }
+        self.send_end()?;
+        Ok(sent)
+    }
+}
+
+///Receives one file with XMODEM-CRC or XMODEM-1K, passing its data to write.
+///XMODEM does not transmit the size: the last block keeps its SUB (0x1A) padding.
+pub fn receive<C: Channel>(
+    channel: &mut C,
+    config: &Config,
+    write: &mut dyn FnMut(&[u8]) -> Result<(), Status>,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<u64, Status> {
+    let mut receiver = Receiver::new(channel, config);
+    let first = receiver.start()?;
+    receiver.receive_data(first, "", None, false, write, progress)
+}
+
+///Sends the data returned by read with XMODEM-CRC, or XMODEM-1K if config.block_1k is set.
+pub fn send<C: Channel>(
+    channel: &mut C,
+    config: &Config,
+    total: Option<u64>,
+    read: &mut dyn FnMut(&mut [u8]) -> Result<usize, Status>,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<u64, Status> {
+    let mut sender = Sender::new(channel, config);
+    sender.wait_request()?;
+    sender.send_data("", total, config.block_1k, read, progress)
+}
+
+///Receives one file with XMODEM into a file opened for writing.
+pub fn receive_file<C: Channel>(
+    channel: &mut C,
+    config: &Config,
+    file: &File,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<u64, Status> {
+    receive(channel, config, &mut |data| file.write_all(data), progress)
+}
+
+///Sends a file opened for reading with XMODEM.
+pub fn send_file<C: Channel>(
+    channel: &mut C,
+    config: &Config,
+    file: &File,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<u64, Status> {
+    let total = file_size(file)?;
+    send(channel, config, Some(total), &mut |buffer| file.read(buffer), progress)
+}
+
+///Where the files of a received YMODEM batch go. Implemented by Directory for a UEFI volume.
+pub trait BatchSink {
+    ///Starts a file, with the size sent along, if any.
+    fn create(&mut self, name: &str, size: Option<u64>) -> Result<(), Status>;
+    fn write(&mut self, data: &[u8]) -> Result<(), Status>;
+    ///Ends the file started last, whether its transfer succeeded or not.
+    fn close(&mut self);
+}
+
+///The files of a YMODEM batch to send. Implemented by Directory for a UEFI volume.
+pub trait BatchSource {
+    ///Opens a file for reading and returns its size.
+    fn open(&mut self, name: &str) -> Result<u64, Status>;
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Status>;
+    fn close(&mut self);
+}
+
+///A directory of a UEFI volume, as the source or the destination of a batch. Received files
+///replace existing ones.
+pub struct Directory<'a> {
+    directory: &'a File,
+    file: Option<File>,
+}
+impl<'a> Directory<'a> {
+    pub fn new(directory: &'a File) -> Directory<'a> {
+        Directory { directory, file: None }
+    }
+
+    fn file(&self) -> Result<&File, Status> {
+        self.file.as_ref().ok_or(Status::NOT_READY)
+    }
+}
+
+impl BatchSink for Directory<'_> {
+    fn create(&mut self, name: &str, _size: Option<u64>) -> Result<(), Status> {
+        if let Ok(existing) = self.directory.open(name, OpenMode::ReadWrite, Attribute::None) {
+            existing.delete();
+        }
+        self.file = Some(self.directory.open(name, OpenMode::ReadWriteCreate, Attribute::None)?);
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Status> {
+        self.file()?.write_all(data)
+    }
+
+    fn close(&mut self) {
+        if let Some(file) = self.file.take() {
+            file.close();
+        }
+    }
+}
+
+impl BatchSource for Directory<'_> {
+    fn open(&mut self, name: &str) -> Result<u64, Status> {
+        let file = self.directory.open(name, OpenMode::Read, Attribute::None)?;
+        let size = file_size(&file);
+        self.file = Some(file);
+        size
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Status> {
+        self.file()?.read(buffer)
+    }
+
+    fn close(&mut self) {
+        BatchSink::close(self)
+    }
+}
+
+///Receives a YMODEM batch, creating every file in directory (existing files are replaced).
+///Returns the number of files received.
+pub fn receive_batch<C: Channel>(
+    channel: &mut C,
+    config: &Config,
+    directory: &File,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<usize, Status> {
+    receive_batch_to(channel, config, &mut Directory::new(directory), progress)
+}
+
+///Receives a YMODEM batch into sink. Returns the number of files received.
+pub fn receive_batch_to<C: Channel, S: BatchSink>(
+    channel: &mut C,
+    config: &Config,
+    sink: &mut S,
+    progress: &mut dyn FnMut(Progress),
+) -> Result<usize, Status> {
+    let mut receiver = Receiver::new(channel, config);
+    let mut count = 0;
+    loop {
+        let len = match receiver.start()? {
+            Packet::Block { number: 0, len } => len,
+            // The ACK of the previous end of file was lost.
+            Packet::End if count > 0 => {
+                receiver.channel.write_all(&[ACK])?;
+                continue;
+            }
+            Packet::Cancel => return Err(Status::ABORTED),
+            _ => {
+                cancel(receiver.channel);
+                return Err(Status::PROTOCOL_ERROR);
+            }
+        };
+        let mut name = [0u8; BLOCK_SIZE];
+        let (name_len, size) = parse_header(&receiver.buffer[..len], &mut name);
+        if name_len == 0 {
+            receiver.channel.write_all(&[ACK])?;
+            return Ok(count);
+        }
+        let name = match core::str::from_utf8(&name[..name_len]) {
+            Ok(name) => name,
+            Err(_) => {
+                cancel(receiver.channel);
+                return Err(Status::INVALID_PARAMETER);
+            }
+        };
+        if let Err(status) = sink.create(name, size) {
+            cancel(receiver.channel);
+            return Err(status);
+        }
+        receiver.channel.write_all(&[ACK])?;
+        let result = receiver
+            .next(CRC_REQUEST)
+            .and_then(|first| receiver.receive_data(first, name, size, true, &mut |data| sink.write(data), progress));
+        sink.close();
+        result?;
+        count += 1;
+    }
+}
+
+///Sends the named files of directory as a YMODEM batch.
+pub fn send_batch<C: Channel>(
+    channel: &mut C,
+    config: &Config,
+    directory: &File,
+    names: &[&str],
+    progress: &mut dyn FnMut(Progress),
+) -> Result<(), Status> {
+    send_batch_from(channel, config, &mut Directory::new(directory), names, progress)
+}
+
+///Sends the named files of source as a YMODEM batch.
+pub fn send_batch_from<C: Channel, S: BatchSource>(
+    channel: &mut C,
+    config: &Config,
+    source: &mut S,
+    names: &[&str],
+    progress: &mut dyn FnMut(Progress),
+) -> Result<(), Status> {
+    let mut sender = Sender::new(channel, config);
+    for &name in names {
+        let size = source.open(name)?;
+        let result = sender.wait_request().and_then(|()| {
+            let filled = write_header(sender.data(), name, size);
+            sender.send_header(filled)?;
+            sender.send_data(name, Some(size), true, &mut |buffer| source.read(buffer), progress)
+        });
+        source.close();
+        result?;
+    }
+    sender.wait_request()?;
+    sender.send_block(0, 0, 0)
+}
+
+fn file_size(file: &File) -> Result<u64, Status> {
+    let status = file.set_position(u64::MAX);
+    if status != Status::SUCCESS {
+        return Err(status);
+    }
+    let size = file.get_position()?;
+    let status = file.set_position(0);
+    if status != Status::SUCCESS {
+        return Err(status);
+    }
+    Ok(size)
+}
+
+///Block 0 of a YMODEM file: its name, a NUL, then its size in decimal.
+///Paths use '/' on the line and '\' on UEFI volumes.
+fn write_header(data: &mut [u8], name: &str, size: u64) -> usize {
+    struct Cursor<'a> {
+        data: &'a mut [u8],
+        len: usize,
+    }
+    impl Write for Cursor<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > self.data.len() {
+                return Err(fmt::Error);
+            }
+            self.data[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+    let mut cursor = Cursor { data, len: 0 };
+    for c in name.chars() {
+        let _ = cursor.write_char(if c == '\\' { '/' } else { c });
+    }
+    let _ = write!(cursor, "\0{}", size);
+    cursor.len
+}
+
+///Parses block 0, copying the name into name. Returns its length and the size, if sent.
+fn parse_header(data: &[u8], name: &mut [u8]) -> (usize, Option<u64>) {
+    let name_len = data.iter().position(|&b| b == 0).unwrap_or(data.len()).min(name.len());
+    for (dst, &src) in name.iter_mut().zip(&data[..name_len]) {
+        *dst = if src == b'/' { b'\\' } else { src };
+    }
+    let rest = data.get(name_len + 1..).unwrap_or(&[]);
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    let size = core::str::from_utf8(&rest[..digits]).ok().and_then(|s| s.parse().ok());
+    (name_len, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::VecDeque,
+        string::{String, ToString},
+        sync::{Condvar, Mutex},
+        thread,
+        time::Duration,
+        vec::Vec,
+    };
+
+    ///One direction of a loopback serial line.
+    #[derive(Default)]
+    struct Pipe {
+        bytes: Mutex<VecDeque<u8>>,
+        ready: Condvar,
+    }
+
+    ///One end of the line. The nth single byte write of dropped.0 is lost, counting from 1.
+    struct Endpoint<'a> {
+        input: &'a Pipe,
+        output: &'a Pipe,
+        dropped: Option<(u8, usize)>,
+        seen: usize,
+    }
+    impl<'a> Endpoint<'a> {
+        fn new(input: &'a Pipe, output: &'a Pipe) -> Endpoint<'a> {
+            Endpoint { input, output, dropped: None, seen: 0 }
+        }
+
+        fn dropping(mut self, byte: u8, nth: usize) -> Endpoint<'a> {
+            self.dropped = Some((byte, nth));
+            self
+        }
+    }
+
+    impl Channel for Endpoint<'_> {
+        fn write_all(&mut self, data: &[u8]) -> Result<(), Status> {
+            if let Some((byte, nth)) = self.dropped {
+                if data == [byte] {
+                    self.seen += 1;
+                    if self.seen == nth {
+                        return Ok(());
+                    }
+                }
+            }
+            self.output.bytes.lock().unwrap().extend(data);
+            self.output.ready.notify_all();
+            Ok(())
+        }
+
+        fn read_byte(&mut self, timeout_ms: u32) -> Result<Option<u8>, Status> {
+            let bytes = self.input.bytes.lock().unwrap();
+            let timeout = Duration::from_millis(timeout_ms as u64);
+            let (mut bytes, _) = self.input.ready.wait_timeout_while(bytes, timeout, |bytes| bytes.is_empty()).unwrap();
+            Ok(bytes.pop_front())
+        }
+    }
+
+    fn config(block_1k: bool) -> Config {
+        Config {
+            retries: 5,
+            start_timeout_ms: 100,
+            packet_timeout_ms: 300,
+            byte_timeout_ms: 100,
+            block_1k,
+        }
+    }
+
+    fn pattern(len: usize, seed: u8) -> Vec<u8> {
+        (0..len).map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed)).collect()
+    }
+
+    ///Sends data with XMODEM from one end while the other receives it.
+    fn xmodem(data: &[u8], block_1k: bool, receiver_drops: Option<(u8, usize)>) -> Vec<u8> {
+        let (to_receiver, to_sender) = (Pipe::default(), Pipe::default());
+        let config = config(block_1k);
+        thread::scope(|scope| {
+            let sender = scope.spawn(|| {
+                let mut channel = Endpoint::new(&to_sender, &to_receiver);
+                let mut rest = data;
+                let mut read = |buffer: &mut [u8]| {
+                    let len = buffer.len().min(rest.len());
+                    buffer[..len].copy_from_slice(&rest[..len]);
+                    rest = &rest[len..];
+                    Ok(len)
+                };
+                send(&mut channel, &config, Some(data.len() as u64), &mut read, &mut |_| {})
+            });
+            let mut channel = Endpoint::new(&to_receiver, &to_sender);
+            if let Some((byte, nth)) = receiver_drops {
+                channel = channel.dropping(byte, nth);
+            }
+            let mut received = Vec::new();
+            let mut write = |data: &[u8]| {
+                received.extend_from_slice(data);
+                Ok(())
+            };
+            assert_eq!(receive(&mut channel, &config, &mut write, &mut |_| {}), Ok(received.len() as u64));
+            assert_eq!(sender.join().unwrap(), Ok(data.len() as u64));
+            received
+        })
+    }
+
+    fn assert_padded(received: &[u8], data: &[u8], block_size: usize) {
+        assert_eq!(received.len(), data.len().div_ceil(block_size) * block_size);
+        assert_eq!(&received[..data.len()], data);
+        assert!(received[data.len()..].iter().all(|&byte| byte == SUB));
+    }
+
+    #[test]
+    fn xmodem_crc_round_trip() {
+        let data = pattern(300, 1);
+        assert_padded(&xmodem(&data, false, None), &data, BLOCK_SIZE);
+    }
+
+    #[test]
+    fn xmodem_1k_round_trip() {
+        let data = pattern(2500, 2);
+        assert_padded(&xmodem(&data, true, None), &data, BLOCK_SIZE_1K);
+    }
+
+    #[test]
+    fn xmodem_lost_ack_repeats_block_once() {
+        let data = pattern(300, 3);
+        assert_padded(&xmodem(&data, false, Some((ACK, 2))), &data, BLOCK_SIZE);
+    }
+
+    struct Source<'a> {
+        files: &'a [(&'a str, Vec<u8>)],
+        open: Option<(usize, usize)>,
+    }
+
+    impl BatchSource for Source<'_> {
+        fn open(&mut self, name: &str) -> Result<u64, Status> {
+            let index = self.files.iter().position(|(file, _)| *file == name).ok_or(Status::NOT_FOUND)?;
+            self.open = Some((index, 0));
+            Ok(self.files[index].1.len() as u64)
+        }
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Status> {
+            let (index, position) = self.open.as_mut().ok_or(Status::NOT_READY)?;
+            let rest = &self.files[*index].1[*position..];
+            let len = buffer.len().min(rest.len());
+            buffer[..len].copy_from_slice(&rest[..len]);
+            *position += len;
+            Ok(len)
+        }
+
+        fn close(&mut self) {
+            self.open = None;
+        }
+    }
+
+    #[derive(Default)]
+    struct Sink {
+        files: Vec<(String, Option<u64>, Vec<u8>)>,
+    }
+
+    impl BatchSink for Sink {
+        fn create(&mut self, name: &str, size: Option<u64>) -> Result<(), Status> {
+            self.files.push((name.to_string(), size, Vec::new()));
+            Ok(())
+        }
+
+        fn write(&mut self, data: &[u8]) -> Result<(), Status> {
+            self.files.last_mut().ok_or(Status::NOT_READY)?.2.extend_from_slice(data);
+            Ok(())
+        }
+
+        fn close(&mut self) {}
+    }
+
+    ///Sends files as a YMODEM batch from one end while the other receives it.
+    fn ymodem(files: &[(&str, Vec<u8>)], receiver_drops: Option<(u8, usize)>) -> Sink {
+        let (to_receiver, to_sender) = (Pipe::default(), Pipe::default());
+        let config = config(true);
+        let names: Vec<&str> = files.iter().map(|(name, _)| *name).collect();
+        thread::scope(|scope| {
+            let sender = scope.spawn(|| {
+                let mut channel = Endpoint::new(&to_sender, &to_receiver);
+                let mut source = Source { files, open: None };
+                send_batch_from(&mut channel, &config, &mut source, &names, &mut |_| {})
+            });
+            let mut channel = Endpoint::new(&to_receiver, &to_sender);
+            if let Some((byte, nth)) = receiver_drops {
+                channel = channel.dropping(byte, nth);
+            }
+            let mut sink = Sink::default();
+            assert_eq!(receive_batch_to(&mut channel, &config, &mut sink, &mut |_| {}), Ok(files.len()));
+            assert_eq!(sender.join().unwrap(), Ok(()));
+            sink
+        })
+    }
+
+    fn assert_batch(sink: &Sink, files: &[(&str, Vec<u8>)]) {
+        assert_eq!(sink.files.len(), files.len());
+        for ((name, size, data), (sent_name, sent_data)) in sink.files.iter().zip(files) {
+            assert_eq!(name, sent_name);
+            assert_eq!(*size, Some(sent_data.len() as u64));
+            assert_eq!(data, sent_data);
+        }
+    }
+
+    #[test]
+    fn ymodem_batch_round_trip() {
+        let files = [("a.txt", pattern(1500, 4)), ("dir\\b.bin", pattern(10, 5)), ("empty", Vec::new())];
+        assert_batch(&ymodem(&files, None), &files);
+    }
+
+    #[test]
+    fn ymodem_lost_header_ack() {
+        let files = [("a.txt", pattern(1500, 6)), ("b.txt", pattern(200, 7))];
+        // The first ACK of the receiver answers the header of a.txt.
+        assert_batch(&ymodem(&files, Some((ACK, 1))), &files);
+    }
+
+    #[test]
+    fn ymodem_lost_header_ack_and_request() {
+        let files = [("a.txt", pattern(1500, 8))];
+        let (to_receiver, to_sender) = (Pipe::default(), Pipe::default());
+        let config = config(true);
+        thread::scope(|scope| {
+            let sender = scope.spawn(|| {
+                let mut channel = Endpoint::new(&to_sender, &to_receiver);
+                let mut source = Source { files: &files, open: None };
+                send_batch_from(&mut channel, &config, &mut source, &["a.txt"], &mut |_| {})
+            });
+            // Both the ACK of the header and the data request that follows it are lost.
+            struct Lossy<'a>(Endpoint<'a>, usize);
+            impl Channel for Lossy<'_> {
+                fn write_all(&mut self, data: &[u8]) -> Result<(), Status> {
+                    self.1 += 1;
+                    // Writes: start request, ACK of the header, data request.
+                    if self.1 == 2 || self.1 == 3 {
+                        return Ok(());
+                    }
+                    self.0.write_all(data)
+                }
+
+                fn read_byte(&mut self, timeout_ms: u32) -> Result<Option<u8>, Status> {
+                    self.0.read_byte(timeout_ms)
+                }
+            }
+            let mut channel = Lossy(Endpoint::new(&to_receiver, &to_sender), 0);
+            let mut sink = Sink::default();
+            assert_eq!(receive_batch_to(&mut channel, &config, &mut sink, &mut |_| {}), Ok(1));
+            assert_eq!(sender.join().unwrap(), Ok(()));
+            assert_batch(&sink, &files);
+        });
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let mut data = [0u8; BLOCK_SIZE];
+        let len = write_header(&mut data, "dir\\file.bin", 1234);
+        assert_eq!(&data[..len], b"dir/file.bin\x001234");
+        let mut name = [0u8; BLOCK_SIZE];
+        let (name_len, size) = parse_header(&data, &mut name);
+        assert_eq!(&name[..name_len], b"dir\\file.bin");
+        assert_eq!(size, Some(1234));
+    }
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+    }
+}
@@ -0,0 +1,273 @@
+use core::fmt::{self, Write};
+
+use lib_efi::{
+    efi::{Handle, Status, SystemTable},
+    protocols::device_path::{
+        self, End, Hardware, Media, PROTOCOL_GUID, TYPE_ACPI, TYPE_END, TYPE_HARDWARE, TYPE_MEDIA, TYPE_MESSAGING,
+    },
+};
+
+use crate::services::handle::handle_protocol;
+
+const HEADER_SIZE: usize = 4;
+const ACPI_DP: u8 = 0x01;
+const MESSAGING_UART: u8 = 0x0E;
+
+///Compressed EISA ID of a PNP device, as stored in the HID of an ACPI device path node.
+pub const fn eisa_pnp_id(id: u16) -> u32 {
+    ((id as u32) << 16) | 0x41D0
+}
+
+///PNP0501: 16550A-compatible serial port.
+pub const PNP_SERIAL_PORT: u32 = eisa_pnp_id(0x0501);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Pci { function: u8, device: u8 },
+    Acpi { hid: u32, uid: u32 },
+    Uart { baud_rate: u64, data_bits: u8, parity: u8, stop_bits: u8 },
+    ///A file path, read with Node::file_path().
+    FilePath,
+    EndInstance,
+    Other,
+}
+
+///One node of a device path. data holds the node body, without the 4 byte header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Node<'a> {
+    pub node_type: u8,
+    pub sub_type: u8,
+    pub data: &'a [u8],
+}
+impl<'a> Node<'a> {
+    pub fn kind(&self) -> NodeKind {
+        match (self.node_type, self.sub_type, self.data.len()) {
+            (TYPE_HARDWARE, Hardware::SUBTYPE_PCI, 2) => NodeKind::Pci {
+                function: self.data[0],
+                device: self.data[1],
+            },
+            (TYPE_ACPI, ACPI_DP, 8) => NodeKind::Acpi {
+                hid: read_u32(self.data, 0),
+                uid: read_u32(self.data, 4),
+            },
+            (TYPE_MESSAGING, MESSAGING_UART, 15) => NodeKind::Uart {
+                baud_rate: read_u64(self.data, 4),
+                data_bits: self.data[12],
+                parity: self.data[13],
+                stop_bits: self.data[14],
+            },
+            (TYPE_MEDIA, Media::SUBTYPE_FILE_PATH, _) => NodeKind::FilePath,
+            (TYPE_END, End::SUBTYPE_INSTANCE, _) => NodeKind::EndInstance,
+            _ => NodeKind::Other,
+        }
+    }
+
+    ///The path of a file path node, stored as a null-terminated UCS-2 string.
+    pub fn file_path(&self) -> Option<impl Iterator<Item = char> + 'a> {
+        if self.kind() != NodeKind::FilePath {
+            return None;
+        }
+        let units = self
+            .data
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .take_while(|&unit| unit != 0);
+        Some(char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)))
+    }
+}
+
+///Iterator over the nodes of a device path, up to the End Entire Device Path node.
+pub struct Nodes<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for Nodes<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Node<'a>> {
+        if self.remaining.len() < HEADER_SIZE {
+            return None;
+        }
+        let length = u16::from_le_bytes([self.remaining[2], self.remaining[3]]) as usize;
+        if length < HEADER_SIZE || length > self.remaining.len() {
+            return None;
+        }
+        let node = Node {
+            node_type: self.remaining[0],
+            sub_type: self.remaining[1],
+            data: &self.remaining[HEADER_SIZE..length],
+        };
+        if node.node_type == TYPE_END && node.sub_type == End::SUBTYPE_ENTIRE {
+            self.remaining = &[];
+            return None;
+        }
+        self.remaining = &self.remaining[length..];
+        Some(node)
+    }
+}
+
+///Iterator over the instances of a multi-instance device path, such as the ConOut variable. Each
+///instance is yielded without its end node.
+pub struct Instances<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for Instances<'a> {
+    type Item = DevicePath<'a>;
+
+    fn next(&mut self) -> Option<DevicePath<'a>> {
+        let mut size = 0;
+        while self.remaining.len() >= size + HEADER_SIZE {
+            let header = &self.remaining[size..size + HEADER_SIZE];
+            let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+            if length < HEADER_SIZE || size + length > self.remaining.len() {
+                break;
+            }
+            if header[0] == TYPE_END {
+                let instance = DevicePath::from_bytes(&self.remaining[..size]);
+                self.remaining = match header[1] {
+                    End::SUBTYPE_INSTANCE => &self.remaining[size + length..],
+                    _ => &[],
+                };
+                return Some(instance);
+            }
+            size += length;
+        }
+        self.remaining = &[];
+        None
+    }
+}
+
+///A device path, borrowed from firmware or from a buffer (e.g. an NVRAM variable).
+#[derive(Clone, Copy)]
+pub struct DevicePath<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> DevicePath<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> DevicePath<'a> {
+        DevicePath { bytes }
+    }
+
+    ///Wraps a device path built by firmware, measuring it up to its End Entire Device Path node.
+    ///# Safety
+    ///protocol must point to a well-formed device path that outlives 'a.
+    pub unsafe fn from_raw(protocol: *const device_path::Protocol) -> DevicePath<'a> {
+        if protocol.is_null() {
+            return DevicePath { bytes: &[] };
+        }
+        let start = protocol as *const u8;
+        let mut size = 0;
+        loop {
+            let header = core::slice::from_raw_parts(start.add(size), HEADER_SIZE);
+            let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+            if length < HEADER_SIZE {
+                break;
+            }
+            size += length;
+            if header[0] == TYPE_END && header[1] == End::SUBTYPE_ENTIRE {
+                break;
+            }
+        }
+        DevicePath {
+            bytes: core::slice::from_raw_parts(start, size),
+        }
+    }
+
+    ///Returns the device path installed on a handle.
+    ///The path belongs to firmware and stays valid as long as the protocol is installed.
+    pub fn for_handle(st: *mut SystemTable, handle: Handle) -> Result<DevicePath<'static>, Status> {
+        let protocol = handle_protocol::<device_path::Protocol>(st, handle, PROTOCOL_GUID)?;
+        Ok(unsafe { DevicePath::from_raw(protocol) })
+    }
+
+    ///The raw device path, including its end node.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn nodes(&self) -> Nodes<'a> {
+        Nodes { remaining: self.bytes }
+    }
+
+    pub fn instances(&self) -> Instances<'a> {
+        Instances { remaining: self.bytes }
+    }
+
+    ///Returns true if one of the nodes is equal to node.
+    pub fn contains(&self, node: &Node) -> bool {
+        self.nodes().any(|n| n == *node)
+    }
+
+    ///Returns true if every node of prefix starts this path, in order.
+    pub fn starts_with(&self, prefix: &DevicePath) -> bool {
+        let mut nodes = self.nodes();
+        prefix.nodes().all(|node| nodes.next() == Some(node))
+    }
+}
+
+impl<'a> PartialEq for DevicePath<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes().eq(other.nodes())
+    }
+}
+
+impl<'a> fmt::Display for DevicePath<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut separator = "";
+        for node in self.nodes() {
+            if node.kind() == NodeKind::EndInstance {
+                f.write_str(",")?;
+                separator = "";
+                continue;
+            }
+            f.write_str(separator)?;
+            write!(f, "{}", node)?;
+            separator = "/";
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Node<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind() {
+            NodeKind::Pci { function, device } => write!(f, "Pci(0x{:X},0x{:X})", device, function),
+            NodeKind::Acpi { hid, uid } if hid == eisa_pnp_id(0x0A03) || hid == eisa_pnp_id(0x0A08) => {
+                write!(f, "PciRoot(0x{:X})", uid)
+            }
+            NodeKind::Acpi { hid, uid } if hid == PNP_SERIAL_PORT => write!(f, "Serial(0x{:X})", uid),
+            NodeKind::Acpi { hid, uid } if hid & 0xFFFF == 0x41D0 => {
+                write!(f, "Acpi(PNP{:04X},0x{:X})", hid >> 16, uid)
+            }
+            NodeKind::Acpi { hid, uid } => write!(f, "Acpi(0x{:08X},0x{:X})", hid, uid),
+            NodeKind::Uart { baud_rate, data_bits, parity, stop_bits } => {
+                let parity = match parity {
+                    1 => "N",
+                    2 => "E",
+                    3 => "O",
+                    4 => "M",
+                    5 => "S",
+                    _ => "D",
+                };
+                let stop_bits = match stop_bits {
+                    1 => "1",
+                    2 => "1.5",
+                    3 => "2",
+                    _ => "D",
+                };
+                write!(f, "Uart({},{},{},{})", baud_rate, data_bits, parity, stop_bits)
+            }
+            NodeKind::FilePath => self.file_path().into_iter().flatten().try_for_each(|c| f.write_char(c)),
+            NodeKind::EndInstance => f.write_str(","),
+            NodeKind::Other => write!(f, "Path({},{})", self.node_type, self.sub_type),
+        }
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    read_u32(data, offset) as u64 | (read_u32(data, offset + 4) as u64) << 32
+}
@@ -5,11 +5,24 @@ use lib_efi::{
     protocols::{device_path, loaded_image::{Protocol, PROTOCOL_GUID}}
 };
 
+use crate::{
+    protocols::device_path::DevicePath,
+    services::handle::{handle_protocol, open_protocol, HandleBuffer},
+};
+
+use super::load_options::Args;
+
+///Longest image file name compared to the first argument, in bytes.
+const MAX_NAME: usize = 256;
+
 
 pub struct LoadedImage {
     protocol: *mut Protocol,
+    ///The image handle the protocol was opened on, if known.
+    handle: Option<Handle>,
 }
 impl LoadedImage {
+    ///Returns the first Loaded Image protocol found, which is not necessarily the one of the running image: use for_image() for that.
     pub fn new(st: *mut SystemTable) -> Result<LoadedImage, Status> {
         let mut protocol: *mut Protocol = core::ptr::null_mut();
         let mut guid = PROTOCOL_GUID;
@@ -25,11 +38,44 @@ impl LoadedImage {
         if status == Status::SUCCESS {
             Ok(LoadedImage {
                 protocol,
+                handle: None,
             })
         } else {
             Err(status)
         }
     }
+
+    ///Opens the protocol of the running image, given the image handle passed to its entry point.
+    pub fn for_image(st: *mut SystemTable, image_handle: Handle) -> Result<LoadedImage, Status> {
+        let protocol = open_protocol::<Protocol>(st, image_handle, PROTOCOL_GUID, image_handle)?;
+        Ok(LoadedImage {
+            protocol,
+            handle: Some(image_handle),
+        })
+    }
+
+    ///Opens the protocol of any loaded image.
+    pub fn for_handle(st: *mut SystemTable, handle: Handle) -> Result<LoadedImage, Status> {
+        let protocol = handle_protocol::<Protocol>(st, handle, PROTOCOL_GUID)?;
+        Ok(LoadedImage {
+            protocol,
+            handle: Some(handle),
+        })
+    }
+
+    ///Lists every image loaded in the system, drivers and applications, in handle database order.
+    pub fn all(st: *mut SystemTable) -> Result<LoadedImages, Status> {
+        Ok(LoadedImages {
+            st,
+            handles: HandleBuffer::by_protocol(st, PROTOCOL_GUID)?,
+        })
+    }
+
+    ///The image handle, unless the protocol was found by new().
+    pub fn handle(&self) -> Option<Handle> {
+        self.handle
+    }
+
     ///Defines the revision of the EFI_LOADED_IMAGE_PROTOCOL structure. All future revisions will be backward compatible to the current revision.
     pub fn revision(&self) -> u32 {
         unsafe {(*self.protocol).revision}
@@ -52,6 +98,10 @@ impl LoadedImage {
     pub fn file_path(&self) -> *mut device_path::Protocol {
         unsafe {(*self.protocol).file_path}
     }
+    ///The file path of the image, such as \EFI\BOOT\BOOTX64.EFI. It is empty for images loaded from memory.
+    pub fn path(&self) -> DevicePath<'static> {
+        unsafe { DevicePath::from_raw(self.file_path()) }
+    }
     ///Reserved. DO NOT USE.
     pub fn reserved(&self) -> *mut c_void {
         unsafe {(*self.protocol).reserved}
@@ -66,6 +116,39 @@ impl LoadedImage {
     pub fn load_options(&self) -> *mut c_void {
         unsafe {(*self.protocol).load_options}
     }
+    ///The load options split into command line arguments. The UEFI Shell passes the command as typed, starting with the program name: it is skipped when it names this image.
+    pub fn args(&self) -> Result<Args, Status> {
+        let options = self.load_options();
+        let bytes: &[u8] = if options.is_null() {
+            &[]
+        } else {
+            unsafe { core::slice::from_raw_parts(options as *const u8, self.load_options_size() as usize) }
+        };
+        let mut args = Args::parse(self.system_table(), bytes)?;
+        if args.get(0).is_some_and(|first| self.is_named(first)) {
+            args.skip_first();
+        }
+        Ok(args)
+    }
+
+    ///Returns true if path names the file of this image, ignoring case and the .efi extension. Without a file path, any .efi file matches.
+    fn is_named(&self, path: &str) -> bool {
+        let file_path = self.path();
+        let mut name = [0u8; MAX_NAME];
+        let mut len = 0;
+        for c in file_path.nodes().filter_map(|node| node.file_path()).flatten() {
+            if c == '\\' || c == '/' {
+                len = 0;
+            } else if len + c.len_utf8() <= MAX_NAME {
+                len += c.encode_utf8(&mut name[len..]).len();
+            }
+        }
+        let image_name = core::str::from_utf8(&name[..len]).unwrap_or_default();
+        if image_name.is_empty() {
+            return program_name(path).len() < file_name(path).len();
+        }
+        program_name(path).eq_ignore_ascii_case(program_name(image_name))
+    }
 
     //location where image was loaded
     ///The base address at which the image was loaded.
@@ -76,6 +159,11 @@ impl LoadedImage {
     pub fn image_size(&self) -> u64 {
         unsafe {(*self.protocol).image_size}
     }
+    ///Returns true if address lies in the loaded image, to find which image code belongs to.
+    pub fn contains(&self, address: u64) -> bool {
+        let base = self.image_base() as u64;
+        address >= base && address - base < self.image_size()
+    }
     ///The memory type that the code sections were loaded as. Type EFI_MEMORY_TYPE is defined in Services — Boot Services.
     pub fn image_code_type(&self) -> MemoryType {
         unsafe {(*self.protocol).image_code_type}
@@ -95,3 +183,47 @@ impl LoadedImage {
     }
     
 }
+
+///Images found by LoadedImage::all().
+pub struct LoadedImages {
+    st: *mut SystemTable,
+    handles: HandleBuffer,
+}
+impl LoadedImages {
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Result<LoadedImage, Status> {
+        let handle = *self.handles.as_slice().get(index).ok_or(Status::NOT_FOUND)?;
+        LoadedImage::for_handle(self.st, handle)
+    }
+
+    ///Iterates over the images, skipping handles whose protocol could not be opened.
+    pub fn iter(&self) -> impl Iterator<Item = LoadedImage> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index).ok())
+    }
+
+    ///The image whose code or data lies at address.
+    pub fn containing(&self, address: u64) -> Option<LoadedImage> {
+        self.iter().find(|image| image.contains(address))
+    }
+}
+
+///The last component of a path, after the volume name and directories.
+fn file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/', ':']).next().unwrap_or(path)
+}
+
+///The file name of a path without its .efi extension, which the UEFI Shell lets users omit.
+fn program_name(path: &str) -> &str {
+    let name = file_name(path);
+    match name.len().checked_sub(4) {
+        Some(dot) if name.is_char_boundary(dot) && name[dot..].eq_ignore_ascii_case(".efi") => &name[..dot],
+        _ => name,
+    }
+}
@@ -0,0 +1,274 @@
+use core::str::FromStr;
+
+use lib_efi::efi::{Status, SystemTable};
+
+use crate::services::pool::PoolBuffer;
+
+///Character escaping the next one, as in the UEFI Shell. Backslashes separate path components.
+const ESCAPE: char = '^';
+const OPTION_PREFIX: &str = "--";
+
+///Command line arguments, split following the UEFI Shell rules: arguments are separated by spaces
+///and tabs, double quotes group words into one argument, and ^ escapes the next character.
+///
+///Options have the form --name or --name=value. Arguments following a lone -- are never options.
+pub struct Args {
+    ///Each argument followed by a null byte.
+    buffer: PoolBuffer<u8>,
+    len: usize,
+    ///Offset of the first argument, past the program name when it was skipped.
+    start: usize,
+}
+impl Args {
+    ///Splits a command line in UCS-2, as the UEFI Shell and most boot managers pass it, or in UTF-8.
+    ///Parsing stops at the first null character. Fails with INVALID_PARAMETER if bytes are neither.
+    pub fn parse(st: *mut SystemTable, bytes: &[u8]) -> Result<Args, Status> {
+        // Each UCS-2 unit takes up to 3 bytes in UTF-8, each argument a null byte more.
+        let mut buffer = PoolBuffer::new(st, 2 * bytes.len() + 1, 0u8)?;
+        let len = if is_ucs2(bytes) {
+            let units = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+            let chars = char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER));
+            split(chars, &mut buffer)
+        } else {
+            let text = core::str::from_utf8(bytes).map_err(|_| Status::INVALID_PARAMETER)?;
+            split(text.chars(), &mut buffer)
+        };
+        Ok(Args { buffer, len, start: 0 })
+    }
+
+    fn all(&self) -> &str {
+        // Only whole characters were written.
+        core::str::from_utf8(&self.buffer[self.start..self.len]).unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.all().split_terminator('\0')
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.len
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.iter().nth(index)
+    }
+
+    ///Drops the first argument, the program name when the UEFI Shell started the image.
+    pub fn skip_first(&mut self) {
+        let first = self.iter().next().map(|first| first.len() + 1);
+        self.start += first.unwrap_or(0);
+    }
+
+    ///The options, as (name, value) pairs without the leading --.
+    pub fn options(&self) -> impl Iterator<Item = (&str, Option<&str>)> + '_ {
+        options(self.iter())
+    }
+
+    ///The arguments which are not options.
+    pub fn positional(&self) -> impl Iterator<Item = &str> + '_ {
+        positional(self.iter())
+    }
+
+    ///Returns true if --name is given, with or without a value.
+    pub fn flag(&self, name: &str) -> bool {
+        self.options().any(|(option, _)| option == name)
+    }
+
+    ///The value of the last --name=value option. A bare --name has an empty value.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options()
+            .filter(|&(option, _)| option == name)
+            .last()
+            .map(|(_, value)| value.unwrap_or(""))
+    }
+
+    ///Parses the value of --name=value. Fails with INVALID_PARAMETER if it does not parse.
+    pub fn parse_value<T: FromStr>(&self, name: &str) -> Result<Option<T>, Status> {
+        match self.value(name) {
+            Some(value) => value.parse().map(Some).map_err(|_| Status::INVALID_PARAMETER),
+            None => Ok(None),
+        }
+    }
+
+    ///The first option whose name is not in known, to report it as unsupported.
+    pub fn unknown<'a>(&'a self, known: &[&str]) -> Option<&'a str> {
+        self.options().map(|(name, _)| name).find(|name| !known.contains(name))
+    }
+}
+
+///Args::options() of any list of arguments.
+fn options<'a>(args: impl Iterator<Item = &'a str>) -> impl Iterator<Item = (&'a str, Option<&'a str>)> {
+    args.take_while(|&arg| arg != OPTION_PREFIX)
+        .filter_map(|arg| arg.strip_prefix(OPTION_PREFIX))
+        .map(|option| match option.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (option, None),
+        })
+}
+
+///Args::positional() of any list of arguments.
+fn positional<'a>(args: impl Iterator<Item = &'a str>) -> impl Iterator<Item = &'a str> {
+    let mut options_ended = false;
+    args.filter(move |&arg| {
+        if options_ended {
+            return true;
+        }
+        if arg == OPTION_PREFIX {
+            options_ended = true;
+            return false;
+        }
+        !arg.starts_with(OPTION_PREFIX)
+    })
+}
+
+///UCS-2 text of Latin characters has a null high byte in most units, which UTF-8 text never has.
+fn is_ucs2(bytes: &[u8]) -> bool {
+    if bytes.len() % 2 != 0 {
+        return false;
+    }
+    let units = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|&unit| unit != 0);
+    let (mut count, mut latin) = (0, 0);
+    for unit in units {
+        count += 1;
+        if unit < 0x100 {
+            latin += 1;
+        }
+    }
+    count == 0 || 2 * latin >= count
+}
+
+///Writes each argument of chars followed by a null byte to buffer, which must hold twice as many
+///bytes as the text plus one. Returns the bytes written.
+fn split(chars: impl Iterator<Item = char>, buffer: &mut [u8]) -> usize {
+    let mut len = 0;
+    let (mut in_argument, mut quoted, mut escaped) = (false, false, false);
+    for c in chars.take_while(|&c| c != '\0') {
+        if escaped {
+            escaped = false;
+        } else if c == ESCAPE {
+            escaped = true;
+            in_argument = true;
+            continue;
+        } else if c == '"' {
+            quoted = !quoted;
+            in_argument = true;
+            continue;
+        } else if (c == ' ' || c == '\t') && !quoted {
+            if in_argument {
+                buffer[len] = 0;
+                len += 1;
+                in_argument = false;
+            }
+            continue;
+        }
+        in_argument = true;
+        len += c.encode_utf8(&mut buffer[len..]).len();
+    }
+    if in_argument {
+        buffer[len] = 0;
+        len += 1;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    ///Splits text as Args::parse() does and returns the arguments.
+    fn args<'a>(text: &str, buffer: &'a mut [u8]) -> Vec<&'a str> {
+        let len = split(text.chars(), buffer);
+        core::str::from_utf8(&buffer[..len])
+            .unwrap()
+            .split_terminator('\0')
+            .collect()
+    }
+
+    fn ucs2(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn splits_on_spaces_and_tabs() {
+        let mut buffer = [0u8; 64];
+        assert_eq!(
+            args("  fs0:\\EFI\\tool.efi\t one  two ", &mut buffer),
+            ["fs0:\\EFI\\tool.efi", "one", "two"]
+        );
+        assert!(args(" \t ", &mut buffer).is_empty());
+    }
+
+    #[test]
+    fn quotes_group_words() {
+        let mut buffer = [0u8; 64];
+        assert_eq!(args("\"a b\" c\"d e\"f", &mut buffer), ["a b", "cd ef"]);
+        assert_eq!(args("x \"\" y \"\"", &mut buffer), ["x", "", "y", ""]);
+    }
+
+    #[test]
+    fn caret_escapes_next_character() {
+        let mut buffer = [0u8; 64];
+        assert_eq!(args("x^\"y ^^z a^ b ^", &mut buffer), ["x\"y", "^z", "a b", ""]);
+        assert_eq!(args("\"^\"quoted^\"\"", &mut buffer), ["\"quoted\""]);
+    }
+
+    #[test]
+    fn stops_at_null() {
+        let mut buffer = [0u8; 64];
+        assert_eq!(args("one two\0three", &mut buffer), ["one", "two"]);
+    }
+
+    #[test]
+    fn buffer_holds_multibyte_characters() {
+        let text = "\u{20ac}\u{20ac} \u{20ac}";
+        let mut buffer = [0u8; 2 * 2 * 4 + 1];
+        assert_eq!(args(text, &mut buffer), ["\u{20ac}\u{20ac}", "\u{20ac}"]);
+    }
+
+    #[test]
+    fn detects_ucs2() {
+        assert!(is_ucs2(&ucs2("fs0:\\EFI\\tool.efi --size=12")));
+        assert!(is_ucs2(&ucs2("caf\u{e9} \u{20ac}\0garbage")));
+        assert!(is_ucs2(&[]));
+        assert!(!is_ucs2(b"fs0:\\EFI\\tool.efi --size=12"));
+        assert!(!is_ucs2(b"odd"));
+        assert!(!is_ucs2("\u{4e2d}\u{6587}".as_bytes()));
+    }
+
+    #[test]
+    fn options_and_positional() {
+        let mut buffer = [0u8; 128];
+        let all = args(
+            "tool.efi --size=12 in --verbose --name= \"--x=a b\" out -- --not -",
+            &mut buffer,
+        );
+        let found: Vec<_> = options(all.iter().copied()).collect();
+        assert_eq!(
+            found,
+            [
+                ("size", Some("12")),
+                ("verbose", None),
+                ("name", Some("")),
+                ("x", Some("a b"))
+            ]
+        );
+        let rest: Vec<_> = positional(all.iter().copied()).collect();
+        assert_eq!(rest, ["tool.efi", "in", "out", "--not", "-"]);
+    }
+
+    #[test]
+    fn empty_argument_is_positional() {
+        let mut buffer = [0u8; 64];
+        let all = args("\"\" --a \"\"", &mut buffer);
+        assert_eq!(options(all.iter().copied()).count(), 1);
+        assert_eq!(positional(all.iter().copied()).collect::<Vec<_>>(), ["", ""]);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod efi_loaded_image_device_path;
-pub mod efi_loaded_image;
\ No newline at end of file
+pub mod efi_loaded_image;
+pub mod load_options;
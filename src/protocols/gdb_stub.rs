@@ -0,0 +1,614 @@
+use core::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    fmt::{self, Write},
+    ptr::addr_of_mut,
+};
+
+use lib_efi::{
+    efi::{Status, SystemTable},
+    protocols::debug_support::{
+        ExceptionType, Protocol, SystemContext, SystemContextAArch64, SystemContextX64,
+        EXCEPT_AARCH64_SYNCHRONOUS_EXCEPTIONS, EXCEPT_X64_BREAKPOINT, EXCEPT_X64_DEBUG, ISA_AARCH64, ISA_X64,
+        PROTOCOL_GUID,
+    },
+};
+
+use super::{
+    console::{
+        serial_io::{ControlBits, SerialIO},
+        xmodem::Channel,
+    },
+    efi_loaded_image::efi_loaded_image::LoadedImage,
+};
+
+const PACKET_SIZE: usize = 4096;
+const MAX_BREAKPOINTS: usize = 32;
+const SEND_RETRIES: u32 = 5;
+const ACK_TIMEOUT_MS: u32 = 1000;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const SIGTRAP: u8 = 5;
+const SIGSEGV: u8 = 11;
+
+const X64_INT3: u8 = 0xCC;
+const X64_TRAP_FLAG: u64 = 1 << 8;
+const AARCH64_BRK: u32 = 0xD420_0000;
+const AARCH64_BRK_MASK: u32 = 0xFFE0_001F;
+const AARCH64_SPSR_SS: u64 = 1 << 21;
+const AARCH64_EC_BRK: u64 = 0x3C;
+const AARCH64_EC_SOFTWARE_STEP_LOWER: u64 = 0x32;
+const AARCH64_EC_SOFTWARE_STEP: u64 = 0x33;
+
+#[derive(Clone, Copy)]
+struct Breakpoint {
+    address: u64,
+    saved: [u8; 4],
+}
+
+///Registers of the interrupted processor, in the layout of the firmware's ISA.
+enum Context<'a> {
+    X64(&'a mut SystemContextX64),
+    AArch64(&'a mut SystemContextAArch64),
+}
+impl Context<'_> {
+    ///Number of registers of the GDB 'g' packet.
+    fn register_count(&self) -> usize {
+        match self {
+            Context::X64(_) => 24,
+            Context::AArch64(_) => 34,
+        }
+    }
+
+    ///Bytes of register n, numbered like GDB's default target descriptions:
+    ///rax..r15, rip, eflags and the segments on x86_64; x0..x30, sp, pc and cpsr on aarch64.
+    fn register(&mut self, n: usize) -> Option<&mut [u8]> {
+        let (register, size): (*mut u64, usize) = match self {
+            Context::X64(c) => match n {
+                0 => (addr_of_mut!(c.rax), 8),
+                1 => (addr_of_mut!(c.rbx), 8),
+                2 => (addr_of_mut!(c.rcx), 8),
+                3 => (addr_of_mut!(c.rdx), 8),
+                4 => (addr_of_mut!(c.rsi), 8),
+                5 => (addr_of_mut!(c.rdi), 8),
+                6 => (addr_of_mut!(c.rbp), 8),
+                7 => (addr_of_mut!(c.rsp), 8),
+                8 => (addr_of_mut!(c.r8), 8),
+                9 => (addr_of_mut!(c.r9), 8),
+                10 => (addr_of_mut!(c.r10), 8),
+                11 => (addr_of_mut!(c.r11), 8),
+                12 => (addr_of_mut!(c.r12), 8),
+                13 => (addr_of_mut!(c.r13), 8),
+                14 => (addr_of_mut!(c.r14), 8),
+                15 => (addr_of_mut!(c.r15), 8),
+                16 => (addr_of_mut!(c.rip), 8),
+                17 => (addr_of_mut!(c.rflags), 4),
+                18 => (addr_of_mut!(c.cs), 4),
+                19 => (addr_of_mut!(c.ss), 4),
+                20 => (addr_of_mut!(c.ds), 4),
+                21 => (addr_of_mut!(c.es), 4),
+                22 => (addr_of_mut!(c.fs), 4),
+                23 => (addr_of_mut!(c.gs), 4),
+                _ => return None,
+            },
+            Context::AArch64(c) => match n {
+                // x0 to x28, fp, lr and sp are the first 32 fields of the context.
+                0..=31 => (unsafe { (addr_of_mut!(**c) as *mut u64).add(n) }, 8),
+                32 => (addr_of_mut!(c.elr), 8),
+                33 => (addr_of_mut!(c.spsr), 4),
+                _ => return None,
+            },
+        };
+        // Registers are little-endian: the first 4 bytes are the low half of a 64-bit field.
+        Some(unsafe { core::slice::from_raw_parts_mut(register as *mut u8, size) })
+    }
+
+    fn set_pc(&mut self, pc: u64) {
+        match self {
+            Context::X64(c) => c.rip = pc,
+            Context::AArch64(c) => c.elr = pc,
+        }
+    }
+
+    fn set_step(&mut self, step: bool) {
+        match self {
+            Context::X64(c) if step => c.rflags |= X64_TRAP_FLAG,
+            Context::X64(c) => c.rflags &= !X64_TRAP_FLAG,
+            Context::AArch64(c) => {
+                if step {
+                    c.spsr |= AARCH64_SPSR_SS;
+                } else {
+                    c.spsr &= !AARCH64_SPSR_SS;
+                }
+                set_hardware_step(step);
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn set_hardware_step(enable: bool) {
+    let mut mdscr: u64;
+    unsafe { core::arch::asm!("mrs {}, mdscr_el1", out(reg) mdscr) };
+    // SS (bit 0) enables software step, KDE (bit 13) debug exceptions at the current EL.
+    if enable {
+        mdscr |= 1 | 1 << 13;
+    } else {
+        mdscr &= !1;
+    }
+    unsafe { core::arch::asm!("msr mdscr_el1, {}", "isb", in(reg) mdscr) };
+}
+
+#[cfg(not(target_arch = "aarch64"))]
+fn set_hardware_step(_enable: bool) {}
+
+///Stops in the debugger, as if a breakpoint was hit. Before install(), the firmware's own handler runs.
+pub fn breakpoint() {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        core::arch::asm!("int3")
+    };
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("brk #0xf000")
+    };
+}
+
+///Reply being built, without the packet framing.
+struct Reply {
+    buffer: [u8; PACKET_SIZE],
+    len: usize,
+}
+impl Reply {
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn push_hex(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if self.len + 2 > PACKET_SIZE {
+                return;
+            }
+            self.buffer[self.len] = HEX_DIGITS[(byte >> 4) as usize];
+            self.buffer[self.len + 1] = HEX_DIGITS[(byte & 0xF) as usize];
+            self.len += 2;
+        }
+    }
+}
+
+impl Write for Reply {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > PACKET_SIZE {
+            return Err(fmt::Error);
+        }
+        self.buffer[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+enum Action {
+    Reply,
+    Resume { step: bool },
+}
+
+struct State {
+    serial: Option<SerialIO>,
+    debug_support: *mut Protocol,
+    offset: u64,
+    breakpoints: [Option<Breakpoint>; MAX_BREAKPOINTS],
+    reply: Reply,
+}
+
+struct Stub {
+    state: UnsafeCell<State>,
+    packet: UnsafeCell<[u8; PACKET_SIZE]>,
+}
+
+// Exception callbacks run on the stopped processor, one at a time.
+unsafe impl Sync for Stub {}
+
+static STUB: Stub = Stub {
+    state: UnsafeCell::new(State {
+        serial: None,
+        debug_support: core::ptr::null_mut(),
+        offset: 0,
+        breakpoints: [None; MAX_BREAKPOINTS],
+        reply: Reply {
+            buffer: [0; PACKET_SIZE],
+            len: 0,
+        },
+    }),
+    packet: UnsafeCell::new([0; PACKET_SIZE]),
+};
+
+///Installs the GDB remote serial protocol stub on the boot processor.
+///link_base is the address the symbol file was linked at: qOffsets reports image_base - link_base.
+///Breakpoints need writable code pages, and reading unmapped memory faults in the firmware.
+pub fn install(st: *mut SystemTable, serial: SerialIO, image: &LoadedImage, link_base: u64) -> Status {
+    let mut protocol: *mut Protocol = core::ptr::null_mut();
+    let mut guid = PROTOCOL_GUID;
+    let boot_services = unsafe { &mut *st }.boot_services;
+    let status = unsafe {
+        ((*boot_services).locate_protocol)(
+            &mut guid,
+            core::ptr::null_mut(),
+            &mut protocol as *mut *mut Protocol as *mut _,
+        )
+    };
+    if status != Status::SUCCESS {
+        return status;
+    }
+    let exceptions: &[ExceptionType] = match unsafe { (*protocol).isa } {
+        ISA_X64 => &[EXCEPT_X64_BREAKPOINT, EXCEPT_X64_DEBUG],
+        ISA_AARCH64 => &[EXCEPT_AARCH64_SYNCHRONOUS_EXCEPTIONS],
+        _ => return Status::UNSUPPORTED,
+    };
+
+    let state = unsafe { &mut *STUB.state.get() };
+    state.serial = Some(serial);
+    state.debug_support = protocol;
+    state.offset = (image.image_base() as u64).wrapping_sub(link_base);
+    for &exception in exceptions {
+        let status =
+            unsafe { ((*protocol).register_exception_callback)(protocol, 0, Some(on_exception), exception) };
+        if status != Status::SUCCESS {
+            return status;
+        }
+    }
+    Status::SUCCESS
+}
+
+///Stops in the debugger if GDB sent an interrupt (Ctrl-C). Call it from long-running loops.
+pub fn poll() {
+    let state = unsafe { &mut *STUB.state.get() };
+    let serial = match state.serial.as_mut() {
+        Some(serial) => serial,
+        None => return,
+    };
+    let pending = serial
+        .get_control()
+        .map(|control| !control.contains(ControlBits::INPUT_BUFFER_EMPTY))
+        .unwrap_or(false);
+    if pending && serial.read_byte(ACK_TIMEOUT_MS) == Ok(Some(0x03)) {
+        breakpoint();
+    }
+}
+
+extern "efiapi" fn on_exception(exception_type: ExceptionType, system_context: SystemContext) {
+    let state = unsafe { &mut *STUB.state.get() };
+    if state.debug_support.is_null() {
+        return;
+    }
+    let (mut context, signal) = match unsafe { (*state.debug_support).isa } {
+        ISA_X64 => {
+            let c = unsafe { &mut *system_context.system_context_x64 };
+            // int3 leaves rip after itself: rewind onto the breakpoints GDB inserted.
+            if exception_type == EXCEPT_X64_BREAKPOINT && state.find(c.rip.wrapping_sub(1)).is_some() {
+                c.rip -= 1;
+            }
+            (Context::X64(c), SIGTRAP)
+        }
+        ISA_AARCH64 => {
+            let c = unsafe { &mut *system_context.system_context_aarch64 };
+            let signal = match (c.esr >> 26) & 0x3F {
+                AARCH64_EC_BRK => {
+                    // Step over brk instructions compiled into the image, e.g. breakpoint().
+                    let instruction = unsafe { core::ptr::read_volatile(c.elr as *const u32) };
+                    if state.find(c.elr).is_none() && instruction & AARCH64_BRK_MASK == AARCH64_BRK {
+                        c.elr += 4;
+                    }
+                    SIGTRAP
+                }
+                AARCH64_EC_SOFTWARE_STEP | AARCH64_EC_SOFTWARE_STEP_LOWER => SIGTRAP,
+                _ => SIGSEGV,
+            };
+            (Context::AArch64(c), signal)
+        }
+        _ => return,
+    };
+    context.set_step(false);
+    state.session(&mut context, signal);
+}
+
+impl State {
+    fn find(&self, address: u64) -> Option<usize> {
+        self.breakpoints.iter().position(|b| matches!(b, Some(b) if b.address == address))
+    }
+
+    fn breakpoint_size(&self) -> usize {
+        match unsafe { (*self.debug_support).isa } {
+            ISA_AARCH64 => 4,
+            _ => 1,
+        }
+    }
+
+    fn invalidate(&self, address: u64, length: usize) {
+        unsafe {
+            ((*self.debug_support).invalidate_instruction_cache)(
+                self.debug_support,
+                0,
+                address as *mut c_void,
+                length as u64,
+            )
+        };
+    }
+
+    fn insert_breakpoint(&mut self, address: u64) -> bool {
+        if self.find(address).is_some() {
+            return true;
+        }
+        let slot = match self.breakpoints.iter().position(Option::is_none) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let size = self.breakpoint_size();
+        let mut saved = [0u8; 4];
+        let code = unsafe { core::slice::from_raw_parts_mut(address as *mut u8, size) };
+        saved[..size].copy_from_slice(code);
+        if size == 4 {
+            code.copy_from_slice(&AARCH64_BRK.to_le_bytes());
+        } else {
+            code[0] = X64_INT3;
+        }
+        self.invalidate(address, size);
+        self.breakpoints[slot] = Some(Breakpoint { address, saved });
+        true
+    }
+
+    fn remove_breakpoint(&mut self, address: u64) -> bool {
+        let slot = match self.find(address) {
+            Some(slot) => slot,
+            None => return false,
+        };
+        if let Some(breakpoint) = self.breakpoints[slot].take() {
+            let size = self.breakpoint_size();
+            unsafe { core::slice::from_raw_parts_mut(address as *mut u8, size) }
+                .copy_from_slice(&breakpoint.saved[..size]);
+            self.invalidate(address, size);
+        }
+        true
+    }
+
+    ///Talks to GDB until it resumes execution.
+    fn session(&mut self, context: &mut Context, signal: u8) {
+        self.reply.clear();
+        let _ = write!(self.reply, "S{:02x}", signal);
+        if self.send_reply().is_err() {
+            return;
+        }
+        // Kept out of the exception stack, like the rest of the state.
+        let packet = unsafe { &mut *STUB.packet.get() };
+        loop {
+            let len = match self.receive_packet(packet) {
+                Ok(len) => len,
+                Err(_) => return,
+            };
+            self.reply.clear();
+            let action = self.handle(context, &packet[..len]);
+            if let Action::Resume { step } = action {
+                context.set_step(step);
+                return;
+            }
+            if self.send_reply().is_err() {
+                return;
+            }
+        }
+    }
+
+    fn handle(&mut self, context: &mut Context, packet: &[u8]) -> Action {
+        let (command, arguments) = match packet.split_first() {
+            Some((&command, arguments)) => (command, arguments),
+            None => return Action::Reply,
+        };
+        match command {
+            b'?' => {
+                let _ = write!(self.reply, "S{:02x}", SIGTRAP);
+            }
+            b'g' => {
+                for n in 0..context.register_count() {
+                    if let Some(register) = context.register(n) {
+                        self.reply.push_hex(register);
+                    }
+                }
+            }
+            b'G' => {
+                let mut hex = arguments;
+                for n in 0..context.register_count() {
+                    if let Some(register) = context.register(n) {
+                        let size = (register.len() * 2).min(hex.len());
+                        decode_hex(&hex[..size], register);
+                        hex = &hex[size..];
+                    }
+                }
+                let _ = self.reply.write_str("OK");
+            }
+            b'p' => match parse_hex(arguments).and_then(|n| context.register(n as usize)) {
+                Some(register) => self.reply.push_hex(register),
+                None => {
+                    let _ = self.reply.write_str("E01");
+                }
+            },
+            b'P' => {
+                let mut parts = arguments.splitn(2, |&b| b == b'=');
+                let register = parts.next().and_then(parse_hex).and_then(|n| context.register(n as usize));
+                match (register, parts.next()) {
+                    (Some(register), Some(value)) => {
+                        decode_hex(value, register);
+                        let _ = self.reply.write_str("OK");
+                    }
+                    _ => {
+                        let _ = self.reply.write_str("E01");
+                    }
+                }
+            }
+            b'm' => match parse_address_length(arguments) {
+                Some((address, length)) => {
+                    let length = length.min(PACKET_SIZE / 2);
+                    let memory = unsafe { core::slice::from_raw_parts(address as *const u8, length) };
+                    self.reply.push_hex(memory);
+                }
+                None => {
+                    let _ = self.reply.write_str("E01");
+                }
+            },
+            b'M' => {
+                let mut parts = arguments.splitn(2, |&b| b == b':');
+                match (parts.next().and_then(parse_address_length), parts.next()) {
+                    (Some((address, length)), Some(data)) if data.len() >= length * 2 => {
+                        let memory = unsafe { core::slice::from_raw_parts_mut(address as *mut u8, length) };
+                        decode_hex(&data[..length * 2], memory);
+                        self.invalidate(address, length);
+                        let _ = self.reply.write_str("OK");
+                    }
+                    _ => {
+                        let _ = self.reply.write_str("E01");
+                    }
+                }
+            }
+            b'c' | b's' => {
+                if let Some(address) = parse_hex(arguments) {
+                    context.set_pc(address);
+                }
+                return Action::Resume { step: command == b's' };
+            }
+            b'Z' | b'z' => {
+                let mut parts = arguments.split(|&b| b == b',');
+                let kind = parts.next();
+                let address = parts.next().and_then(parse_hex);
+                // Only software breakpoints: hardware ones and watchpoints get an empty reply.
+                if let (Some(b"0"), Some(address)) = (kind, address) {
+                    let done = if command == b'Z' {
+                        self.insert_breakpoint(address)
+                    } else {
+                        self.remove_breakpoint(address)
+                    };
+                    let _ = self.reply.write_str(if done { "OK" } else { "E01" });
+                }
+            }
+            b'D' => {
+                for slot in 0..MAX_BREAKPOINTS {
+                    if let Some(breakpoint) = self.breakpoints[slot] {
+                        self.remove_breakpoint(breakpoint.address);
+                    }
+                }
+                let _ = self.reply.write_str("OK");
+                let _ = self.send_reply();
+                return Action::Resume { step: false };
+            }
+            b'k' => return Action::Resume { step: false },
+            b'H' => {
+                let _ = self.reply.write_str("OK");
+            }
+            b'q' if arguments.starts_with(b"Supported") => {
+                let _ = write!(self.reply, "PacketSize={:x}", PACKET_SIZE);
+            }
+            b'q' if arguments == b"Offsets" => {
+                let _ = write!(
+                    self.reply,
+                    "Text={:x};Data={:x};Bss={:x}",
+                    self.offset, self.offset, self.offset
+                );
+            }
+            b'q' if arguments == b"Attached" => {
+                let _ = self.reply.write_str("1");
+            }
+            b'q' if arguments == b"C" => {
+                let _ = self.reply.write_str("QC1");
+            }
+            // Unsupported packets get an empty reply.
+            _ => {}
+        }
+        Action::Reply
+    }
+
+    fn receive_packet(&mut self, packet: &mut [u8]) -> Result<usize, Status> {
+        let serial = self.serial.as_mut().ok_or(Status::NOT_READY)?;
+        loop {
+            // Acknowledgements and interrupts are meaningless while stopped.
+            while read_byte(serial)? != b'$' {}
+            let mut len = 0;
+            let mut checksum: u8 = 0;
+            let mut overflow = false;
+            loop {
+                let byte = read_byte(serial)?;
+                if byte == b'#' {
+                    break;
+                }
+                if len == PACKET_SIZE {
+                    overflow = true;
+                    continue;
+                }
+                packet[len] = byte;
+                checksum = checksum.wrapping_add(byte);
+                len += 1;
+            }
+            let high = hex_digit(read_byte(serial)?);
+            let low = hex_digit(read_byte(serial)?);
+            let received = high.zip(low).map(|(high, low)| high << 4 | low);
+            if !overflow && received == Some(checksum) {
+                serial.write_all(b"+")?;
+                return Ok(len);
+            }
+            serial.write_all(b"-")?;
+        }
+    }
+
+    fn send_reply(&mut self) -> Result<(), Status> {
+        let serial = self.serial.as_mut().ok_or(Status::NOT_READY)?;
+        let data = &self.reply.buffer[..self.reply.len];
+        let checksum = data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+        let trailer = [b'#', HEX_DIGITS[(checksum >> 4) as usize], HEX_DIGITS[(checksum & 0xF) as usize]];
+        for _ in 0..SEND_RETRIES {
+            serial.write_all(b"$")?;
+            serial.write_all(data)?;
+            serial.write_all(&trailer)?;
+            if serial.read_byte(ACK_TIMEOUT_MS)? == Some(b'+') {
+                return Ok(());
+            }
+        }
+        Err(Status::TIMEOUT)
+    }
+}
+
+fn read_byte(serial: &mut SerialIO) -> Result<u8, Status> {
+    loop {
+        if let Some(byte) = serial.read_byte(ACK_TIMEOUT_MS)? {
+            return Ok(byte);
+        }
+    }
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn parse_hex(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() || digits.len() > 16 {
+        return None;
+    }
+    digits.iter().try_fold(0u64, |value, &c| Some(value << 4 | hex_digit(c)? as u64))
+}
+
+fn parse_address_length(arguments: &[u8]) -> Option<(u64, usize)> {
+    let mut parts = arguments.splitn(2, |&b| b == b',');
+    let address = parse_hex(parts.next()?)?;
+    let length = parse_hex(parts.next()?)? as usize;
+    Some((address, length))
+}
+
+///Decodes pairs of hex digits into output, stopping at the shorter of the two.
+fn decode_hex(hex: &[u8], output: &mut [u8]) {
+    for (byte, pair) in output.iter_mut().zip(hex.chunks_exact(2)) {
+        if let (Some(high), Some(low)) = (hex_digit(pair[0]), hex_digit(pair[1])) {
+            *byte = high << 4 | low;
+        }
+    }
+}
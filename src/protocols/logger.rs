@@ -0,0 +1,367 @@
+use core::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    fmt::{self, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use lib_efi::efi::{Event, Status, SystemTable, EVT_SIGNAL_EXIT_BOOT_SERVICES, TPL_NOTIFY};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::{
+    protocols::{
+        console::{
+            serial_io::SerialIO,
+            text_output::{BackgroundColor, ForegroudColor, TextOutput},
+        },
+        media::file::File,
+    },
+    services::time::Clock,
+};
+
+///Size of the buffer holding the records logged before the file sink is attached.
+pub const HISTORY_SIZE: usize = 8192;
+const LINE_SIZE: usize = 256;
+///Level and length stored before each history entry.
+const ENTRY_HEADER_SIZE: usize = 3;
+
+///Fixed-capacity byte ring. When full, the oldest entries are dropped to make room.
+///Every entry is a level byte, the length of its line on 2 bytes, then the line: messages may
+///hold line breaks, so they cannot delimit entries.
+pub struct RingBuffer<const N: usize> {
+    bytes: [u8; N],
+    start: usize,
+    len: usize,
+}
+impl<const N: usize> RingBuffer<N> {
+    pub const fn new() -> RingBuffer<N> {
+        RingBuffer {
+            bytes: [0; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    fn push_entry(&mut self, level: Level, line: &[u8]) {
+        let size = line.len() + ENTRY_HEADER_SIZE;
+        if line.len() > u16::MAX as usize || size > N {
+            return;
+        }
+        while N - self.len < size {
+            self.drop_entry();
+        }
+        self.push(level as u8);
+        for byte in (line.len() as u16).to_le_bytes() {
+            self.push(byte);
+        }
+        for &byte in line {
+            self.push(byte);
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.bytes[(self.start + self.len) % N] = byte;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.bytes[self.start];
+        self.start = (self.start + 1) % N;
+        self.len -= 1;
+        Some(byte)
+    }
+
+    ///Removes the header of the oldest entry. Returns its level and length.
+    fn pop_header(&mut self) -> Option<(usize, usize)> {
+        let level = self.pop()? as usize;
+        let len = u16::from_le_bytes([self.pop()?, self.pop()?]) as usize;
+        Some((level, len.min(self.len)))
+    }
+
+    fn drop_entry(&mut self) {
+        if let Some((_, len)) = self.pop_header() {
+            self.start = (self.start + len) % N;
+            self.len -= len;
+        }
+    }
+
+    ///Removes the oldest entry, copying its line into line. Returns its level and length.
+    fn pop_entry(&mut self, line: &mut [u8]) -> Option<(usize, usize)> {
+        let (level, len) = self.pop_header()?;
+        let mut copied = 0;
+        for _ in 0..len {
+            let byte = self.pop()?;
+            if copied < line.len() {
+                line[copied] = byte;
+                copied += 1;
+            }
+        }
+        Some((level, copied))
+    }
+}
+
+impl<const N: usize> Default for RingBuffer<N> {
+    fn default() -> Self {
+        RingBuffer::new()
+    }
+}
+
+///A formatted record. Lines longer than the buffer are truncated.
+struct Line {
+    bytes: [u8; LINE_SIZE],
+    len: usize,
+}
+impl Line {
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.as_bytes()) {
+            Ok(s) => s,
+            Err(e) => unsafe { core::str::from_utf8_unchecked(&self.bytes[..e.valid_up_to()]) },
+        }
+    }
+}
+
+impl Write for Line {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Keep room for the "\r\n" ending.
+        let room = LINE_SIZE - 2 - self.len;
+        let mut n = s.len().min(room);
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+        self.bytes[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+struct Sink<T> {
+    output: T,
+    level: LevelFilter,
+}
+
+struct State {
+    clock: Option<Clock>,
+    console: Option<Sink<TextOutput>>,
+    serial: Option<Sink<SerialIO>>,
+    file: Option<Sink<File>>,
+    history: RingBuffer<HISTORY_SIZE>,
+}
+
+///log backend writing to the console, a serial port and a file, each with its own level filter.
+///Records logged before a file is attached are kept in memory and written to it once it is.
+///After ExitBootServices() the sinks are no longer used: records only go to the history.
+pub struct Logger {
+    busy: AtomicBool,
+    boot_services_active: AtomicBool,
+    state: UnsafeCell<State>,
+}
+
+// Firmware is single-threaded. Interrupting notify functions that log while a record is being
+// written find the logger busy and their record is dropped.
+unsafe impl Sync for Logger {}
+unsafe impl Send for Logger {}
+
+static LOGGER: Logger = Logger {
+    busy: AtomicBool::new(false),
+    boot_services_active: AtomicBool::new(true),
+    state: UnsafeCell::new(State {
+        clock: None,
+        console: None,
+        serial: None,
+        file: None,
+        history: RingBuffer::new(),
+    }),
+};
+
+extern "efiapi" fn on_exit_boot_services(_event: Event, _context: *mut c_void) {
+    LOGGER.boot_services_active.store(false, Ordering::SeqCst);
+}
+
+///Installs the logger as the log backend, with no sink attached yet.
+///max_level is the most verbose level recorded by any sink or by the history.
+pub fn init(st: *mut SystemTable, max_level: LevelFilter) -> Result<&'static Logger, Status> {
+    let boot_services = unsafe { (*st).boot_services };
+    let mut event: Event = core::ptr::null_mut();
+    let status = unsafe {
+        ((*boot_services).create_event)(
+            EVT_SIGNAL_EXIT_BOOT_SERVICES,
+            TPL_NOTIFY,
+            Some(on_exit_boot_services),
+            core::ptr::null_mut(),
+            &mut event,
+        )
+    };
+    if status != Status::SUCCESS {
+        return Err(status);
+    }
+    if log::set_logger(&LOGGER).is_err() {
+        unsafe { ((*boot_services).close_event)(event) };
+        return Err(Status::ALREADY_STARTED);
+    }
+    LOGGER.with_state(|state| state.clock = Some(Clock::new(st)));
+    log::set_max_level(max_level);
+    Ok(&LOGGER)
+}
+
+impl Logger {
+    ///Runs f with exclusive access to the state. Returns None if the logger is already busy.
+    fn with_state<R>(&self, f: impl FnOnce(&mut State) -> R) -> Option<R> {
+        if self.busy.swap(true, Ordering::Acquire) {
+            return None;
+        }
+        let result = f(unsafe { &mut *self.state.get() });
+        self.busy.store(false, Ordering::Release);
+        Some(result)
+    }
+
+    pub fn set_console(&self, output: TextOutput, level: LevelFilter) {
+        self.with_state(|state| state.console = Some(Sink { output, level }));
+    }
+
+    pub fn set_serial(&self, output: SerialIO, level: LevelFilter) {
+        self.with_state(|state| state.serial = Some(Sink { output, level }));
+    }
+
+    ///Attaches a file opened for writing. New records are appended to its end,
+    ///after the records of the history that pass level.
+    pub fn set_file(&self, output: File, level: LevelFilter) -> Status {
+        let status = output.set_position(u64::MAX);
+        if status != Status::SUCCESS {
+            return status;
+        }
+        self.with_state(|state| {
+            let mut line = [0u8; LINE_SIZE];
+            let mut status = Status::SUCCESS;
+            while let Some((entry_level, len)) = state.history.pop_entry(&mut line) {
+                if status == Status::SUCCESS && entry_level <= level as usize {
+                    status = output.write_all(&line[..len]).err().unwrap_or(Status::SUCCESS);
+                }
+            }
+            output.flush();
+            state.file = Some(Sink { output, level });
+            status
+        })
+        .unwrap_or(Status::NOT_READY)
+    }
+
+    ///Detaches the file sink, flushing and closing the file.
+    pub fn close_file(&self) {
+        self.with_state(|state| {
+            if let Some(sink) = state.file.take() {
+                sink.output.close();
+            }
+        });
+    }
+
+    pub fn set_console_level(&self, level: LevelFilter) {
+        self.with_state(|state| state.console.iter_mut().for_each(|sink| sink.level = level));
+    }
+
+    pub fn set_serial_level(&self, level: LevelFilter) {
+        self.with_state(|state| state.serial.iter_mut().for_each(|sink| sink.level = level));
+    }
+
+    pub fn set_file_level(&self, level: LevelFilter) {
+        self.with_state(|state| state.file.iter_mut().for_each(|sink| sink.level = level));
+    }
+
+    fn format(&self, state: &State, record: &Record, boot_services_active: bool) -> Line {
+        let mut line = Line {
+            bytes: [0; LINE_SIZE],
+            len: 0,
+        };
+        // GetTime() is a runtime service, but the clock is only trusted until the OS takes over.
+        if let Some(Ok(time)) = state.clock.as_ref().filter(|_| boot_services_active).map(Clock::get_time) {
+            let _ = write!(
+                line,
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02} ",
+                time.year, time.month, time.day, time.hour, time.minute, time.second
+            );
+        }
+        let _ = write!(line, "{:<5} {}: {}", record.level(), record.target(), record.args());
+        line.bytes[line.len] = b'\r';
+        line.bytes[line.len + 1] = b'\n';
+        line.len += 2;
+        line
+    }
+}
+
+fn color(level: Level) -> ForegroudColor {
+    match level {
+        Level::Error => ForegroudColor::LIGHTRED,
+        Level::Warn => ForegroudColor::YELLOW,
+        Level::Info => ForegroudColor::WHITE,
+        Level::Debug => ForegroudColor::LIGHTGRAY,
+        Level::Trace => ForegroudColor::DARKGRAY,
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let boot_services_active = self.boot_services_active.load(Ordering::SeqCst);
+        self.with_state(|state| {
+            let line = self.format(state, record, boot_services_active);
+            let level = record.level();
+            if !boot_services_active {
+                state.history.push_entry(level, line.as_bytes());
+                return;
+            }
+            if let Some(sink) = state.console.as_ref().filter(|sink| level <= sink.level) {
+                // Leave the colors of the application as they were.
+                let attribute = sink.output.attribute();
+                sink.output.set_attribute(color(level), BackgroundColor::Black);
+                sink.output.output_str(line.as_str());
+                sink.output.restore_attribute(attribute);
+            }
+            if let Some(sink) = state.serial.as_ref().filter(|sink| level <= sink.level) {
+                let _ = sink.output.write_all(line.as_bytes());
+            }
+            match state.file.as_ref() {
+                Some(sink) if level <= sink.level => {
+                    let _ = sink.output.write_all(line.as_bytes());
+                    sink.output.flush();
+                }
+                Some(_) => {}
+                None => state.history.push_entry(level, line.as_bytes()),
+            }
+        });
+    }
+
+    fn flush(&self) {
+        if !self.boot_services_active.load(Ordering::SeqCst) {
+            return;
+        }
+        self.with_state(|state| {
+            if let Some(sink) = state.file.as_ref() {
+                sink.output.flush();
+            }
+        });
+    }
+}
@@ -34,10 +34,13 @@ pub enum OpenMode {
     Read = 0x0000000000000001,
     Write = 0x0000000000000002,
     Create = 0x8000000000000000,
+    ReadWrite = 0x0000000000000003,
+    ReadWriteCreate = 0x8000000000000003,
 }
 
 #[repr(u64)]
 pub enum Attribute {
+    None = 0x0000000000000000,
     ReadOnly = 0x0000000000000001,
     Hidden = 0x0000000000000002,
     System = 0x0000000000000004,
@@ -164,13 +167,14 @@ impl File {
     ///If This is not a directory, the function reads the requested number of bytes from the file at the file’s current position and returns them in Buffer. If the read goes beyond the end of the file, the read length is truncated to the end of the file. The file’s current position is increased by the number of bytes returned.
 
     ///If This is a directory, the function reads the directory entry at the file’s current position and returns the entry in Buffer. If the Buffer is not large enough to hold the current directory entry, then EFI_BUFFER_TOO_SMALL is returned and the current file position is not updated. BufferSize is set to be the size of the buffer needed to read the entry. On success, the current position is updated to the next directory entry. If there are no more directory entries, the read returns a zero-length buffer. EFI_FILE_INFO is the structure returned as the directory entry.
-    pub fn read(&self, size: usize) -> Result<(*mut c_void, usize), Status> {
-        let buffer: *mut c_void = null_mut();
-        let mut buffer_size: usize = size;
-        let status = unsafe { ((*self.root).read)(self.root, &mut buffer_size, buffer) };
+    pub fn read(&self, buffer: &mut [u8]) -> Result<usize, Status> {
+        let mut buffer_size: usize = buffer.len();
+        let status = unsafe {
+            ((*self.root).read)(self.root, &mut buffer_size, buffer.as_mut_ptr() as *mut c_void)
+        };
 
         match status {
-            Status::SUCCESS => Ok((buffer, buffer_size)),
+            Status::SUCCESS => Ok(buffer_size),
             _ => Err(status),
         }
 
@@ -201,13 +205,13 @@ impl File {
             */
     }
     ///The Write() function writes the specified number of bytes to the file at the current file position. The current file position is advanced the actual number of bytes written, which is returned in BufferSize. Partial writes only occur when there has been a data error during the write attempt (such as “file space full”). The file is automatically grown to hold the data if required. Direct writes to opened directories are not supported.
-    pub fn write(&self, buffer: &mut [u8]) -> Result<usize, Status> {
+    pub fn write(&self, buffer: &[u8]) -> Result<usize, Status> {
         let mut buffer_size: usize = buffer.len();
         let status = unsafe {
             ((*self.root).write)(
                 self.root,
                 &mut buffer_size,
-                buffer.as_mut_ptr() as *mut c_void,
+                buffer.as_ptr() as *mut c_void,
             )
         };
 
@@ -249,6 +253,17 @@ impl File {
         The volume is full.
          */
     }
+    ///Writes all of bytes, repeating Write() after partial writes. Fails with DEVICE_ERROR if a write
+    ///makes no progress.
+    pub fn write_all(&self, mut bytes: &[u8]) -> Result<(), Status> {
+        while !bytes.is_empty() {
+            match self.write(bytes)? {
+                0 => return Err(Status::DEVICE_ERROR),
+                written => bytes = &bytes[written..],
+            }
+        }
+        Ok(())
+    }
     ///Returns the current file position.
     pub fn get_position(&self) -> Result<u64, Status> {
         let mut size: u64 = 0;
@@ -1,3 +1,6 @@
 pub mod console;
+pub mod device_path;
 pub mod efi_loaded_image;
+pub mod gdb_stub;
+pub mod logger;
 pub mod media;
\ No newline at end of file
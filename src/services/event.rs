@@ -0,0 +1,142 @@
+use core::{ffi::c_void, ptr::null_mut};
+
+use lib_efi::efi::{
+    BootServices, Event, Status, SystemTable, EVT_NOTIFY_SIGNAL, EVT_TIMER, TIMER_CANCEL, TIMER_PERIODIC,
+    TIMER_RELATIVE, TPL_CALLBACK,
+};
+
+use super::pool::PoolBuffer;
+
+///A timer event, closed on drop. Wait for it with wait_for_event().
+pub struct Timer {
+    boot_services: *mut BootServices,
+    event: Event,
+}
+impl Timer {
+    pub fn new(st: *mut SystemTable) -> Result<Timer, Status> {
+        let boot_services = unsafe { (*st).boot_services };
+        let mut event: Event = null_mut();
+        let status = unsafe { ((*boot_services).create_event)(EVT_TIMER, TPL_CALLBACK, None, null_mut(), &mut event) };
+        match status {
+            Status::SUCCESS => Ok(Timer { boot_services, event }),
+            _ => Err(status),
+        }
+    }
+
+    pub fn event(&self) -> Event {
+        self.event
+    }
+
+    ///Signals the event every period, in units of 100 ns (SetTimer(TimerPeriodic)).
+    pub fn set_periodic(&self, period: u64) -> Status {
+        unsafe { ((*self.boot_services).set_timer)(self.event, TIMER_PERIODIC, period) }
+    }
+
+    ///Signals the event once, after delay in units of 100 ns (SetTimer(TimerRelative)).
+    pub fn set_relative(&self, delay: u64) -> Status {
+        unsafe { ((*self.boot_services).set_timer)(self.event, TIMER_RELATIVE, delay) }
+    }
+
+    pub fn cancel(&self) -> Status {
+        unsafe { ((*self.boot_services).set_timer)(self.event, TIMER_CANCEL, 0) }
+    }
+
+    ///Returns true and clears the signal if the timer fired since the last wait or check (CheckEvent()).
+    pub fn check(&self) -> Result<bool, Status> {
+        check_event(self.boot_services, self.event)
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        unsafe { ((*self.boot_services).close_event)(self.event) };
+    }
+}
+
+extern "efiapi" fn count_tick(_event: Event, context: *mut c_void) {
+    let ticks = context as *mut u64;
+    unsafe { ticks.write_volatile(ticks.read_volatile() + 1) };
+}
+
+///A periodic timer counting how many times it fired, to measure elapsed time in units of its
+///period. Unlike Timer, it cannot be waited for. Closed on drop.
+pub struct TickCounter {
+    boot_services: *mut BootServices,
+    event: Event,
+    ///Incremented by count_tick() at TPL_CALLBACK.
+    ticks: PoolBuffer<u64>,
+}
+impl TickCounter {
+    pub fn new(st: *mut SystemTable) -> Result<TickCounter, Status> {
+        let boot_services = unsafe { (*st).boot_services };
+        // The notification function needs a count which does not move with the TickCounter.
+        let mut ticks = PoolBuffer::new(st, 1, 0u64)?;
+        let context = ticks.as_mut_ptr() as *mut c_void;
+        let mut event: Event = null_mut();
+        let status = unsafe {
+            ((*boot_services).create_event)(
+                EVT_TIMER | EVT_NOTIFY_SIGNAL,
+                TPL_CALLBACK,
+                Some(count_tick),
+                context,
+                &mut event,
+            )
+        };
+        match status {
+            Status::SUCCESS => Ok(TickCounter { boot_services, event, ticks }),
+            _ => Err(status),
+        }
+    }
+
+    ///Counts a tick every period, in units of 100 ns (SetTimer(TimerPeriodic)).
+    pub fn set_periodic(&self, period: u64) -> Status {
+        unsafe { ((*self.boot_services).set_timer)(self.event, TIMER_PERIODIC, period) }
+    }
+
+    pub fn cancel(&self) -> Status {
+        unsafe { ((*self.boot_services).set_timer)(self.event, TIMER_CANCEL, 0) }
+    }
+
+    ///Returns the ticks counted since the last call and starts counting again from 0.
+    pub fn take(&mut self) -> u64 {
+        let ticks = self.ticks.as_mut_ptr();
+        // Keep count_tick() from running between the read and the reset.
+        let tpl = unsafe { ((*self.boot_services).raise_tpl)(TPL_CALLBACK) };
+        let count = unsafe { ticks.read_volatile() };
+        unsafe { ticks.write_volatile(0) };
+        unsafe { ((*self.boot_services).restore_tpl)(tpl) };
+        count
+    }
+}
+
+impl Drop for TickCounter {
+    fn drop(&mut self) {
+        // Before ticks is freed.
+        unsafe { ((*self.boot_services).close_event)(self.event) };
+    }
+}
+
+fn check_event(boot_services: *mut BootServices, event: Event) -> Result<bool, Status> {
+    match unsafe { ((*boot_services).check_event)(event) } {
+        Status::SUCCESS => Ok(true),
+        Status::NOT_READY => Ok(false),
+        status => Err(status),
+    }
+}
+
+///Stops until one of events is signaled and returns its index (WaitForEvent()).
+pub fn wait_for_event(st: *mut SystemTable, events: &[Event]) -> Result<usize, Status> {
+    let boot_services = unsafe { (*st).boot_services };
+    let mut index = 0;
+    let status = unsafe { ((*boot_services).wait_for_event)(events.len(), events.as_ptr() as *mut Event, &mut index) };
+    match status {
+        Status::SUCCESS => Ok(index),
+        _ => Err(status),
+    }
+}
+
+///Returns true if event is signaled, clearing its signal (CheckEvent()). For the events of input
+///protocols, this does not consume the input.
+pub fn is_signaled(st: *mut SystemTable, event: Event) -> Result<bool, Status> {
+    check_event(unsafe { (*st).boot_services }, event)
+}
@@ -0,0 +1,94 @@
+use core::{ffi::c_void, ptr::null_mut};
+
+use lib_efi::efi::{BootServices, Guid, Handle, Status, SystemTable, BY_PROTOCOL, OPEN_PROTOCOL_GET_PROTOCOL};
+
+///Handles returned by LocateHandleBuffer(). The buffer is returned to the pool on drop.
+pub struct HandleBuffer {
+    boot_services: *mut BootServices,
+    handles: *mut Handle,
+    count: usize,
+}
+impl HandleBuffer {
+    ///Returns every handle that supports the protocol.
+    pub fn by_protocol(st: *mut SystemTable, protocol_guid: Guid) -> Result<HandleBuffer, Status> {
+        let boot_services = unsafe { (*st).boot_services };
+        let mut guid = protocol_guid;
+        let mut count: usize = 0;
+        let mut handles: *mut Handle = null_mut();
+        let status = unsafe {
+            ((*boot_services).locate_handle_buffer)(BY_PROTOCOL, &mut guid, null_mut(), &mut count, &mut handles)
+        };
+        match status {
+            Status::SUCCESS => Ok(HandleBuffer {
+                boot_services,
+                handles,
+                count,
+            }),
+            _ => Err(status),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[Handle] {
+        if self.handles.is_null() {
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(self.handles, self.count) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl Drop for HandleBuffer {
+    fn drop(&mut self) {
+        if !self.handles.is_null() {
+            unsafe { ((*self.boot_services).free_pool)(self.handles as *mut c_void) };
+        }
+    }
+}
+
+///Queries a handle to determine if it supports a protocol and returns the protocol interface (HandleProtocol()).
+pub fn handle_protocol<T>(st: *mut SystemTable, handle: Handle, protocol_guid: Guid) -> Result<*mut T, Status> {
+    let boot_services = unsafe { (*st).boot_services };
+    let mut guid = protocol_guid;
+    let mut interface: *mut c_void = null_mut();
+    let status = unsafe { ((*boot_services).handle_protocol)(handle, &mut guid, &mut interface) };
+    match status {
+        Status::SUCCESS if !interface.is_null() => Ok(interface as *mut T),
+        Status::SUCCESS => Err(Status::NOT_FOUND),
+        _ => Err(status),
+    }
+}
+
+///Returns the protocol interface installed on a handle, opened on behalf of agent, usually the
+///image handle of the caller (OpenProtocol() with GET_PROTOCOL). The interface needs no closing.
+pub fn open_protocol<T>(
+    st: *mut SystemTable,
+    handle: Handle,
+    protocol_guid: Guid,
+    agent: Handle,
+) -> Result<*mut T, Status> {
+    let boot_services = unsafe { (*st).boot_services };
+    let mut guid = protocol_guid;
+    let mut interface: *mut c_void = null_mut();
+    let status = unsafe {
+        ((*boot_services).open_protocol)(
+            handle,
+            &mut guid,
+            &mut interface,
+            agent,
+            null_mut(),
+            OPEN_PROTOCOL_GET_PROTOCOL,
+        )
+    };
+    match status {
+        Status::SUCCESS if !interface.is_null() => Ok(interface as *mut T),
+        Status::SUCCESS => Err(Status::NOT_FOUND),
+        _ => Err(status),
+    }
+}
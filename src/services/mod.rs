@@ -0,0 +1,5 @@
+pub mod event;
+pub mod handle;
+pub mod pool;
+pub mod time;
+pub mod variable;
@@ -0,0 +1,99 @@
+use core::{
+    convert::TryFrom,
+    ffi::c_void,
+    mem::{align_of, size_of},
+    ops::{Deref, DerefMut},
+    ptr::null_mut,
+};
+
+use lib_efi::efi::{BootServices, Status, SystemTable, LOADER_DATA};
+
+use crate::protocols::media::file::File;
+
+///A slice allocated from the UEFI pool (AllocatePool()), returned to it on drop.
+pub struct PoolBuffer<T: Copy> {
+    boot_services: *mut BootServices,
+    pointer: *mut T,
+    len: usize,
+}
+impl<T: Copy> PoolBuffer<T> {
+    ///Allocates len elements, all set to value.
+    pub fn new(st: *mut SystemTable, len: usize, value: T) -> Result<PoolBuffer<T>, Status> {
+        // Pool allocations are 8-byte aligned.
+        if align_of::<T>() > 8 {
+            return Err(Status::UNSUPPORTED);
+        }
+        let size = len.checked_mul(size_of::<T>()).ok_or(Status::BAD_BUFFER_SIZE)?;
+        let boot_services = unsafe { (*st).boot_services };
+        let mut buffer: *mut c_void = null_mut();
+        let status = unsafe { ((*boot_services).allocate_pool)(LOADER_DATA, size.max(1), &mut buffer) };
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        let pointer = buffer as *mut T;
+        for i in 0..len {
+            unsafe { pointer.add(i).write(value) };
+        }
+        Ok(PoolBuffer {
+            boot_services,
+            pointer,
+            len,
+        })
+    }
+}
+
+impl PoolBuffer<u8> {
+    ///Reads a whole file, from its start.
+    pub fn read_file(st: *mut SystemTable, file: &File) -> Result<PoolBuffer<u8>, Status> {
+        let status = file.set_position(u64::MAX);
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        let size = usize::try_from(file.get_position()?).map_err(|_| Status::OUT_OF_RESOURCES)?;
+        let status = file.set_position(0);
+        if status != Status::SUCCESS {
+            return Err(status);
+        }
+        let mut buffer = PoolBuffer::new(st, size, 0u8)?;
+        let mut filled = 0;
+        while filled < size {
+            match file.read(&mut buffer[filled..])? {
+                0 => return Err(Status::END_OF_FILE),
+                read => filled += read,
+            }
+        }
+        Ok(buffer)
+    }
+}
+
+impl<T: Copy> Deref for PoolBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.pointer, self.len) }
+    }
+}
+
+impl<T: Copy> DerefMut for PoolBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.pointer, self.len) }
+    }
+}
+
+impl<T: Copy> AsRef<[T]> for PoolBuffer<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Copy> AsMut<[T]> for PoolBuffer<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: Copy> Drop for PoolBuffer<T> {
+    fn drop(&mut self) {
+        unsafe { ((*self.boot_services).free_pool)(self.pointer as *mut c_void) };
+    }
+}
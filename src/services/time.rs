@@ -0,0 +1,33 @@
+use lib_efi::efi::{RuntimeServices, Status, SystemTable, Time, TimeCapabilities};
+
+pub struct Clock {
+    runtime_services: *mut RuntimeServices,
+}
+impl Clock {
+    pub fn new(st: *mut SystemTable) -> Clock {
+        Clock {
+            runtime_services: unsafe { (*st).runtime_services },
+        }
+    }
+
+    ///Returns the current time and date information (GetTime()).
+    pub fn get_time(&self) -> Result<Time, Status> {
+        let mut time: Time = unsafe { core::mem::zeroed() };
+        let status = unsafe { ((*self.runtime_services).get_time)(&mut time, core::ptr::null_mut()) };
+        match status {
+            Status::SUCCESS => Ok(time),
+            _ => Err(status),
+        }
+    }
+
+    ///Returns the resolution and accuracy of the real time clock.
+    pub fn capabilities(&self) -> Result<TimeCapabilities, Status> {
+        let mut time: Time = unsafe { core::mem::zeroed() };
+        let mut capabilities: TimeCapabilities = unsafe { core::mem::zeroed() };
+        let status = unsafe { ((*self.runtime_services).get_time)(&mut time, &mut capabilities) };
+        match status {
+            Status::SUCCESS => Ok(capabilities),
+            _ => Err(status),
+        }
+    }
+}
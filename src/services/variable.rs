@@ -0,0 +1,85 @@
+use core::ffi::c_void;
+
+use lib_efi::efi::{
+    Guid, RuntimeServices, Status, SystemTable, VARIABLE_BOOTSERVICE_ACCESS,
+    VARIABLE_NON_VOLATILE,
+};
+
+use crate::protocols::media::file::str_to_utf16;
+
+///Vendor GUID under which uefi_helper stores its own settings (calibration, layouts, modes...).
+pub const VENDOR_GUID: Guid = Guid::from_fields(
+    0x76a54fa3,
+    0x3aae,
+    0x45f8,
+    0x9b,
+    0xde,
+    &[0x5d, 0xee, 0x82, 0xe2, 0x78, 0xaf],
+);
+
+///EFI_GLOBAL_VARIABLE: vendor of the variables defined by the UEFI specification (ConOut, BootOrder...).
+pub const GLOBAL_VARIABLE_GUID: Guid = Guid::from_fields(
+    0x8be4df61,
+    0x93ca,
+    0x11d2,
+    0xaa,
+    0x0d,
+    &[0x00, 0xe0, 0x98, 0x03, 0x2b, 0x8c],
+);
+
+///Attributes used for settings that must survive a reboot but are only needed before ExitBootServices().
+pub const NON_VOLATILE_BOOT_ACCESS: u32 = VARIABLE_NON_VOLATILE | VARIABLE_BOOTSERVICE_ACCESS;
+
+pub struct Variable {
+    runtime_services: *mut RuntimeServices,
+}
+impl Variable {
+    pub fn new(st: *mut SystemTable) -> Variable {
+        Variable {
+            runtime_services: unsafe { (*st).runtime_services },
+        }
+    }
+
+    ///Returns the value of a variable.
+    ///On success, returns the number of bytes written into data and the attributes of the variable.
+    ///If data is too small, EFI_BUFFER_TOO_SMALL is returned and nothing is written.
+    pub fn get(&self, name: &str, vendor_guid: Guid, data: &mut [u8]) -> Result<(usize, u32), Status> {
+        let mut variable_name = str_to_utf16(name);
+        let mut guid = vendor_guid;
+        let mut attributes: u32 = 0;
+        let mut data_size = data.len();
+        let status = unsafe {
+            ((*self.runtime_services).get_variable)(
+                variable_name.as_mut_ptr(),
+                &mut guid,
+                &mut attributes,
+                &mut data_size,
+                data.as_mut_ptr() as *mut c_void,
+            )
+        };
+        match status {
+            Status::SUCCESS => Ok((data_size, attributes)),
+            _ => Err(status),
+        }
+    }
+
+    ///Sets the value of a variable.
+    pub fn set(&self, name: &str, vendor_guid: Guid, attributes: u32, data: &[u8]) -> Status {
+        let mut variable_name = str_to_utf16(name);
+        let mut guid = vendor_guid;
+        unsafe {
+            ((*self.runtime_services).set_variable)(
+                variable_name.as_mut_ptr(),
+                &mut guid,
+                attributes,
+                data.len(),
+                data.as_ptr() as *mut c_void,
+            )
+        }
+    }
+
+    ///Deletes a variable. A size of zero passed to SetVariable() causes the variable to be deleted.
+    pub fn delete(&self, name: &str, vendor_guid: Guid) -> Status {
+        self.set(name, vendor_guid, 0, &[])
+    }
+}